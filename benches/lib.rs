@@ -24,6 +24,12 @@ fn throws_up_ok() -> Result<&'static str, &'static str> {
     Ok(ok_msg)
 }
 
+#[inline(never)]
+fn throws_up_same_ok() -> Result<&'static str, &'static str> {
+    let ok_msg = test::black_box(up_same!(gives_throw_ok()));
+    Ok(ok_msg)
+}
+
 #[inline(never)]
 fn throws_throw_ok() -> Result<&'static str, &'static str> {
     let ok_msg = test::black_box(throw!(gives_ok()));
@@ -32,21 +38,26 @@ fn throws_throw_ok() -> Result<&'static str, &'static str> {
 
 #[inline(never)]
 fn throws_try_ok() -> StdResult<&'static str, &'static str> {
-    let ok_msg = test::black_box(try!(gives_ok()));
+    let ok_msg = test::black_box(gives_ok()?);
     Ok(ok_msg)
 }
 
 #[bench]
 fn bench_throw_ok_return(bench: &mut Bencher) {
-    bench.iter(|| throws_throw_ok())
+    bench.iter(throws_throw_ok)
 }
 
 #[bench]
 fn bench_up_ok_return(bench: &mut Bencher) {
-    bench.iter(|| throws_up_ok())
+    bench.iter(throws_up_ok)
+}
+
+#[bench]
+fn bench_up_same_ok_return(bench: &mut Bencher) {
+    bench.iter(throws_up_same_ok)
 }
 
 #[bench]
 fn bench_try_ok_return(bench: &mut Bencher) {
-    bench.iter(|| throws_try_ok())
+    bench.iter(throws_try_ok)
 }
@@ -0,0 +1,27 @@
+use std::fmt;
+
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+
+use crate::http::{problem_body, HttpStatus};
+use crate::Error;
+
+/// Responds with the status given by the inner error's `HttpStatus` implementation, and an
+/// `application/problem+json` body, as with the `axum` feature's `IntoResponse` implementation.
+/// This lets actix-web handlers return a `throw::Result` directly.
+///
+/// Only available with the `actix-web` feature.
+impl<E: fmt::Debug + fmt::Display + HttpStatus> ResponseError for Error<E> {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(HttpStatus::status(self)).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        self.report();
+        let body = problem_body(self, status.canonical_reason());
+        HttpResponse::build(status)
+            .content_type("application/problem+json")
+            .body(body.to_string())
+    }
+}
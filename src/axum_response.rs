@@ -0,0 +1,57 @@
+use std::fmt;
+
+use ::http::header::{HeaderValue, CONTENT_TYPE};
+use ::http::StatusCode;
+use axum_core::body::Body;
+use axum_core::response::{IntoResponse, Response};
+
+use crate::http::{problem_body, HttpStatus};
+use crate::Error;
+
+/// Responds with the status given by the inner error's `HttpStatus` implementation, and an
+/// `application/problem+json` body, including the error's context and points only if enabled with
+/// `throw::http::set_expose_details()`. The error is written with `Error::report()` first.
+///
+/// Only available with the `axum` feature.
+///
+/// ```
+/// # #[cfg(feature = "axum")]
+/// # mod example {
+/// use throw::http::HttpStatus;
+///
+/// #[derive(Debug)]
+/// struct NotFound;
+///
+/// impl std::fmt::Display for NotFound {
+///     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         f.write_str("user not found")
+///     }
+/// }
+///
+/// impl HttpStatus for NotFound {
+///     fn status(&self) -> u16 {
+///         404
+///     }
+/// }
+///
+/// async fn get_user() -> throw::Result<String, NotFound> {
+///     throw::throw_new!(NotFound);
+/// }
+/// # }
+/// # fn main() {}
+/// ```
+impl<E: fmt::Display + HttpStatus> IntoResponse for Error<E> {
+    fn into_response(self) -> Response {
+        let status =
+            StatusCode::from_u16(self.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        self.report();
+        let body = problem_body(&self, status.canonical_reason());
+        let mut response = Response::new(Body::from(body.to_string()));
+        *response.status_mut() = status;
+        response.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        );
+        response
+    }
+}
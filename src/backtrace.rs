@@ -0,0 +1,61 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{Error, Metadata};
+
+static FORCE: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether `Error::new()` always captures a backtrace, rather than only when
+/// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set. Disabled by default.
+///
+/// Capturing a backtrace is slow, so this is best left off for errors which are expected to
+/// happen often.
+///
+/// Only available with the `backtrace` feature.
+pub fn set_force_backtrace(force: bool) {
+    FORCE.store(force, Ordering::Relaxed);
+}
+
+/// Captures a backtrace into an error's metadata, if backtraces are enabled.
+pub(crate) fn capture_into(metadata: &mut Option<Box<Metadata>>) {
+    let backtrace = if FORCE.load(Ordering::Relaxed) {
+        Backtrace::force_capture()
+    } else {
+        Backtrace::capture()
+    };
+    if backtrace.status() == BacktraceStatus::Captured {
+        metadata.get_or_insert_with(Default::default).backtrace = Some(backtrace);
+    }
+}
+
+impl<E> Error<E> {
+    /// Gets the backtrace captured when this error was created by `Error::new()`, or by a macro
+    /// such as `throw!()` or `throw_new!()`.
+    ///
+    /// Backtraces are only captured when `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set, or when
+    /// forced with `throw::set_force_backtrace()`. It can be rendered alongside the error's points
+    /// with `Error::display_with_backtrace()`.
+    ///
+    /// Only available with the `backtrace` feature.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate throw;
+    /// fn fails() -> throw::Result<(), &'static str> {
+    ///     throw_new!("failed");
+    /// }
+    ///
+    /// # #[cfg(feature = "backtrace")]
+    /// fn main() {
+    ///     throw::set_force_backtrace(true);
+    ///     let error = fails().unwrap_err();
+    ///     let backtrace = error.backtrace().unwrap();
+    ///     println!("{}", error.display_with_backtrace(backtrace));
+    /// }
+    /// # #[cfg(not(feature = "backtrace"))]
+    /// # fn main() {}
+    /// ```
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.metadata.as_ref().and_then(|m| m.backtrace.as_ref())
+    }
+}
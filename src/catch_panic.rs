@@ -0,0 +1,112 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+use std::panic::{self, UnwindSafe};
+use std::sync::Once;
+
+use crate::{Error, ErrorPoint, Result};
+
+static INSTALL_LOCATION_HOOK: Once = Once::new();
+
+thread_local! {
+    // Location of the last panic on this thread, recorded by the hook installed by `__catch_panic`.
+    static LAST_LOCATION: RefCell<Option<PanicLocation>> = const { RefCell::new(None) };
+}
+
+/// Error produced by `catch_panic!()` when the closure it runs panics.
+///
+/// Only available with the `std` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicError {
+    message: String,
+    location: Option<PanicLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PanicLocation {
+    file: String,
+    line: u32,
+    column: u32,
+}
+
+impl PanicError {
+    /// The panic's message, or `"Box<dyn Any>"` if its payload wasn't a `&str` or `String`.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The file, line and column the panic happened at, if they were recorded.
+    #[inline]
+    pub fn location(&self) -> Option<(&str, u32, u32)> {
+        self.location
+            .as_ref()
+            .map(|location| (&*location.file, location.line, location.column))
+    }
+}
+
+impl fmt::Display for PanicError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("panicked")?;
+        if let Some(location) = &self.location {
+            write!(
+                fmt,
+                " at {}:{}:{}",
+                location.file, location.line, location.column
+            )?;
+        }
+        write!(fmt, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for PanicError {}
+
+/// Creates a `PanicError` from a panic's payload, without a location.
+#[cfg(feature = "tokio")]
+pub(crate) fn from_payload(payload: &(dyn Any + Send)) -> PanicError {
+    PanicError {
+        message: payload_message(payload),
+        location: None,
+    }
+}
+
+/// The message of a panic, or `"Box<dyn Any>"` if its payload wasn't a `&str` or `String`.
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| String::from(*message))
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("Box<dyn Any>"))
+}
+
+#[doc(hidden)]
+pub fn __catch_panic<T, F: FnOnce() -> T + UnwindSafe>(
+    f: F,
+    point: ErrorPoint,
+) -> Result<T, PanicError> {
+    INSTALL_LOCATION_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let location = info.location().map(|location| PanicLocation {
+                file: location.file().to_owned(),
+                line: location.line(),
+                column: location.column(),
+            });
+            LAST_LOCATION.with(|last| *last.borrow_mut() = location);
+            previous(info);
+        }));
+    });
+    LAST_LOCATION.with(|last| last.borrow_mut().take());
+    let payload = match panic::catch_unwind(f) {
+        Ok(value) => return Ok(value),
+        Err(payload) => payload,
+    };
+    let message = payload_message(&*payload);
+    let location = LAST_LOCATION.with(|last| last.borrow_mut().take());
+    let mut error = Error::new(PanicError { message, location });
+    error.__push_point(point);
+    if crate::hook::is_active() {
+        crate::hook::run(&error, Some(&error.error().to_string()));
+    }
+    Err(error)
+}
@@ -0,0 +1,170 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic::Location;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::Error;
+
+/// Writes crash dumps: JSON files describing an error or panic which ended the program, for users
+/// to attach to bug reports.
+///
+/// Each dump is written to a new file named `{name}-crash-{timestamp}-{pid}.json`, in the system's
+/// temporary directory unless another is set with `dir()`. It holds:
+///
+/// - `error`: the error, in the format of its Serialize implementation, or `panic`: the panic's
+///   `message` and `location`
+/// - `crate`: the `name` and `version` given to `CrashDump::new()`
+/// - `os`: the operating system's `name` and `arch`itecture
+/// - `program`: the path the program was run as
+/// - `timestamp_us`: when the dump was written, in microseconds since the Unix epoch
+///
+/// `crash_dump!()` creates a `CrashDump` with the name and version of the crate it's used in.
+///
+/// Only available with the `crash-dump` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// # #[cfg(feature = "crash-dump")]
+/// # fn main() -> std::process::ExitCode {
+/// fn run() -> throw::Result<(), std::num::ParseIntError> {
+///     let value: u32 = throw!("42".parse());
+///     println!("value: {}", value);
+///     Ok(())
+/// }
+///
+/// let dumps = crash_dump!();
+/// dumps.install_panic_hook();
+/// dumps.report(run)
+/// # }
+/// # #[cfg(not(feature = "crash-dump"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct CrashDump {
+    name: Cow<'static, str>,
+    version: Cow<'static, str>,
+    dir: PathBuf,
+}
+
+impl CrashDump {
+    /// Creates a writer of crash dumps for the given crate name and version, writing to the
+    /// system's temporary directory.
+    pub fn new<N, V>(name: N, version: V) -> CrashDump
+    where
+        N: Into<Cow<'static, str>>,
+        V: Into<Cow<'static, str>>,
+    {
+        CrashDump {
+            name: name.into(),
+            version: version.into(),
+            dir: std::env::temp_dir(),
+        }
+    }
+
+    /// Sets the directory crash dumps are written to.
+    pub fn dir<P: Into<PathBuf>>(mut self, dir: P) -> CrashDump {
+        self.dir = dir.into();
+        self
+    }
+
+    /// Writes a crash dump for an error, returning the path it was written to.
+    pub fn write<E: fmt::Display>(&self, error: &Error<E>) -> io::Result<PathBuf> {
+        self.write_dump("error", error.to_json_value())
+    }
+
+    /// Writes a crash dump for a panic, returning the path it was written to.
+    fn write_panic(&self, message: &str, location: Option<&Location>) -> io::Result<PathBuf> {
+        let panic = json!({
+            "message": message,
+            "location": location.map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        });
+        self.write_dump("panic", panic)
+    }
+
+    fn write_dump(&self, key: &str, value: Value) -> io::Result<PathBuf> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut dump = json!({
+            "crate": { "name": self.name, "version": self.version },
+            "os": { "name": std::env::consts::OS, "arch": std::env::consts::ARCH },
+            "program": std::env::args_os().next().map(|p| p.to_string_lossy().into_owned()),
+            "timestamp_us": crate::serde_impls::timestamp_micros(now),
+        });
+        dump[key] = value;
+        let path = self.dir.join(format!(
+            "{}-crash-{}-{}.json",
+            self.name,
+            now.as_secs(),
+            std::process::id()
+        ));
+        fs::write(&path, serde_json::to_vec_pretty(&dump)?)?;
+        Ok(path)
+    }
+
+    /// Prints where a crash dump was written, or why it couldn't be.
+    fn announce(&self, written: io::Result<PathBuf>) {
+        match written {
+            Ok(path) => eprintln!(
+                "\n{} crashed. A report was written to {}; please attach it to a bug report.",
+                self.name,
+                path.display()
+            ),
+            Err(e) => eprintln!(
+                "\n{} crashed, and writing a report failed: {}",
+                self.name, e
+            ),
+        }
+    }
+
+    /// Installs a panic hook which runs the previously installed hook, then writes a crash dump
+    /// for the panic and prints its path to stderr.
+    pub fn install_panic_hook(&self) {
+        let dumps = self.clone();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            dumps.announce(dumps.write_panic(message, info.location()));
+        }));
+    }
+
+    /// Runs a fallible `main` function like `throw::report()`, also writing a crash dump for any
+    /// error it returns and printing the dump's path to stderr.
+    pub fn report<E, F>(&self, f: F) -> ExitCode
+    where
+        E: fmt::Display,
+        F: FnOnce() -> crate::Result<(), E>,
+    {
+        let result = f();
+        let error = result.as_ref().err().map(Error::to_json_value);
+        let code = crate::report(|| result);
+        if let Some(error) = error {
+            self.announce(self.write_dump("error", error));
+        }
+        code
+    }
+}
+
+/// Creates a `throw::CrashDump` with the name and version of the crate it's used in, from
+/// `CARGO_PKG_NAME` and `CARGO_PKG_VERSION`.
+///
+/// Only available with the `crash-dump` feature.
+#[macro_export]
+macro_rules! crash_dump {
+    () => {
+        $crate::CrashDump::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    };
+}
@@ -0,0 +1,135 @@
+use core::fmt;
+
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+use serde::de::{Deserialize, Deserializer};
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+use serde::ser::{Serialize, Serializer};
+
+use crate::Error;
+
+/// How severe an error is, as reported by `Error::severity()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// An error which stops the current operation.
+    Error,
+    /// A problem which doesn't stop the current operation, but should still be looked at.
+    Warning,
+    /// Information which might help, but doesn't indicate a problem by itself.
+    Advice,
+}
+
+impl Severity {
+    /// Gets this severity's lowercase name, as used in serialized errors.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Advice => "advice",
+        }
+    }
+
+    /// The label starting an error's Display output.
+    pub(crate) fn label(&self) -> &'static str {
+        match *self {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+            Severity::Advice => "Advice",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+impl Serialize for Severity {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+impl<'de> Deserialize<'de> for Severity {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Severity, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SeverityVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for SeverityVisitor {
+            type Value = Severity;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("\"error\", \"warning\" or \"advice\"")
+            }
+
+            fn visit_str<Err>(self, v: &str) -> core::result::Result<Severity, Err>
+            where
+                Err: serde::de::Error,
+            {
+                match v {
+                    "error" => Ok(Severity::Error),
+                    "warning" => Ok(Severity::Warning),
+                    "advice" => Ok(Severity::Advice),
+                    _ => Err(Err::unknown_variant(v, &["error", "warning", "advice"])),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(SeverityVisitor)
+    }
+}
+
+/// Diagnostic metadata for an error type: an error code, a severity, and a help message.
+///
+/// `Error::diagnose()` copies this metadata onto a `throw::Error`, where it's shown in Display
+/// output and included when serializing. All methods default to `None`.
+///
+/// With the `macros` feature, this can be implemented with `#[derive(ThrowDiagnostic)]`, taking
+/// metadata from `#[diagnostic(code = "E1042", severity = "warning", help = "...")]` attributes
+/// on the type, and on each variant of an enum to override the type's metadata.
+pub trait ThrowDiagnostic {
+    /// A short code identifying this kind of error, such as `"E1042"`.
+    fn code(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// How severe this error is.
+    fn severity(&self) -> Option<Severity> {
+        None
+    }
+
+    /// A message suggesting how to fix this error.
+    fn help(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl<E: ThrowDiagnostic> Error<E> {
+    /// Copies the code, severity and help message from the inner error onto this error, keeping
+    /// any which have already been set.
+    pub fn diagnose(mut self) -> Error<E> {
+        if self.code().is_none() {
+            if let Some(code) = self.error().code() {
+                self.set_code(code);
+            }
+        }
+        if self.severity().is_none() {
+            if let Some(severity) = self.error().severity() {
+                self.set_severity(severity);
+            }
+        }
+        if self.help().is_none() {
+            if let Some(help) = self.error().help() {
+                self.set_help(help);
+            }
+        }
+        self
+    }
+}
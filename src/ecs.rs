@@ -0,0 +1,81 @@
+use std::any::type_name;
+use std::fmt::{self, Write};
+
+use serde_json::{json, Map, Value};
+
+use crate::Error;
+
+impl<E: fmt::Display> Error<E> {
+    /// Maps this error to Elastic Common Schema fields, to be merged into a log event.
+    ///
+    /// The result has these fields, with any which don't apply left out:
+    ///
+    /// - `error.message`: the inner error's Display output
+    /// - `error.type`: the inner error's type name
+    /// - `error.code`: the error's code, from `Error::code()`
+    /// - `error.stack_trace`: one line for each point, most recent first, as in Display output
+    /// - `log.level`: the error's severity, from `Error::severity()`
+    /// - `labels.*`: each key/value pair of context, with values formatted as strings
+    ///
+    /// Only available with the `ecs` feature.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate throw;
+    /// # #[cfg(feature = "ecs")]
+    /// # fn main() {
+    /// fn check(id: u32) -> throw::Result<(), &'static str> {
+    ///     throw_new!("not found", "id" => id);
+    /// }
+    ///
+    /// let ecs = check(4).unwrap_err().to_ecs_value();
+    /// assert_eq!(ecs["error"]["message"], "not found");
+    /// assert_eq!(ecs["labels"]["id"], "4");
+    /// # }
+    /// # #[cfg(not(feature = "ecs"))]
+    /// # fn main() {}
+    /// ```
+    pub fn to_ecs_value(&self) -> Value {
+        let mut error = Map::new();
+        error.insert("message".to_owned(), Value::from(self.error().to_string()));
+        error.insert("type".to_owned(), Value::from(type_name::<E>()));
+        if let Some(code) = self.code() {
+            error.insert("code".to_owned(), Value::from(code));
+        }
+        if !self.points().is_empty() {
+            let mut stack_trace = String::new();
+            for point in self.points().iter().rev() {
+                if !stack_trace.is_empty() {
+                    stack_trace.push('\n');
+                }
+                let _ = write!(
+                    stack_trace,
+                    "at {}:{} in {} ({})",
+                    point.line(),
+                    point.column(),
+                    point.module_path(),
+                    crate::fmt::remap_global(point.file())
+                );
+                if let Some(note) = point.note() {
+                    let _ = write!(stack_trace, " \u{2014} {}", note);
+                }
+            }
+            error.insert("stack_trace".to_owned(), Value::from(stack_trace));
+        }
+
+        let mut ecs = Map::new();
+        ecs.insert("error".to_owned(), Value::Object(error));
+        if let Some(severity) = self.severity() {
+            ecs.insert("log".to_owned(), json!({ "level": severity.as_str() }));
+        }
+        if !self.get_context().is_empty() {
+            let labels = self
+                .get_context()
+                .iter()
+                .map(|kv| (kv.key().to_owned(), Value::from(kv.value().to_string())))
+                .collect();
+            ecs.insert("labels".to_owned(), Value::Object(labels));
+        }
+        Value::Object(ecs)
+    }
+}
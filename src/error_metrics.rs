@@ -0,0 +1,20 @@
+use crate::{Error, ErrorPoint};
+
+/// Name of the counter incremented for each point recorded.
+const ERRORS_TOTAL: &str = "throw.errors_total";
+
+/// Increments `throw.errors_total` for a point being added to an error, labelled with the
+/// point's module and the error's code, or an empty code for errors without one.
+pub(crate) fn count_point<E>(error: &Error<E>, point: &ErrorPoint) {
+    let code = error
+        .metadata
+        .as_ref()
+        .and_then(|m| m.code.clone())
+        .unwrap_or_default();
+    metrics::counter!(
+        ERRORS_TOTAL,
+        "module" => point.module_path().to_owned(),
+        "code" => code,
+    )
+    .increment(1);
+}
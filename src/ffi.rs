@@ -0,0 +1,185 @@
+//! A flattened, `#[repr(C)]` view of errors, for handing them to C callers.
+//!
+//! `FfiError::new()` flattens an error into memory owned by the returned `FfiError`, which C code
+//! can read directly, and which is freed by passing it to `throw_ffi_error_free()`. All strings
+//! are NUL-terminated UTF-8, with any NUL characters in them replaced by U+FFFD.
+//!
+//! A matching C declaration is:
+//!
+//! ```c
+//! typedef struct {
+//!     uint32_t line;
+//!     uint32_t column;
+//!     const char *module_path;
+//!     const char *file;
+//!     const char *note; /* NULL if the point has no note */
+//! } throw_ffi_point;
+//!
+//! typedef struct {
+//!     const char *message;
+//!     const char *display;
+//!     const throw_ffi_point *points;
+//!     size_t points_len;
+//!     const char *const *context_keys;
+//!     const char *const *context_values;
+//!     size_t context_len;
+//! } throw_ffi_error;
+//!
+//! void throw_ffi_error_free(throw_ffi_error *error);
+//! ```
+//!
+//! Only available with the `ffi` feature, so that `throw_ffi_error_free()` is only exported from
+//! crates which ask for it.
+
+use std::ffi::CString;
+use std::fmt;
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::{Error, ErrorPoint};
+
+/// An ErrorPoint, flattened for C callers. Part of an `FfiError`, which owns its strings.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FfiPoint {
+    /// The line the point is at.
+    pub line: u32,
+    /// The column the point is at.
+    pub column: u32,
+    /// The module the point is in.
+    pub module_path: *const c_char,
+    /// The file the point is in.
+    pub file: *const c_char,
+    /// The note given for the point, or null if it has none.
+    pub note: *const c_char,
+}
+
+/// An error, flattened for C callers.
+///
+/// Created with `FfiError::new()`, and freed with `throw_ffi_error_free()`. Every pointer stays
+/// valid until the error is freed.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FfiError {
+    /// The inner error's Display output.
+    pub message: *const c_char,
+    /// The error's full Display output, including its context and points.
+    pub display: *const c_char,
+    /// The error's points, with the point it was created at first.
+    pub points: *const FfiPoint,
+    /// The number of points.
+    pub points_len: usize,
+    /// The keys of the error's context, in the order they were added.
+    pub context_keys: *const *const c_char,
+    /// The Display output of each value of the error's context, matching `context_keys`.
+    pub context_values: *const *const c_char,
+    /// The number of key/value pairs of context.
+    pub context_len: usize,
+}
+
+fn c_string(string: &str) -> *const c_char {
+    let string =
+        CString::new(string.replace('\0', "\u{fffd}")).expect("NUL characters were replaced");
+    string.into_raw()
+}
+
+/// Frees a string created by `c_string()`.
+///
+/// # Safety
+///
+/// `string` must be null, or have been created by `c_string()` and not freed yet.
+unsafe fn free_c_string(string: *const c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string as *mut c_char));
+    }
+}
+
+/// Leaks a boxed slice, returning a pointer to its start. Freed with `free_slice()`.
+fn into_raw_slice<T>(values: Vec<T>) -> *const T {
+    Box::into_raw(values.into_boxed_slice()) as *const T
+}
+
+/// Frees a slice leaked by `into_raw_slice()`, returning its values.
+///
+/// # Safety
+///
+/// `values` and `len` must have come from `into_raw_slice()`, and not been freed yet.
+unsafe fn free_slice<T>(values: *const T, len: usize) -> Box<[T]> {
+    Box::from_raw(ptr::slice_from_raw_parts_mut(values as *mut T, len))
+}
+
+impl FfiPoint {
+    fn new(point: &ErrorPoint) -> FfiPoint {
+        FfiPoint {
+            line: point.line(),
+            column: point.column(),
+            module_path: c_string(point.module_path()),
+            file: c_string(point.file()),
+            note: point.note().map_or(ptr::null(), c_string),
+        }
+    }
+}
+
+impl FfiError {
+    /// Flattens an error, returning a pointer which must be freed with `throw_ffi_error_free()`.
+    ///
+    /// ```
+    /// use std::ffi::CStr;
+    ///
+    /// let mut error = throw::Error::new("failed");
+    /// error.add_context("attempt", 3);
+    ///
+    /// let ffi = throw::ffi::FfiError::new(&error);
+    /// unsafe {
+    ///     assert_eq!(CStr::from_ptr((*ffi).message).to_str(), Ok("failed"));
+    ///     assert_eq!((*ffi).context_len, 1);
+    ///     assert_eq!(CStr::from_ptr(*(*ffi).context_values).to_str(), Ok("3"));
+    ///     throw::ffi::throw_ffi_error_free(ffi);
+    /// }
+    /// ```
+    pub fn new<E: fmt::Display>(error: &Error<E>) -> *mut FfiError {
+        let points: Vec<FfiPoint> = error.points().iter().map(FfiPoint::new).collect();
+        let context = error.get_context();
+        let keys: Vec<*const c_char> = context.iter().map(|kv| c_string(kv.key())).collect();
+        let values: Vec<*const c_char> = context
+            .iter()
+            .map(|kv| c_string(&kv.value().to_string()))
+            .collect();
+        Box::into_raw(Box::new(FfiError {
+            message: c_string(&error.error().to_string()),
+            display: c_string(&error.to_string()),
+            points_len: points.len(),
+            points: into_raw_slice(points),
+            context_len: context.len(),
+            context_keys: into_raw_slice(keys),
+            context_values: into_raw_slice(values),
+        }))
+    }
+}
+
+/// Frees an error created by `FfiError::new()`. Does nothing if `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null, or have been returned by `FfiError::new()` and not freed yet. None of
+/// its pointers may be used after it is freed.
+#[no_mangle]
+pub unsafe extern "C" fn throw_ffi_error_free(error: *mut FfiError) {
+    if error.is_null() {
+        return;
+    }
+    let error = Box::from_raw(error);
+    free_c_string(error.message);
+    free_c_string(error.display);
+    for point in free_slice(error.points, error.points_len).iter() {
+        free_c_string(point.module_path);
+        free_c_string(point.file);
+        free_c_string(point.note);
+    }
+    for &key in free_slice(error.context_keys, error.context_len).iter() {
+        free_c_string(key);
+    }
+    for &value in free_slice(error.context_values, error.context_len).iter() {
+        free_c_string(value);
+    }
+}
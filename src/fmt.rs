@@ -0,0 +1,1647 @@
+//! Rendering errors in styles other than their Display output.
+//!
+//! `Error`'s Display implementation always renders the full trace. An `ErrorFormatter` renders
+//! errors in another style, with `Error::display_with()`:
+//!
+//! ```
+//! # extern crate throw;
+//! use throw::fmt::{ErrorFormatter, Style};
+//!
+//! let mut error = throw::Error::new("failed");
+//! error.add_context("attempt", 3);
+//!
+//! let compact = ErrorFormatter::new().style(Style::Compact);
+//! assert_eq!(error.display_with(&compact).to_string(), "Error: failed");
+//!
+//! let custom = ErrorFormatter::new().style(Style::custom(|error, f| {
+//!     write!(f, "{} ({} context values)", error.error(), error.get_context().len())
+//! }));
+//! assert_eq!(error.display_with(&custom).to_string(), "failed (1 context values)");
+//! ```
+
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use crate::{Error, ErrorPoint, KvPair, Severity};
+
+/// Renders errors in a chosen `Style`, with `Error::display_with()`.
+#[derive(Debug)]
+pub struct ErrorFormatter {
+    style: Style,
+    order: Option<PointOrder>,
+    indent: Cow<'static, str>,
+    point_prefix: Cow<'static, str>,
+    label: Option<Cow<'static, str>>,
+    module_prefix: Cow<'static, str>,
+    help_label: Cow<'static, str>,
+    omitted_value: Cow<'static, str>,
+    oldest_first_header: Cow<'static, str>,
+    #[cfg(feature = "std")]
+    backtrace_header: Cow<'static, str>,
+    frames_omitted: Plural,
+    bytes_truncated: Plural,
+    line_prefix: Cow<'static, str>,
+    terse: bool,
+    context: ContextVisibility,
+    group_context: bool,
+    max_value_len: Option<usize>,
+    wrap_width: Option<usize>,
+    path_remap: Option<PathRemap>,
+    snapshot: bool,
+    #[cfg(feature = "std")]
+    source: bool,
+    #[cfg(feature = "color")]
+    color: ColorChoice,
+}
+
+impl Default for ErrorFormatter {
+    fn default() -> ErrorFormatter {
+        ErrorFormatter {
+            style: Style::default(),
+            order: None,
+            indent: Cow::Borrowed("\t"),
+            point_prefix: Cow::Borrowed("at"),
+            label: None,
+            module_prefix: Cow::Borrowed("in"),
+            help_label: Cow::Borrowed("help"),
+            omitted_value: Cow::Borrowed("<omitted>"),
+            oldest_first_header: Cow::Borrowed(OLDEST_FIRST_HEADER),
+            #[cfg(feature = "std")]
+            backtrace_header: Cow::Borrowed("backtrace and points, innermost first:"),
+            frames_omitted: Plural::new(
+                "\u{2026} {} frame omitted \u{2026}",
+                "\u{2026} {} frames omitted \u{2026}",
+            ),
+            bytes_truncated: BYTES_TRUNCATED,
+            line_prefix: Cow::Borrowed(""),
+            terse: false,
+            context: ContextVisibility::Shown,
+            group_context: false,
+            max_value_len: None,
+            wrap_width: None,
+            path_remap: None,
+            snapshot: false,
+            #[cfg(feature = "std")]
+            source: false,
+            #[cfg(feature = "color")]
+            color: ColorChoice::default(),
+        }
+    }
+}
+
+impl ErrorFormatter {
+    /// Creates a formatter rendering errors the same way as their Display implementation.
+    pub fn new() -> ErrorFormatter {
+        ErrorFormatter::default()
+    }
+
+    /// Sets the style errors are rendered in.
+    pub fn style(mut self, style: Style) -> ErrorFormatter {
+        self.style = style;
+        self
+    }
+
+    /// Sets the order points are rendered in by the `Full` and `Pretty` styles. Without this, the
+    /// order set with `set_default_point_order()` is used.
+    ///
+    /// When points are rendered oldest first, a `points, oldest first:` line comes before them.
+    pub fn point_order(mut self, order: PointOrder) -> ErrorFormatter {
+        self.order = Some(order);
+        self
+    }
+
+    fn order(&self) -> PointOrder {
+        self.order.unwrap_or_else(default_point_order)
+    }
+
+    /// Sets the string each line after the first is indented with by the `Full` style. This
+    /// defaults to a tab. Lines continuing a multi-line message or context value are indented by
+    /// two more spaces.
+    pub fn indent<S: Into<Cow<'static, str>>>(mut self, indent: S) -> ErrorFormatter {
+        self.indent = indent.into();
+        self
+    }
+
+    /// Sets the word each point's line starts with, instead of `at`.
+    pub fn point_prefix<S: Into<Cow<'static, str>>>(mut self, prefix: S) -> ErrorFormatter {
+        self.point_prefix = prefix.into();
+        self
+    }
+
+    /// Sets the label the first line starts with, instead of `Error`, `Warning` or `Advice`.
+    pub fn label<S: Into<Cow<'static, str>>>(mut self, label: S) -> ErrorFormatter {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the word between each point's position and its module, instead of `in`.
+    pub fn module_prefix<S: Into<Cow<'static, str>>>(mut self, prefix: S) -> ErrorFormatter {
+        self.module_prefix = prefix.into();
+        self
+    }
+
+    /// Sets the label the error's help message starts with, instead of `help`.
+    pub fn help_label<S: Into<Cow<'static, str>>>(mut self, label: S) -> ErrorFormatter {
+        self.help_label = label.into();
+        self
+    }
+
+    /// Sets what is rendered in place of context values with `ContextVisibility::Omitted`,
+    /// instead of `<omitted>`.
+    pub fn omitted_value<S: Into<Cow<'static, str>>>(mut self, value: S) -> ErrorFormatter {
+        self.omitted_value = value.into();
+        self
+    }
+
+    /// Sets the line rendered before points rendered oldest first, instead of
+    /// `points, oldest first:`. Errors rendered with a different header can't be parsed back with
+    /// `parse_display()`.
+    pub fn oldest_first_header<S: Into<Cow<'static, str>>>(mut self, header: S) -> ErrorFormatter {
+        self.oldest_first_header = header.into();
+        self
+    }
+
+    /// Sets the line rendered before the frames and points by `Error::display_with_backtrace()`,
+    /// instead of `backtrace and points, innermost first:`.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn backtrace_header<S: Into<Cow<'static, str>>>(mut self, header: S) -> ErrorFormatter {
+        self.backtrace_header = header.into();
+        self
+    }
+
+    /// Sets the line rendered in place of the points left out by `terse()`, with `{}` replaced by
+    /// how many there are. `one` is used when a single point is left out, and `other` otherwise.
+    pub fn frames_omitted<O, T>(mut self, one: O, other: T) -> ErrorFormatter
+    where
+        O: Into<Cow<'static, str>>,
+        T: Into<Cow<'static, str>>,
+    {
+        self.frames_omitted = Plural::new(one, other);
+        self
+    }
+
+    /// Sets what follows context values cut short by `max_value_len()`, with `{}` replaced by how
+    /// many bytes were left out. `one` is used when a single byte is left out, and `other`
+    /// otherwise.
+    pub fn bytes_truncated<O, T>(mut self, one: O, other: T) -> ErrorFormatter
+    where
+        O: Into<Cow<'static, str>>,
+        T: Into<Cow<'static, str>>,
+    {
+        self.bytes_truncated = Plural::new(one, other);
+        self
+    }
+
+    /// Sets a prefix written at the start of every line, such as `"// "` to render errors as code
+    /// comments. It's used by every style, as well as by `{:#}` and `display_with_backtrace()` when
+    /// this is the global formatter.
+    pub fn line_prefix<S: Into<Cow<'static, str>>>(mut self, prefix: S) -> ErrorFormatter {
+        self.line_prefix = prefix.into();
+        self
+    }
+
+    /// Writes what `write` renders with the line prefix at the start of each line.
+    pub(crate) fn write_prefixed<F>(&self, f: &mut dyn fmt::Write, write: F) -> fmt::Result
+    where
+        F: FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    {
+        let prefix = &*self.line_prefix;
+        f.write_str(prefix)?;
+        write(&mut Indented { f, prefix })
+    }
+
+    /// Sets whether the `Full` and `Pretty` styles only render the points where the error was
+    /// first and last thrown, replacing any between them with a `… N frames omitted …` line.
+    pub fn terse(mut self, terse: bool) -> ErrorFormatter {
+        self.terse = terse;
+        self
+    }
+
+    /// Sets whether the `Full` and `Pretty` styles render the error's context values, such as to
+    /// show errors to end users without revealing internal details.
+    pub fn context(mut self, visibility: ContextVisibility) -> ErrorFormatter {
+        self.context = visibility;
+        self
+    }
+
+    /// Sets whether the `Full` and `Pretty` styles render each context value indented under the
+    /// point that added it, rather than in one list before the points. Values added before the
+    /// error was first thrown, such as from `context_scope!()`, are still listed before them.
+    pub fn group_context(mut self, group: bool) -> ErrorFormatter {
+        self.group_context = group;
+        self
+    }
+
+    /// Sets the most bytes of each context value the `Full` and `Pretty` styles render. Longer
+    /// values are cut short and followed by `… (N bytes truncated)`.
+    pub fn max_value_len(mut self, len: usize) -> ErrorFormatter {
+        self.max_value_len = Some(len);
+        self
+    }
+
+    /// Sets the width in characters at which the `Full` and `Pretty` styles break context values
+    /// onto further indented lines.
+    pub fn wrap_values(mut self, width: usize) -> ErrorFormatter {
+        self.wrap_width = Some(width.max(1));
+        self
+    }
+
+    /// Sets how file paths are rewritten before being rendered. Without this, the remapping set
+    /// with `set_global_path_remap()` is used.
+    pub fn path_remap(mut self, remap: PathRemap) -> ErrorFormatter {
+        self.path_remap = Some(remap);
+        self
+    }
+
+    fn remap<'a>(&self, file: &'a str) -> Cow<'a, str> {
+        match self.path_remap {
+            Some(ref remap) => remap.apply(file),
+            None => remap_global(file),
+        }
+    }
+
+    /// Sets whether line and column numbers are rendered as `_:_`, and the crate, timestamp and
+    /// thread recorded for each point are left out, so that snapshots of rendered errors don't
+    /// change whenever code around the points moves or the program is run again.
+    pub fn snapshot(mut self, snapshot: bool) -> ErrorFormatter {
+        self.snapshot = snapshot;
+        self
+    }
+
+    /// Writes a point's `line:column`, or `_:_` in snapshot mode.
+    fn write_position(&self, f: &mut dyn fmt::Write, point: &ErrorPoint) -> fmt::Result {
+        if self.snapshot {
+            f.write_str("_:_")
+        } else {
+            write!(f, "{}:{}", point.line(), point.column())
+        }
+    }
+
+    /// Sets whether the `Full` style renders the line of source code each point is on, with a
+    /// caret under its column. Points whose files can't be read from the current directory are
+    /// rendered without it.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn source_snippets(mut self, source: bool) -> ErrorFormatter {
+        self.source = source;
+        self
+    }
+
+    /// Sets whether the `Full` and `Compact` styles are rendered with ANSI colors: the error
+    /// message in red, context keys in bold and file paths dimmed. This defaults to
+    /// `ColorChoice::Auto`.
+    ///
+    /// Only available with the `color` feature.
+    #[cfg(feature = "color")]
+    pub fn color(mut self, color: ColorChoice) -> ErrorFormatter {
+        self.color = color;
+        self
+    }
+
+    #[cfg(feature = "color")]
+    fn colored(&self) -> bool {
+        use std::io::IsTerminal;
+
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").unwrap_or_default().is_empty()
+                    && std::io::stderr().is_terminal()
+            }
+        }
+    }
+
+    #[cfg(not(feature = "color"))]
+    fn colored(&self) -> bool {
+        false
+    }
+}
+
+/// How an `ErrorFormatter` renders an error's context.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContextVisibility {
+    /// Render each key and value. This is the default.
+    #[default]
+    Shown,
+    /// Render each key, with `<omitted>` in place of its value.
+    Omitted,
+    /// Leave the context out entirely.
+    Hidden,
+}
+
+/// Rewrites of the start of file paths, like rustc's `--remap-path-prefix`, for keeping build
+/// directories out of rendered errors.
+///
+/// ```
+/// # extern crate throw;
+/// use throw::fmt::PathRemap;
+///
+/// let remap = PathRemap::new()
+///     .strip_prefix("/home/ci/build/")
+///     .prefix("/home/ci/.cargo/registry/", "[registry]/");
+/// assert_eq!(remap.apply("/home/ci/build/src/main.rs"), "src/main.rs");
+/// assert_eq!(
+///     remap.apply("/home/ci/.cargo/registry/serde/src/lib.rs"),
+///     "[registry]/serde/src/lib.rs"
+/// );
+/// assert_eq!(remap.apply("src/lib.rs"), "src/lib.rs");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathRemap {
+    rules: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+}
+
+impl PathRemap {
+    /// Creates a remapping which leaves all paths unchanged.
+    pub fn new() -> PathRemap {
+        PathRemap::default()
+    }
+
+    /// Replaces `from` with `to` at the start of paths. When several rules match a path, the
+    /// rule added last is used.
+    pub fn prefix<F, T>(mut self, from: F, to: T) -> PathRemap
+    where
+        F: Into<Cow<'static, str>>,
+        T: Into<Cow<'static, str>>,
+    {
+        self.rules.push((from.into(), to.into()));
+        self
+    }
+
+    /// Removes `prefix` from the start of paths.
+    pub fn strip_prefix<P: Into<Cow<'static, str>>>(self, prefix: P) -> PathRemap {
+        self.prefix(prefix, "")
+    }
+
+    /// Rewrites a path using the last matching rule, if any.
+    pub fn apply<'a>(&self, file: &'a str) -> Cow<'a, str> {
+        for (from, to) in self.rules.iter().rev() {
+            if let Some(rest) = file.strip_prefix(&**from) {
+                if to.is_empty() {
+                    return Cow::Borrowed(rest);
+                }
+                let mut remapped = String::with_capacity(to.len() + rest.len());
+                remapped.push_str(to);
+                remapped.push_str(rest);
+                return Cow::Owned(remapped);
+            }
+        }
+        Cow::Borrowed(file)
+    }
+}
+
+#[cfg(feature = "std")]
+static GLOBAL_PATH_REMAP: std::sync::RwLock<Option<PathRemap>> = std::sync::RwLock::new(None);
+
+/// Sets how file paths are rewritten by `Error`'s Display, Debug and Serialize implementations,
+/// and by formatters without their own `path_remap()`. This applies to the whole program.
+///
+/// Only available with the `std` feature.
+#[cfg(feature = "std")]
+pub fn set_global_path_remap(remap: PathRemap) {
+    let mut global = GLOBAL_PATH_REMAP
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *global = Some(remap);
+}
+
+/// Rewrites a path using the remapping set with `set_global_path_remap()`.
+#[cfg(feature = "std")]
+pub(crate) fn remap_global(file: &str) -> Cow<'_, str> {
+    let global = GLOBAL_PATH_REMAP
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match *global {
+        Some(ref remap) => remap.apply(file),
+        None => Cow::Borrowed(file),
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn remap_global(file: &str) -> Cow<'_, str> {
+    Cow::Borrowed(file)
+}
+
+/// The order an error's points are rendered or serialized in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointOrder {
+    /// The point where the error was first thrown comes first, as returned by `Error::points()`.
+    /// Serialized as `"oldest_first"` in the `"order"` field.
+    OldestFirst,
+    /// The point where the error was most recently thrown comes first. This is the default in
+    /// Display output. Serialized as `"newest_first"` in the `"order"` field.
+    NewestFirst,
+}
+
+impl PointOrder {
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub(crate) fn as_str(&self) -> &'static str {
+        match *self {
+            PointOrder::OldestFirst => "oldest_first",
+            PointOrder::NewestFirst => "newest_first",
+        }
+    }
+}
+
+/// The line rendered before points when they're rendered oldest first.
+pub(crate) const OLDEST_FIRST_HEADER: &str = "points, oldest first:";
+
+/// What follows truncated context values by default.
+const BYTES_TRUNCATED: Plural = Plural {
+    one: Cow::Borrowed("\u{2026} (1 byte truncated)"),
+    other: Cow::Borrowed("\u{2026} ({} bytes truncated)"),
+};
+
+/// A template rendered with a count, in its singular or plural form.
+#[derive(Debug)]
+struct Plural {
+    one: Cow<'static, str>,
+    other: Cow<'static, str>,
+}
+
+impl Plural {
+    fn new<O, T>(one: O, other: T) -> Plural
+    where
+        O: Into<Cow<'static, str>>,
+        T: Into<Cow<'static, str>>,
+    {
+        Plural {
+            one: one.into(),
+            other: other.into(),
+        }
+    }
+
+    /// Writes the form for `count`, with its first `{}` replaced by `count`.
+    fn write(&self, f: &mut dyn fmt::Write, count: usize) -> fmt::Result {
+        let template = if count == 1 { &self.one } else { &self.other };
+        match template.find("{}") {
+            Some(at) => write!(f, "{}{}{}", &template[..at], count, &template[at + 2..]),
+            None => f.write_str(template),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+static GLOBAL_FORMATTER: std::sync::OnceLock<ErrorFormatter> = std::sync::OnceLock::new();
+
+/// Sets the formatter `Error`'s Display implementation renders errors with, for the whole program.
+/// This can only be set once, so is best done at the start of `main()`. If it's already been set,
+/// the given formatter is returned.
+///
+/// Formatting with `{:#}` still renders errors in the `Compact` style, using the other options of
+/// the global formatter.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # extern crate throw;
+/// use throw::fmt::{ErrorFormatter, PointOrder, Style};
+///
+/// throw::fmt::set_global_formatter(
+///     ErrorFormatter::new()
+///         .style(Style::Pretty)
+///         .point_order(PointOrder::OldestFirst),
+/// )
+/// .expect("the global formatter is only set once");
+///
+/// let mut error = throw::Error::new("failed");
+/// error.add_context("attempt", 3);
+/// assert_eq!(error.to_string(), "Error: failed\n\u{2570}\u{2500} attempt: 3");
+/// ```
+#[cfg(feature = "std")]
+#[allow(clippy::result_large_err)]
+pub fn set_global_formatter(formatter: ErrorFormatter) -> Result<(), ErrorFormatter> {
+    GLOBAL_FORMATTER.set(formatter)
+}
+
+/// Sets the style `Error`'s Display implementation renders errors in, for the whole program. This
+/// is the same as `set_global_formatter()` with a formatter using the given style.
+///
+/// Only available with the `std` feature.
+#[cfg(feature = "std")]
+pub fn set_global_style(style: Style) -> Result<(), Style> {
+    set_global_formatter(ErrorFormatter::new().style(style)).map_err(|formatter| formatter.style)
+}
+
+/// Gets the formatter set with `set_global_formatter()`.
+#[cfg(feature = "std")]
+pub(crate) fn global_formatter() -> Option<&'static ErrorFormatter> {
+    GLOBAL_FORMATTER.get()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn global_formatter() -> Option<&'static ErrorFormatter> {
+    None
+}
+
+static DEFAULT_POINT_ORDER: AtomicBool = AtomicBool::new(false);
+
+/// Sets the order points are rendered in by `Error`'s Display and Debug implementations, and by
+/// formatters without their own `point_order()`. This applies to the whole program, and defaults
+/// to `PointOrder::NewestFirst`.
+pub fn set_default_point_order(order: PointOrder) {
+    DEFAULT_POINT_ORDER.store(order == PointOrder::OldestFirst, Ordering::Relaxed);
+}
+
+/// Gets the order set with `set_default_point_order()`.
+pub fn default_point_order() -> PointOrder {
+    if DEFAULT_POINT_ORDER.load(Ordering::Relaxed) {
+        PointOrder::OldestFirst
+    } else {
+        PointOrder::NewestFirst
+    }
+}
+
+/// Whether an `ErrorFormatter` renders errors with ANSI colors.
+///
+/// Only available with the `color` feature.
+#[cfg(feature = "color")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Use colors if stderr is a terminal and the `NO_COLOR` environment variable isn't set. This
+    /// is the default.
+    #[default]
+    Auto,
+    /// Always use colors.
+    Always,
+    /// Never use colors.
+    Never,
+}
+
+/// Closure rendering an error for `Style::Custom`.
+pub type CustomFormat =
+    dyn Fn(&ErrorView<'_>, &mut fmt::Formatter<'_>) -> fmt::Result + Send + Sync;
+
+/// A way of rendering errors.
+#[derive(Default)]
+pub enum Style {
+    /// The error's message, context, points and help message, each on their own line. This is
+    /// the same as `Error`'s Display implementation, and is the default.
+    #[default]
+    Full,
+    /// A single line with the error's message and the point where it was first thrown, such as
+    /// `Error: No such file (src/x.rs:79:17)`. This is the same as formatting an error with
+    /// `{:#}`.
+    Compact,
+    /// The error's message, followed by its context, points and help message drawn as a tree with
+    /// box-drawing characters. Values spanning multiple lines are indented to line up with the
+    /// tree. This is the style used by `Error::to_string_pretty()`.
+    Pretty,
+    /// The error serialized as JSON, in the format of its Serialize implementation.
+    ///
+    /// Only available with the `std` and `serde-1-std` features.
+    #[cfg(all(feature = "std", feature = "serde-1-std"))]
+    Json,
+    /// Rendered by a closure, given a view of the error.
+    Custom(Box<CustomFormat>),
+}
+
+impl Style {
+    /// Creates a `Style::Custom` rendering errors with the given closure.
+    pub fn custom<F>(f: F) -> Style
+    where
+        F: Fn(&ErrorView<'_>, &mut fmt::Formatter<'_>) -> fmt::Result + Send + Sync + 'static,
+    {
+        Style::Custom(Box::new(f))
+    }
+}
+
+impl fmt::Debug for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Style::Full => f.write_str("Full"),
+            Style::Compact => f.write_str("Compact"),
+            Style::Pretty => f.write_str("Pretty"),
+            #[cfg(all(feature = "std", feature = "serde-1-std"))]
+            Style::Json => f.write_str("Json"),
+            Style::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// An error with its inner error type erased, given to `Style::Custom` closures.
+pub struct ErrorView<'a> {
+    error: &'a dyn fmt::Display,
+    points: &'a [ErrorPoint],
+    context: &'a [KvPair],
+    code: Option<&'a str>,
+    severity: Option<Severity>,
+    help: Option<&'a str>,
+    #[cfg(feature = "tracing")]
+    span_trace: Option<&'a tracing_error::SpanTrace>,
+}
+
+impl<'a> ErrorView<'a> {
+    pub(crate) fn new<E>(error: &'a Error<E>, inner: &'a dyn fmt::Display) -> ErrorView<'a> {
+        ErrorView {
+            error: inner,
+            points: error.points(),
+            context: error.get_context(),
+            code: error.code(),
+            severity: error.severity(),
+            help: error.help(),
+            #[cfg(feature = "tracing")]
+            span_trace: error.span_trace(),
+        }
+    }
+
+    /// The original error.
+    pub fn error(&self) -> &'a dyn fmt::Display {
+        self.error
+    }
+
+    /// The error's ErrorPoints, with the first time it was thrown first.
+    pub fn points(&self) -> &'a [ErrorPoint] {
+        self.points
+    }
+
+    /// The error's key/value context, in the order it was added.
+    pub fn get_context(&self) -> &'a [KvPair] {
+        self.context
+    }
+
+    /// The code identifying this kind of error, if one has been set.
+    pub fn code(&self) -> Option<&'a str> {
+        self.code
+    }
+
+    /// How severe the error is, if that has been set.
+    pub fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    /// The message suggesting how to fix the error, if one has been set.
+    pub fn help(&self) -> Option<&'a str> {
+        self.help
+    }
+
+    /// The `tracing` spans the error was created in, if they were captured.
+    ///
+    /// Only available with the `tracing` feature.
+    #[cfg(feature = "tracing")]
+    pub fn span_trace(&self) -> Option<&'a tracing_error::SpanTrace> {
+        self.span_trace
+    }
+
+    /// The captured spans' lines, innermost first.
+    fn spans(&self) -> Vec<SpanLine> {
+        #[cfg_attr(not(feature = "tracing"), allow(unused_mut))]
+        let mut spans = Vec::new();
+        #[cfg(feature = "tracing")]
+        if let Some(span_trace) = self.span_trace {
+            span_trace.with_spans(|metadata, fields| {
+                spans.push(SpanLine {
+                    metadata,
+                    fields: fields.to_string(),
+                });
+                true
+            });
+        }
+        spans
+    }
+
+    /// Writes the first line, continuing any further lines of the message after `indent` and two
+    /// spaces.
+    fn write_header(
+        &self,
+        f: &mut dyn fmt::Write,
+        options: &ErrorFormatter,
+        colored: bool,
+        indent: &str,
+    ) -> fmt::Result {
+        paint(f, RED, colored)?;
+        match options.label {
+            Some(ref label) => f.write_str(label)?,
+            None => f.write_str(self.severity.as_ref().map_or("Error", Severity::label))?,
+        }
+        if let Some(code) = self.code {
+            write!(f, "[{}]", code)?;
+        }
+        write!(
+            continued(&mut Indented { f, prefix: indent }),
+            ": {}",
+            self.error
+        )?;
+        paint(f, RESET, colored)
+    }
+
+    /// The error's points, in the order they're rendered.
+    fn ordered_points(&self, order: PointOrder) -> impl Iterator<Item = (usize, &'a ErrorPoint)> {
+        let points = self.points.iter().enumerate();
+        let (oldest_first, newest_first) = match order {
+            PointOrder::OldestFirst => (Some(points), None),
+            PointOrder::NewestFirst => (None, Some(points.rev())),
+        };
+        oldest_first
+            .into_iter()
+            .flatten()
+            .chain(newest_first.into_iter().flatten())
+    }
+
+    /// The context rendered, newest first.
+    fn shown_context(&self, options: &ErrorFormatter) -> &'a [KvPair] {
+        match options.context {
+            ContextVisibility::Hidden => &[],
+            ContextVisibility::Shown | ContextVisibility::Omitted => self.context,
+        }
+    }
+
+    /// The index of the rendered point a key/value pair is grouped under, if any.
+    fn group_of(&self, kv: &KvPair, options: &ErrorFormatter) -> Option<usize> {
+        let len = self.points.len();
+        let point = kv.point().filter(|_| options.group_context)?;
+        let omitted = options.terse && len > 2 && point != 0 && point != len - 1;
+        Some(point).filter(|&point| point < len && !omitted)
+    }
+
+    /// The context rendered before the points, newest first.
+    fn listed_context<'o>(
+        &'o self,
+        options: &'o ErrorFormatter,
+    ) -> impl Iterator<Item = &'a KvPair> + 'o {
+        self.shown_context(options)
+            .iter()
+            .rev()
+            .filter(move |kv| self.group_of(kv, options).is_none())
+    }
+
+    /// The context rendered under the point at `index`, newest first.
+    fn point_context<'o>(
+        &'o self,
+        index: usize,
+        options: &'o ErrorFormatter,
+    ) -> impl Iterator<Item = &'a KvPair> + 'o {
+        self.shown_context(options)
+            .iter()
+            .rev()
+            .filter(move |kv| self.group_of(kv, options) == Some(index))
+    }
+
+    /// Writes a key/value pair, continuing its value on indented lines.
+    fn write_pair(
+        &self,
+        f: &mut Indented<'_>,
+        kv: &KvPair,
+        options: &ErrorFormatter,
+        colored: bool,
+    ) -> fmt::Result {
+        paint(f, BOLD, colored)?;
+        f.write_str(kv.key())?;
+        paint(f, RESET, colored)?;
+        self.write_value(&mut continued(f), kv, options)
+    }
+
+    /// Writes the pairs grouped under the point at `index`, one more level indented than it.
+    fn write_point_context(
+        &self,
+        f: &mut dyn fmt::Write,
+        index: usize,
+        options: &ErrorFormatter,
+        colored: bool,
+    ) -> fmt::Result {
+        let indent = &*options.indent;
+        for kv in self.point_context(index, options) {
+            let mut f = Indented { f, prefix: indent };
+            let mut f = continued(&mut f);
+            f.write_str("\n")?;
+            self.write_pair(&mut f, kv, options, colored)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a key/value pair's value, or `<omitted>` if values are omitted.
+    fn write_value(
+        &self,
+        f: &mut dyn fmt::Write,
+        kv: &KvPair,
+        options: &ErrorFormatter,
+    ) -> fmt::Result {
+        match options.context {
+            ContextVisibility::Omitted => write!(f, ": {}", options.omitted_value),
+            ContextVisibility::Shown | ContextVisibility::Hidden => {
+                f.write_str(": ")?;
+                let value = Truncated {
+                    value: kv.value(),
+                    max_len: options.max_value_len,
+                    template: &options.bytes_truncated,
+                };
+                match options.wrap_width {
+                    Some(width) => write!(
+                        Wrapped {
+                            f,
+                            width,
+                            column: 0,
+                        },
+                        "{}",
+                        value
+                    ),
+                    None => write!(f, "{}", value),
+                }
+            }
+        }
+    }
+
+    /// The lines rendered for the error's points, in order.
+    fn trace(&self, options: &ErrorFormatter) -> impl Iterator<Item = TraceLine<'a>> {
+        let len = self.points.len();
+        let omitted = if options.terse && len > 2 { len - 2 } else { 0 };
+        self.ordered_points(options.order()).enumerate().filter_map(
+            move |(index, (point_index, point))| {
+                if omitted == 0 || index == 0 || index == len - 1 {
+                    Some(TraceLine::Point(point_index, point))
+                } else if index == 1 {
+                    Some(TraceLine::Omitted(omitted))
+                } else {
+                    None
+                }
+            },
+        )
+    }
+
+    /// Writes a point's location, starting with the point prefix.
+    fn write_point(
+        &self,
+        f: &mut dyn fmt::Write,
+        point: &ErrorPoint,
+        options: &ErrorFormatter,
+        colored: bool,
+    ) -> fmt::Result {
+        write!(f, "{} ", options.point_prefix)?;
+        options.write_position(f, point)?;
+        write!(f, " {} {} (", options.module_prefix, point.module_path())?;
+        paint(f, DIM, colored)?;
+        f.write_str(&options.remap(point.file()))?;
+        paint(f, RESET, colored)?;
+        f.write_str(")")?;
+        if options.snapshot {
+            return Ok(());
+        }
+        write_metadata(f, point, colored)
+    }
+
+    pub(crate) fn write_full(
+        &self,
+        f: &mut dyn fmt::Write,
+        options: &ErrorFormatter,
+    ) -> fmt::Result {
+        let colored = options.colored();
+        let order = options.order();
+        let indent = &*options.indent;
+        self.write_header(f, options, colored, indent)?;
+        for kv in self.listed_context(options) {
+            write!(f, "\n{}", indent)?;
+            self.write_pair(&mut Indented { f, prefix: indent }, kv, options, colored)?;
+        }
+        if order == PointOrder::OldestFirst && !self.points.is_empty() {
+            write!(f, "\n{}{}", indent, options.oldest_first_header)?;
+        }
+        for line in self.trace(options) {
+            write!(f, "\n{}", indent)?;
+            let (index, point) = match line {
+                TraceLine::Point(index, point) => (index, point),
+                TraceLine::Omitted(count) => {
+                    options.frames_omitted.write(f, count)?;
+                    continue;
+                }
+            };
+            self.write_point(f, point, options, colored)?;
+            if let Some(note) = point.note() {
+                write!(
+                    continued(&mut Indented { f, prefix: indent }),
+                    " \u{2014} {}",
+                    note
+                )?;
+            }
+            #[cfg(feature = "std")]
+            if options.source {
+                write_snippet(f, point, indent, colored)?;
+            }
+            self.write_point_context(f, index, options, colored)?;
+        }
+        for span in self.spans() {
+            write!(f, "\n{}", indent)?;
+            span.write(f, options, colored)?;
+        }
+        if let Some(help) = self.help {
+            write!(f, "\n{}", indent)?;
+            write!(
+                continued(&mut Indented { f, prefix: indent }),
+                "{}: {}",
+                options.help_label,
+                help
+            )?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_pretty(
+        &self,
+        f: &mut dyn fmt::Write,
+        options: &ErrorFormatter,
+    ) -> fmt::Result {
+        let colored = options.colored();
+        let order = options.order();
+        let order_header = order == PointOrder::OldestFirst && !self.points.is_empty();
+        let spans = self.spans();
+        let total = self.listed_context(options).count()
+            + order_header as usize
+            + self.trace(options).count()
+            + spans.len()
+            + self.help.is_some() as usize;
+        let trunk = if total == 0 { " " } else { "\u{2502}" };
+        self.write_header(f, options, colored, trunk)?;
+        let mut written = 0;
+        // Starts the next branch of the tree, returning the prefix for any lines it continues on.
+        let mut branch = |f: &mut dyn fmt::Write| {
+            written += 1;
+            if written == total {
+                f.write_str("\n\u{2570}\u{2500} ").map(|()| "   ")
+            } else {
+                f.write_str("\n\u{251c}\u{2500} ").map(|()| "\u{2502}  ")
+            }
+        };
+        for kv in self.listed_context(options) {
+            let prefix = branch(f)?;
+            paint(f, BOLD, colored)?;
+            f.write_str(kv.key())?;
+            paint(f, RESET, colored)?;
+            self.write_value(&mut Indented { f, prefix }, kv, options)?;
+        }
+        if order_header {
+            branch(f)?;
+            f.write_str(&options.oldest_first_header)?;
+        }
+        for line in self.trace(options) {
+            let prefix = branch(f)?;
+            let (index, point) = match line {
+                TraceLine::Point(index, point) => (index, point),
+                TraceLine::Omitted(count) => {
+                    options.frames_omitted.write(f, count)?;
+                    continue;
+                }
+            };
+            self.write_point(f, point, options, colored)?;
+            if let Some(note) = point.note() {
+                write!(Indented { f, prefix }, " \u{2014} {}", note)?;
+            }
+            // The point's own context branches off beneath it.
+            let mut pairs = self.point_context(index, options).peekable();
+            while let Some(kv) = pairs.next() {
+                let mut f = Indented { f, prefix };
+                let sub = if pairs.peek().is_some() {
+                    f.write_str("\n\u{251c}\u{2500} ")?;
+                    "\u{2502}  "
+                } else {
+                    f.write_str("\n\u{2570}\u{2500} ")?;
+                    "   "
+                };
+                paint(&mut f, BOLD, colored)?;
+                f.write_str(kv.key())?;
+                paint(&mut f, RESET, colored)?;
+                self.write_value(
+                    &mut Indented {
+                        f: &mut f,
+                        prefix: sub,
+                    },
+                    kv,
+                    options,
+                )?;
+            }
+        }
+        for span in spans {
+            branch(f)?;
+            span.write(f, options, colored)?;
+        }
+        if let Some(help) = self.help {
+            let prefix = branch(f)?;
+            write!(Indented { f, prefix }, "{}: {}", options.help_label, help)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the error in the `Full` style, with its points merged into a backtrace's frames.
+    #[cfg(feature = "std")]
+    fn write_interleaved(
+        &self,
+        f: &mut dyn fmt::Write,
+        options: &ErrorFormatter,
+        backtrace: &str,
+    ) -> fmt::Result {
+        let colored = options.colored();
+        let indent = &*options.indent;
+        self.write_header(f, options, colored, indent)?;
+        for kv in self.listed_context(options) {
+            write!(f, "\n{}", indent)?;
+            self.write_pair(&mut Indented { f, prefix: indent }, kv, options, colored)?;
+        }
+        let frames = Frame::parse_all(backtrace);
+        if !frames.is_empty() || !self.points.is_empty() {
+            write!(f, "\n{}{}", indent, options.backtrace_header)?;
+        }
+        let mut frames = frames.iter();
+        for (index, point) in self.points.iter().enumerate() {
+            // Each point is written after the frame it's in, or after the previous point if its
+            // frame isn't found.
+            if frames.clone().any(|frame| frame.is_at(point)) {
+                for frame in frames.by_ref() {
+                    frame.write(f, options, colored)?;
+                    if frame.is_at(point) {
+                        break;
+                    }
+                }
+            }
+            write!(f, "\n{}", indent)?;
+            self.write_point(f, point, options, colored)?;
+            if let Some(note) = point.note() {
+                write!(
+                    continued(&mut Indented { f, prefix: indent }),
+                    " \u{2014} {}",
+                    note
+                )?;
+            }
+            self.write_point_context(f, index, options, colored)?;
+        }
+        for frame in frames {
+            frame.write(f, options, colored)?;
+        }
+        if let Some(help) = self.help {
+            write!(f, "\n{}", indent)?;
+            write!(
+                continued(&mut Indented { f, prefix: indent }),
+                "{}: {}",
+                options.help_label,
+                help
+            )?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_compact(
+        &self,
+        f: &mut dyn fmt::Write,
+        options: &ErrorFormatter,
+    ) -> fmt::Result {
+        let colored = options.colored();
+        self.write_header(f, options, colored, "")?;
+        if let Some(point) = self.points.first() {
+            f.write_str(" (")?;
+            paint(f, DIM, colored)?;
+            let file = options.remap(point.file());
+            write!(f, "{}:", file)?;
+            options.write_position(f, point)?;
+            paint(f, RESET, colored)?;
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the line of source code a point is on with a caret under its column, if its file can be
+/// read.
+#[cfg(feature = "std")]
+fn write_snippet(
+    f: &mut dyn fmt::Write,
+    point: &ErrorPoint,
+    indent: &str,
+    colored: bool,
+) -> fmt::Result {
+    let source = match std::fs::read_to_string(point.file()) {
+        Ok(source) => source,
+        Err(_) => return Ok(()),
+    };
+    let line = (point.line() as usize)
+        .checked_sub(1)
+        .and_then(|index| source.lines().nth(index));
+    let line = match line {
+        Some(line) => line,
+        None => return Ok(()),
+    };
+    let number = point.line().to_string();
+    let gutter = " ".repeat(number.len());
+    write!(f, "\n{}{} |", indent, gutter)?;
+    write!(f, "\n{}{} | {}", indent, number, line)?;
+    write!(f, "\n{}{} | ", indent, gutter)?;
+    // Columns count characters from 1. Tabs are kept so that the caret lines up with the line.
+    for c in line.chars().take(point.column().saturating_sub(1) as usize) {
+        f.write_char(if c == '\t' { '\t' } else { ' ' })?;
+    }
+    paint(f, RED, colored)?;
+    f.write_char('^')?;
+    paint(f, RESET, colored)
+}
+
+/// A line rendered for a `tracing` span an error was created in.
+struct SpanLine {
+    #[cfg(feature = "tracing")]
+    metadata: &'static tracing::Metadata<'static>,
+    #[cfg(feature = "tracing")]
+    fields: String,
+}
+
+impl SpanLine {
+    /// Writes `in name{fields} (file:line)`.
+    #[cfg(feature = "tracing")]
+    fn write(
+        &self,
+        f: &mut dyn fmt::Write,
+        options: &ErrorFormatter,
+        colored: bool,
+    ) -> fmt::Result {
+        write!(f, "in {}", self.metadata.name())?;
+        if !self.fields.is_empty() {
+            write!(f, "{{{}}}", self.fields)?;
+        }
+        if let (Some(file), Some(line)) = (self.metadata.file(), self.metadata.line()) {
+            f.write_str(" (")?;
+            paint(f, DIM, colored)?;
+            write!(f, "{}:{}", options.remap(file), line)?;
+            paint(f, RESET, colored)?;
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    fn write(&self, _: &mut dyn fmt::Write, _: &ErrorFormatter, _: bool) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// A line rendered for an error's points.
+enum TraceLine<'a> {
+    /// A point, with its index in `Error::points()`.
+    Point(usize, &'a ErrorPoint),
+    /// Points left out by `ErrorFormatter::terse()`.
+    Omitted(usize),
+}
+
+const RED: &str = "\x1b[31m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Wraps a writer, indenting each new line by two spaces more than it does.
+fn continued<'a>(f: &'a mut Indented<'_>) -> Indented<'a> {
+    Indented { f, prefix: "  " }
+}
+
+/// Writes to another writer, starting each new line with a prefix.
+struct Indented<'a> {
+    f: &'a mut dyn fmt::Write,
+    prefix: &'a str,
+}
+
+impl<'a> fmt::Write for Indented<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(first) = lines.next() {
+            self.f.write_str(first)?;
+        }
+        for line in lines {
+            self.f.write_str("\n")?;
+            self.f.write_str(self.prefix)?;
+            self.f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes an ANSI escape code, if colors are enabled.
+fn paint(f: &mut dyn fmt::Write, code: &str, colored: bool) -> fmt::Result {
+    if colored {
+        f.write_str(code)
+    } else {
+        Ok(())
+    }
+}
+
+/// Writes the metadata recorded for a point as
+/// ` [my_crate 1.2.0, 2026-10-15T05:25:36.422123Z, thread 'main' #1]`, or nothing if none was
+/// recorded.
+fn write_metadata(f: &mut dyn fmt::Write, point: &ErrorPoint, colored: bool) -> fmt::Result {
+    let mut open = false;
+    let mut separator = |f: &mut dyn fmt::Write| {
+        if open {
+            return f.write_str(", ");
+        }
+        open = true;
+        f.write_str(" ")?;
+        paint(f, DIM, colored)?;
+        f.write_str("[")
+    };
+    if let Some(name) = point.crate_name() {
+        separator(f)?;
+        f.write_str(name)?;
+        if let Some(version) = point.crate_version() {
+            write!(f, " {}", version)?;
+        }
+    }
+    if let Some(timestamp) = point.timestamp() {
+        separator(f)?;
+        write_timestamp(f, timestamp)?;
+    }
+    if point.thread_id().is_some() || point.thread_name().is_some() {
+        separator(f)?;
+        f.write_str("thread")?;
+        if let Some(name) = point.thread_name() {
+            write!(f, " '{}'", name)?;
+        }
+        if let Some(id) = point.thread_id() {
+            write!(f, " #{}", id)?;
+        }
+    }
+    if open {
+        f.write_str("]")?;
+        paint(f, RESET, colored)?;
+    }
+    Ok(())
+}
+
+/// Writes a time since the Unix epoch as an RFC 3339 UTC timestamp with microseconds.
+fn write_timestamp(f: &mut dyn fmt::Write, timestamp: Duration) -> fmt::Result {
+    let secs = timestamp.as_secs();
+    // converts days since the epoch to a civil date, from Howard Hinnant's `civil_from_days`
+    let days = secs / 86_400 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    write!(
+        f,
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year,
+        month,
+        day,
+        secs % 86_400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        timestamp.subsec_micros()
+    )
+}
+
+/// Renders an error with an `ErrorFormatter`, created by `Error::display_with()`.
+pub struct DisplayWith<'a, E> {
+    error: &'a Error<E>,
+    formatter: &'a ErrorFormatter,
+}
+
+impl<'a, E> fmt::Display for DisplayWith<'a, E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let view = ErrorView::new(self.error, self.error.error());
+        self.formatter
+            .write_prefixed(f, |f| match self.formatter.style {
+                Style::Full => view.write_full(f, self.formatter),
+                Style::Compact => view.write_compact(f, self.formatter),
+                Style::Pretty => view.write_pretty(f, self.formatter),
+                #[cfg(all(feature = "std", feature = "serde-1-std"))]
+                Style::Json => f.write_str(&self.error.to_json_string()),
+                Style::Custom(ref custom) => write!(f, "{}", DisplayCustom(&view, &**custom)),
+            })
+    }
+}
+
+/// Renders an error with its points merged into a backtrace, created by
+/// `Error::display_with_backtrace()`.
+///
+/// Only available with the `std` feature.
+#[cfg(feature = "std")]
+pub struct DisplayWithBacktrace<'a, E> {
+    error: &'a Error<E>,
+    backtrace: &'a std::backtrace::Backtrace,
+}
+
+#[cfg(feature = "std")]
+impl<'a, E> fmt::Display for DisplayWithBacktrace<'a, E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let default = ErrorFormatter::new();
+        let formatter = global_formatter().unwrap_or(&default);
+        let view = ErrorView::new(self.error, self.error.error());
+        formatter.write_prefixed(f, |f| {
+            view.write_interleaved(f, formatter, &self.backtrace.to_string())
+        })
+    }
+}
+
+/// A frame parsed from a `std::backtrace::Backtrace`'s Display output.
+#[cfg(feature = "std")]
+struct Frame<'a> {
+    index: &'a str,
+    symbol: &'a str,
+    location: Option<&'a str>,
+}
+
+#[cfg(feature = "std")]
+impl<'a> Frame<'a> {
+    /// Parses frames from lines such as `  3: app::main` followed by `at ./src/main.rs:4:5`.
+    fn parse_all(backtrace: &'a str) -> Vec<Frame<'a>> {
+        let mut frames: Vec<Frame> = Vec::new();
+        for line in backtrace.lines().map(str::trim) {
+            if let Some(location) = line.strip_prefix("at ") {
+                if let Some(frame) = frames.last_mut() {
+                    frame.location = frame.location.or(Some(location));
+                }
+                continue;
+            }
+            let colon = match line.find(": ") {
+                Some(colon) => colon,
+                None => continue,
+            };
+            let index = &line[..colon];
+            if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) {
+                frames.push(Frame {
+                    index,
+                    symbol: &line[colon + 2..],
+                    location: None,
+                });
+            }
+        }
+        frames
+    }
+
+    /// Writes this frame on a new line, indented by two spaces more than points.
+    fn write(
+        &self,
+        f: &mut dyn fmt::Write,
+        options: &ErrorFormatter,
+        colored: bool,
+    ) -> fmt::Result {
+        write!(f, "\n{}  {}: {}", options.indent, self.index, self.symbol)?;
+        if let Some(location) = self.location {
+            paint(f, DIM, colored)?;
+            write!(f, " ({})", options.remap(location))?;
+            paint(f, RESET, colored)?;
+        }
+        Ok(())
+    }
+
+    /// Whether this frame is at the same file and line as a point.
+    fn is_at(&self, point: &ErrorPoint) -> bool {
+        let location = match self.location {
+            Some(location) => location,
+            None => return false,
+        };
+        // Locations are `file:line:column`, with files often absolute or starting with `./`.
+        let mut parts = location.rsplitn(3, ':');
+        let (_column, line, file) = (parts.next(), parts.next(), parts.next());
+        match (line, file) {
+            (Some(line), Some(file)) => {
+                line == point.line().to_string()
+                    && file.trim_start_matches("./").ends_with(point.file())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Displays an error using a `Style::Custom` closure.
+struct DisplayCustom<'a>(&'a ErrorView<'a>, &'a CustomFormat);
+
+impl<'a> fmt::Display for DisplayCustom<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
+/// Displays a value cut short after `max_len` bytes, followed by how many bytes were left out.
+pub(crate) struct Truncated<'a> {
+    pub(crate) value: &'a dyn fmt::Display,
+    pub(crate) max_len: Option<usize>,
+    template: &'a Plural,
+}
+
+impl<'a> Truncated<'a> {
+    /// Displays a value cut short with the default `… (N bytes truncated)` suffix.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub(crate) fn new(value: &'a dyn fmt::Display, max_len: usize) -> Truncated<'a> {
+        Truncated {
+            value,
+            max_len: Some(max_len),
+            template: &BYTES_TRUNCATED,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Truncated<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let max_len = match self.max_len {
+            Some(max_len) => max_len,
+            None => return self.value.fmt(f),
+        };
+        let mut truncating = Truncating {
+            f,
+            remaining: max_len,
+            truncated: 0,
+        };
+        write!(truncating, "{}", self.value)?;
+        match truncating.truncated {
+            0 => Ok(()),
+            truncated => self.template.write(f, truncated),
+        }
+    }
+}
+
+/// Writes to another writer until `remaining` bytes have been written, then counts the rest.
+struct Truncating<'a, 'f> {
+    f: &'a mut fmt::Formatter<'f>,
+    remaining: usize,
+    truncated: usize,
+}
+
+impl<'a, 'f> fmt::Write for Truncating<'a, 'f> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut end = s.len().min(self.remaining);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        // Once a value is cut short, nothing after it is written, even if it would fit.
+        self.remaining = if end < s.len() {
+            0
+        } else {
+            self.remaining - end
+        };
+        self.truncated += s.len() - end;
+        self.f.write_str(&s[..end])
+    }
+}
+
+/// Writes to another writer, breaking lines longer than `width` characters.
+struct Wrapped<'a> {
+    f: &'a mut dyn fmt::Write,
+    width: usize,
+    column: usize,
+}
+
+impl<'a> fmt::Write for Wrapped<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut start = 0;
+        for (i, c) in s.char_indices() {
+            if c == '\n' {
+                self.column = 0;
+                continue;
+            }
+            if self.column == self.width {
+                self.f.write_str(&s[start..i])?;
+                self.f.write_str("\n")?;
+                start = i;
+                self.column = 0;
+            }
+            self.column += 1;
+        }
+        self.f.write_str(&s[start..])
+    }
+}
+
+/// Renders points most recent first, one per line.
+#[cfg(any(
+    feature = "log",
+    feature = "opentelemetry",
+    feature = "sentry",
+    feature = "slog",
+    feature = "tracing"
+))]
+pub(crate) struct StackTrace<'a>(pub(crate) &'a [ErrorPoint]);
+
+#[cfg(any(
+    feature = "log",
+    feature = "opentelemetry",
+    feature = "sentry",
+    feature = "slog",
+    feature = "tracing"
+))]
+impl<'a> fmt::Display for StackTrace<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, point) in self.0.iter().rev().enumerate() {
+            if i != 0 {
+                f.write_str("\n")?;
+            }
+            write!(
+                f,
+                "at {}:{} in {} ({})",
+                point.line(),
+                point.column(),
+                point.module_path(),
+                remap_global(point.file())
+            )?;
+            write_metadata(f, point, false)?;
+            if let Some(note) = point.note() {
+                write!(f, " \u{2014} {}", note)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Displays a value using its Debug implementation, for rendering `Error`'s Debug output.
+pub(crate) struct DebugAsDisplay<'a, T>(pub(crate) &'a T);
+
+impl<'a, T: fmt::Debug> fmt::Display for DebugAsDisplay<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+impl<E: fmt::Display> Error<E> {
+    /// Renders this error with the given formatter, rather than in its Display output's style.
+    pub fn display_with<'a>(&'a self, formatter: &'a ErrorFormatter) -> DisplayWith<'a, E> {
+        DisplayWith {
+            error: self,
+            formatter,
+        }
+    }
+
+    /// Writes this error's Display output to a writer, without allocating, so that it can be
+    /// rendered into a fixed-size buffer on targets without an allocator.
+    ///
+    /// Like Display, this uses the formatter set with `set_global_formatter()`, if any. The only
+    /// allocations made are when rewriting a file path with a `PathRemap` set with
+    /// `set_global_path_remap()` which doesn't just strip a prefix, and when the global formatter
+    /// uses the `Json` or a custom style.
+    pub fn write_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write!(w, "{}", self)
+    }
+
+    /// Renders this error in the `Pretty` style, with its context and points drawn as a tree:
+    ///
+    /// ```text
+    /// Error: No such file or directory (os error 2)
+    /// ├─ path: config.toml
+    /// ├─ at 16:23 in app::config (src/config.rs)
+    /// ╰─ at 9:19 in app (src/main.rs)
+    /// ```
+    pub fn to_string_pretty(&self) -> String {
+        self.display_with(&ErrorFormatter::new().style(Style::Pretty))
+            .to_string()
+    }
+
+    /// Renders this error with its points merged into the frames of a backtrace, innermost first,
+    /// so that both the points recorded by throw and the frames between them can be seen together.
+    /// Each point comes after the frame at its file and line, or after the point before it if that
+    /// frame can't be found.
+    ///
+    /// The backtrace should be captured where the error was created. If it wasn't captured, such
+    /// as when `RUST_BACKTRACE` isn't set, only the points are rendered.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn display_with_backtrace<'a>(
+        &'a self,
+        backtrace: &'a std::backtrace::Backtrace,
+    ) -> DisplayWithBacktrace<'a, E> {
+        DisplayWithBacktrace {
+            error: self,
+            backtrace,
+        }
+    }
+
+    /// Renders this error in the `Full` style, followed by the line of source code at each point
+    /// with a caret under its column, when the point's file can be read:
+    ///
+    /// ```text
+    /// Error: No such file or directory (os error 2)
+    ///     at 16:23 in app::config (src/config.rs)
+    ///        |
+    ///     16 |     let file = throw!(File::open(path));
+    ///        |                ^
+    /// ```
+    ///
+    /// File paths are relative to the crate the point is in, so this is most useful when running
+    /// from the root of a crate or workspace, as `cargo run` and `cargo test` do.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn render_with_source(&self) -> String {
+        self.display_with(&ErrorFormatter::new().source_snippets(true))
+            .to_string()
+    }
+}
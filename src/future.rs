@@ -0,0 +1,109 @@
+use core::future::Future;
+use core::panic::Location;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::{ErrorPoint, Result, ThrowContextValues};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Extension methods for futures resolving to a `throw::Result`, adding an ErrorPoint to the
+/// error when one is returned, as `up!()` does for a `throw::Result` in a function.
+///
+/// The point is at the location these methods are called from, but as the module can't be found
+/// from there, its module path is `<unknown>`. These are useful where `up!()` can't be used, as
+/// in future adaptors and middleware, which don't return from a function.
+///
+/// This trait is implemented for all futures resolving to a `throw::Result`.
+///
+/// ```
+/// # extern crate throw;
+/// # extern crate futures_executor;
+/// use throw::ThrowFutureExt;
+///
+/// async fn fetch(id: u32) -> throw::Result<String, &'static str> {
+///     throw::throw_new!("not found", "id" => id);
+/// }
+///
+/// fn main() {
+///     let fut = fetch(4).up().throw_context("attempt", 2);
+///     let error = futures_executor::block_on(fut).unwrap_err();
+///     assert_eq!(error.points().len(), 2);
+///     assert!(error.to_string().contains("attempt: 2"));
+/// }
+/// ```
+pub trait ThrowFutureExt<T, E>: Future<Output = Result<T, E>> + Sized {
+    /// Adds an ErrorPoint at this location to the error this future resolves to, if it fails.
+    #[track_caller]
+    fn up(self) -> UpFuture<Self> {
+        UpFuture {
+            future: self,
+            location: Location::caller(),
+            context: Vec::new(),
+        }
+    }
+
+    /// Like `up()`, also adding the given key/value pair to the error's context. Calling
+    /// `throw_context()` on the returned future adds more pairs to the same point.
+    #[track_caller]
+    fn throw_context<V: Into<ThrowContextValues>>(
+        self,
+        key: &'static str,
+        value: V,
+    ) -> UpFuture<Self> {
+        UpFuture {
+            future: self,
+            location: Location::caller(),
+            context: vec![(key, value.into())],
+        }
+    }
+}
+
+impl<F, T, E> ThrowFutureExt<T, E> for F where F: Future<Output = Result<T, E>> {}
+
+/// Future returned by `ThrowFutureExt::up` and `ThrowFutureExt::throw_context`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct UpFuture<F> {
+    future: F,
+    location: &'static Location<'static>,
+    context: Vec<(&'static str, ThrowContextValues)>,
+}
+
+impl<F> UpFuture<F> {
+    /// Adds another key/value pair to the context added with this future's point.
+    pub fn throw_context<V: Into<ThrowContextValues>>(
+        mut self,
+        key: &'static str,
+        value: V,
+    ) -> UpFuture<F> {
+        self.context.push((key, value.into()));
+        self
+    }
+}
+
+impl<F, T, E> Future for UpFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T, E>> {
+        // SAFETY: `future` is never moved out of `self`, and `UpFuture` doesn't implement `Drop`
+        // or `Unpin` other than through its fields. The other fields aren't treated as pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+        match future.poll(cx) {
+            Poll::Ready(Err(mut error)) => {
+                error.__push_point(ErrorPoint::from_caller(this.location));
+                for (key, value) in this.context.drain(..) {
+                    error.add_context(key, value);
+                }
+                crate::__private::PointHook::new(None).run(&error);
+                Poll::Ready(Err(error))
+            }
+            poll => poll,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::{Error, Severity, ThrowContextValues};
+
+impl<E: fmt::Display> Error<E> {
+    /// Creates a GELF 1.1 message describing this error, to be sent to Graylog.
+    ///
+    /// The `short_message` is the inner error's Display output, and the `full_message` is this
+    /// error's full Display output, including context and points. The `level` is the syslog level
+    /// matching the error's severity: 3 (error) by default, 4 (warning) for
+    /// `Severity::Warning`, and 6 (informational) for `Severity::Advice`.
+    ///
+    /// Each key/value pair of context is added as an additional field, with its key prefixed by
+    /// `_` and any characters GELF doesn't allow replaced by `_`. Numbers are kept as numbers, and
+    /// other values are formatted as strings. Context with the key `id`, which GELF reserves, is
+    /// left out.
+    ///
+    /// Only available with the `gelf` feature.
+    ///
+    /// ```
+    /// # extern crate throw;
+    /// # #[cfg(feature = "gelf")]
+    /// # fn main() {
+    /// let mut error = throw::Error::new("upload failed");
+    /// error.add_context("bytes", 1024);
+    /// let message = error.to_gelf("web-1");
+    /// assert_eq!(message["host"], "web-1");
+    /// assert_eq!(message["short_message"], "upload failed");
+    /// assert_eq!(message["_bytes"], 1024);
+    /// # }
+    /// # #[cfg(not(feature = "gelf"))]
+    /// # fn main() {}
+    /// ```
+    pub fn to_gelf(&self, host: &str) -> Value {
+        let level = match self.severity() {
+            None | Some(Severity::Error) => 3,
+            Some(Severity::Warning) => 4,
+            Some(Severity::Advice) => 6,
+        };
+        let mut message = Map::new();
+        message.insert("version".to_owned(), Value::from("1.1"));
+        message.insert("host".to_owned(), Value::from(host));
+        message.insert(
+            "short_message".to_owned(),
+            Value::from(self.error().to_string()),
+        );
+        message.insert("full_message".to_owned(), Value::from(self.to_string()));
+        message.insert("level".to_owned(), Value::from(level));
+        for kv in self.get_context() {
+            if kv.key() == "id" {
+                continue;
+            }
+            let key = kv
+                .key()
+                .chars()
+                .map(|c| match c {
+                    'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '.' | '-' => c,
+                    _ => '_',
+                })
+                .collect::<String>();
+            message.insert(format!("_{}", key), gelf_value(kv.value()));
+        }
+        Value::Object(message)
+    }
+}
+
+/// Converts a context value to a GELF additional field value, which must be a string or number.
+fn gelf_value(value: &ThrowContextValues) -> Value {
+    match *value {
+        ThrowContextValues::Int8(v) => Value::from(v),
+        ThrowContextValues::Uint8(v) => Value::from(v),
+        ThrowContextValues::Int16(v) => Value::from(v),
+        ThrowContextValues::Uint16(v) => Value::from(v),
+        ThrowContextValues::Int32(v) => Value::from(v),
+        ThrowContextValues::Uint32(v) => Value::from(v),
+        ThrowContextValues::Int64(v) => Value::from(v),
+        ThrowContextValues::Uint64(v) => Value::from(v),
+        ThrowContextValues::Float32(v) if v.is_finite() => Value::from(v),
+        ThrowContextValues::Float64(v) if v.is_finite() => Value::from(v),
+        ref other => Value::from(other.to_string()),
+    }
+}
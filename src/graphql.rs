@@ -0,0 +1,47 @@
+use std::fmt;
+
+use async_graphql::{ErrorExtensions, Value};
+
+use crate::Error;
+
+/// Converts an error into a GraphQL error, with the inner error's Display output as its message,
+/// each key/value pair of context as an `extensions` field under its own key, and the error's
+/// code, from `Error::code()`, under `code`. The error is written with `Error::report()` first, so
+/// its full trace can be found in logs.
+///
+/// As async-graphql converts every error implementing Display with `?` without extensions,
+/// resolvers should call `.extend()` on results with a throw error, from async-graphql's
+/// `ResultExt`.
+///
+/// Only available with the `async-graphql` feature.
+///
+/// ```
+/// # #[cfg(feature = "async-graphql")]
+/// # mod example {
+/// use async_graphql::ResultExt;
+///
+/// fn find_user(id: u32) -> throw::Result<String, &'static str> {
+///     throw::throw_new!("user not found", "user_id" => id);
+/// }
+///
+/// fn user(id: u32) -> async_graphql::Result<String> {
+///     let name = find_user(id).extend()?;
+///     Ok(name.to_uppercase())
+/// }
+/// # }
+/// # fn main() {}
+/// ```
+impl<E: fmt::Display> ErrorExtensions for Error<E> {
+    fn extend(&self) -> async_graphql::Error {
+        self.report();
+        async_graphql::Error::new(self.error().to_string()).extend_with(|_, extensions| {
+            for kv in self.get_context() {
+                let value = serde_json::to_value(kv.value()).unwrap_or_default();
+                extensions.set(kv.key(), Value::from_json(value).unwrap_or_default());
+            }
+            if let Some(code) = self.code() {
+                extensions.set("code", code);
+            }
+        })
+    }
+}
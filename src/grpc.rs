@@ -0,0 +1,95 @@
+//! Converting errors into gRPC statuses for `tonic`.
+//!
+//! Only available with the `tonic` feature.
+
+use std::fmt;
+use std::sync::RwLock;
+
+use tonic::metadata::{Ascii, MetadataKey, MetadataValue};
+use tonic::{Code, Status};
+
+use crate::report::fingerprint;
+use crate::Error;
+
+static EXPOSED_CONTEXT: RwLock<&'static [&'static str]> = RwLock::new(&[]);
+
+/// An error which chooses the gRPC status code of the `tonic::Status` it's converted into.
+pub trait GrpcCode {
+    /// The gRPC status code, such as `Code::NotFound`. Defaults to `Code::Internal`.
+    fn grpc_code(&self) -> Code {
+        Code::Internal
+    }
+}
+
+impl GrpcCode for &'static str {}
+
+impl GrpcCode for String {}
+
+impl GrpcCode for std::io::Error {}
+
+/// Sets the context keys whose values are added to the metadata of statuses converted from
+/// errors. None are added by default, as context often contains private data.
+///
+/// Each key is added in lowercase, with any characters gRPC doesn't allow in metadata keys
+/// replaced by `-`. Values which aren't valid in metadata are left out.
+pub fn set_exposed_context(keys: &'static [&'static str]) {
+    *EXPOSED_CONTEXT
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = keys;
+}
+
+/// Converts an error into a `tonic::Status`, with the code given by the inner error's `GrpcCode`
+/// implementation and the inner error's Display output as the message. The error is written with
+/// `Error::report()` first, so its full trace can be found in logs.
+///
+/// The status's metadata has the error's fingerprint, as described in
+/// `throw::report::JsonReporter`, under `throw-fingerprint`, along with any context chosen with
+/// `set_exposed_context()`.
+///
+/// ```
+/// # #[cfg(feature = "tonic")]
+/// # mod example {
+/// fn get_user() -> throw::Result<(), &'static str> {
+///     throw::throw_new!("user not found");
+/// }
+///
+/// fn handler() -> Result<(), tonic::Status> {
+///     get_user()?;
+///     Ok(())
+/// }
+/// # }
+/// # fn main() {}
+/// ```
+impl<E: fmt::Display + GrpcCode> From<Error<E>> for Status {
+    fn from(error: Error<E>) -> Status {
+        error.report();
+        let mut status = Status::new(error.error().grpc_code(), error.error().to_string());
+        let metadata = status.metadata_mut();
+        if let Ok(value) = format!("{:016x}", fingerprint(&error)).parse() {
+            metadata.insert("throw-fingerprint", value);
+        }
+        let exposed = *EXPOSED_CONTEXT
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for kv in error.get_context() {
+            if !exposed.contains(&kv.key()) {
+                continue;
+            }
+            let key: String = kv
+                .key()
+                .chars()
+                .map(|c| match c {
+                    'a'..='z' | '0'..='9' | '_' | '.' | '-' => c,
+                    'A'..='Z' => c.to_ascii_lowercase(),
+                    _ => '-',
+                })
+                .collect();
+            let key = MetadataKey::from_bytes(key.as_bytes());
+            let value = kv.value().to_string().parse::<MetadataValue<Ascii>>();
+            if let (Ok(key), Ok(value)) = (key, value) {
+                metadata.insert(key, value);
+            }
+        }
+        status
+    }
+}
@@ -0,0 +1,254 @@
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::{Error, ErrorPoint, KvPair};
+
+/// A function called with each point recorded by the macros, set with `set_hook()`.
+pub type Hook = Box<dyn Fn(&ErrorPointEvent) + Send + Sync>;
+
+// Shared so that the hook can be called without holding the lock, letting it set or take the
+// hook itself.
+static HOOK: RwLock<Option<Arc<Hook>>> = RwLock::new(None);
+
+// Checked before taking the lock, so that errors thrown while no hook is set don't contend on it.
+static HOOK_SET: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Set while the hook runs on this thread, so that errors it throws itself don't run it again.
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A point being recorded on an error, as passed to the hook set with `set_hook()`.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorPointEvent<'a> {
+    point: &'a ErrorPoint,
+    origin: &'a ErrorPoint,
+    error: Option<&'a str>,
+    context: &'a [KvPair],
+}
+
+impl<'a> ErrorPointEvent<'a> {
+    /// The point being recorded, which is the error's newest point.
+    pub fn point(&self) -> &'a ErrorPoint {
+        self.point
+    }
+
+    /// The error's first point, where it was originally thrown. This is the same as `point()`
+    /// when the error is first thrown.
+    pub fn origin(&self) -> &'a ErrorPoint {
+        self.origin
+    }
+
+    /// The inner error's Display output, rendered before it was converted into the type the
+    /// function returns. This is `None` if the error's type isn't known to implement Display
+    /// where the point was recorded, such as when it is a generic type parameter.
+    pub fn error(&self) -> Option<&'a str> {
+        self.error
+    }
+
+    /// All of the error's context, including any key/value pairs given with this point.
+    pub fn context(&self) -> &'a [KvPair] {
+        self.context
+    }
+}
+
+/// Sets a hook called whenever `throw!()`, `up!()`, `throw_new!()` or another of the macros,
+/// including `throw_warn!()` and `retry!()`, records a point on an error, after any key/value
+/// pairs given to the macro are added. This replaces any previously set hook, and applies to the
+/// whole program.
+///
+/// Points recorded while the hook runs on the same thread don't call it again. The hook may call
+/// `set_hook()` or `take_hook()` itself, which applies from the next point recorded.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn fails() -> throw::Result<(), &'static str> {
+///     throw_new!("failed", "attempt" => 3);
+/// }
+///
+/// fn main() {
+///     throw::set_hook(Box::new(|event| {
+///         eprintln!(
+///             "{} at {}:{}",
+///             event.error().unwrap_or("error"),
+///             event.point().file(),
+///             event.point().line()
+///         );
+///     }));
+///     assert!(fails().is_err());
+///     throw::take_hook();
+/// }
+/// ```
+pub fn set_hook(hook: Hook) {
+    let mut global = HOOK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *global = Some(Arc::new(hook));
+    HOOK_SET.store(true, Ordering::Release);
+}
+
+/// Removes the hook set with `set_hook()`, returning it.
+///
+/// Only available with the `std` feature.
+pub fn take_hook() -> Option<Hook> {
+    let mut global = HOOK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    HOOK_SET.store(false, Ordering::Release);
+    // If the hook is still running on another thread, it's shared until that call finishes.
+    global.take().map(|hook| {
+        Arc::try_unwrap(hook).unwrap_or_else(|shared| Box::new(move |event| shared(event)))
+    })
+}
+
+/// Whether a hook is set, and isn't already running on this thread.
+pub(crate) fn is_active() -> bool {
+    HOOK_SET.load(Ordering::Acquire) && !IN_HOOK.with(Cell::get)
+}
+
+/// Calls the hook for the newest point of an error, if a hook is set and the error has a point.
+pub(crate) fn run<E>(error: &Error<E>, rendered: Option<&str>) {
+    let (origin, point) = match (error.points().first(), error.points().last()) {
+        (Some(origin), Some(point)) if is_active() => (origin, point),
+        _ => return,
+    };
+    let hook = match *HOOK.read().unwrap_or_else(|poisoned| poisoned.into_inner()) {
+        Some(ref hook) => Arc::clone(hook),
+        None => return,
+    };
+    IN_HOOK.with(|in_hook| in_hook.set(true));
+    let _reset = ResetInHook;
+    hook(&ErrorPointEvent {
+        point,
+        origin,
+        error: rendered,
+        context: error.get_context(),
+    });
+}
+
+/// Clears `IN_HOOK` when dropped, so that it's cleared even if the hook panics.
+struct ResetInHook;
+
+impl Drop for ResetInHook {
+    fn drop(&mut self) {
+        IN_HOOK.with(|in_hook| in_hook.set(false));
+    }
+}
+
+/// A hook forwarding at most a given number of events for each error origin in each time window
+/// to another hook, so that a hot error path can't flood whatever the events are sent to.
+///
+/// Events are grouped by the file, line and column of the error's origin point, where it was
+/// first thrown, rather than of the point being recorded. Each point an error is propagated
+/// through records an event, so grouping by the point being recorded would let one hot error
+/// through once per caller it passes; grouped by origin, they all count towards the same limit.
+/// The origin is used rather than the fingerprint in `throw::report::JsonReporter`'s reports,
+/// since that covers every point, and isn't known until the error has stopped propagating. Each
+/// group's window starts with the first event forwarded for it, and events past the limit are
+/// dropped until the window has passed. Groups whose windows have passed are forgotten whenever
+/// an event from a new origin comes in.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # extern crate throw;
+/// use std::time::Duration;
+/// use throw::SamplingHook;
+///
+/// let sampling = SamplingHook::new(
+///     10,
+///     Duration::from_secs(60),
+///     Box::new(|event| {
+///         let error = event.error().unwrap_or("error");
+///         eprintln!("{} at line {}", error, event.point().line());
+///     }),
+/// );
+/// throw::set_hook(sampling.into_hook());
+/// # throw::take_hook();
+/// ```
+pub struct SamplingHook {
+    max_events: u32,
+    window: Duration,
+    sink: Hook,
+    windows: Mutex<HashMap<Location, Window>>,
+}
+
+/// The file, line and column of the origin point events are grouped by.
+type Location = (Cow<'static, str>, u32, u32);
+
+/// The events forwarded for one location in its current window.
+struct Window {
+    start: Instant,
+    count: u32,
+}
+
+impl SamplingHook {
+    /// Creates a hook forwarding at most `max_events` events for each error origin in each
+    /// `window` to `sink`.
+    pub fn new(max_events: u32, window: Duration, sink: Hook) -> SamplingHook {
+        SamplingHook {
+            max_events,
+            window,
+            sink,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forwards an event to the sink, unless its origin's limit has been reached in the current
+    /// window.
+    pub fn call(&self, event: &ErrorPointEvent) {
+        let origin = event.origin();
+        let now = Instant::now();
+        let forward = {
+            let mut windows = self
+                .windows
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let file = match origin.static_location() {
+                Some((_, file)) => Cow::Borrowed(file),
+                None => Cow::Owned(origin.file().to_owned()),
+            };
+            let key = (file, origin.line, origin.column);
+            if !windows.contains_key(&key) {
+                windows.retain(|_, window| now.duration_since(window.start) < self.window);
+            }
+            let window = windows.entry(key).or_insert(Window {
+                start: now,
+                count: 0,
+            });
+            if now.duration_since(window.start) >= self.window {
+                window.start = now;
+                window.count = 0;
+            }
+            if window.count < self.max_events {
+                window.count += 1;
+                true
+            } else {
+                false
+            }
+        };
+        if forward {
+            (self.sink)(event);
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn __origins(&self) -> usize {
+        self.windows
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+
+    /// Boxes this for use with `set_hook()`.
+    pub fn into_hook(self) -> Hook {
+        Box::new(move |event| self.call(event))
+    }
+}
@@ -0,0 +1,106 @@
+//! Mapping errors to HTTP responses.
+//!
+//! Inner errors implement `HttpStatus` to choose the status code of the response sent for them
+//! by the web framework integrations, such as the `axum` feature's `IntoResponse` implementation
+//! and the `actix-web` feature's `ResponseError` implementation. `throw::Error<E>` implements it
+//! as well, taking the status from its inner error.
+//!
+//! With the `macros` feature, `#[derive(HttpStatus)]` implements it from `#[http(status = 404)]`
+//! attributes on the type, and on each variant of an enum to override the type's status.
+
+#[cfg(any(feature = "axum", feature = "actix-web", feature = "rocket"))]
+use std::fmt;
+#[cfg(feature = "problem-json")]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(any(feature = "axum", feature = "actix-web", feature = "rocket"))]
+use serde_json::{Map, Value};
+
+use crate::Error;
+
+#[cfg(feature = "macros")]
+pub use throw_macros::HttpStatus;
+
+/// An error which chooses the HTTP status code of the response sent for it.
+///
+/// ```
+/// # extern crate throw;
+/// # #[cfg(feature = "macros")]
+/// # fn main() {
+/// use throw::http::HttpStatus;
+///
+/// #[derive(HttpStatus)]
+/// #[http(status = 400)]
+/// enum ApiError {
+///     BadRequest,
+///     #[http(status = 404)]
+///     NotFound,
+/// }
+///
+/// assert_eq!(ApiError::BadRequest.status(), 400);
+/// assert_eq!(throw::Error::new(ApiError::NotFound).status(), 404);
+/// # }
+/// # #[cfg(not(feature = "macros"))]
+/// # fn main() {}
+/// ```
+pub trait HttpStatus {
+    /// The HTTP status code, such as 404. Defaults to 500 (Internal Server Error).
+    fn status(&self) -> u16 {
+        500
+    }
+}
+
+impl HttpStatus for &'static str {}
+
+#[cfg(feature = "std")]
+impl HttpStatus for String {}
+
+#[cfg(feature = "std")]
+impl HttpStatus for std::io::Error {}
+
+impl<E: HttpStatus> HttpStatus for Error<E> {
+    fn status(&self) -> u16 {
+        self.error().status()
+    }
+}
+
+#[cfg(feature = "problem-json")]
+static EXPOSE_DETAILS: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether the responses sent for errors include their context and points. Disabled by
+/// default, as these describe the server's code and often contain private data.
+///
+/// Every error is logged when its response is created, so the full error can still be found in
+/// logs when its details aren't exposed.
+///
+/// Only available with the `problem-json` feature.
+#[cfg(feature = "problem-json")]
+pub fn set_expose_details(expose: bool) {
+    EXPOSE_DETAILS.store(expose, Ordering::Relaxed);
+}
+
+/// Creates the `application/problem+json` body of the response sent for an error.
+///
+/// With details exposed, the body is `Error::to_problem_json()`. Otherwise, it only has the
+/// `type`, `title` and `status` members, with the inner error's Display output as the `title` for
+/// 4xx statuses, and `reason` as the `title` for any other status, to avoid revealing details of
+/// server errors.
+#[cfg(any(feature = "axum", feature = "actix-web", feature = "rocket"))]
+pub(crate) fn problem_body<E: fmt::Display + HttpStatus>(
+    error: &Error<E>,
+    reason: Option<&str>,
+) -> Value {
+    let status = error.status();
+    if EXPOSE_DETAILS.load(Ordering::Relaxed) {
+        return error.to_problem_json(status);
+    }
+    let title = match status {
+        400..=499 => error.error().to_string(),
+        _ => reason.unwrap_or("Internal Server Error").to_owned(),
+    };
+    let mut problem = Map::new();
+    problem.insert("type".to_owned(), Value::from("about:blank"));
+    problem.insert("title".to_owned(), Value::from(title));
+    problem.insert("status".to_owned(), Value::from(status));
+    Value::Object(problem)
+}
@@ -0,0 +1,160 @@
+use core::fmt;
+use core::iter::FromIterator;
+
+use crate::{Error, MultiError, Result};
+
+#[cfg(not(feature = "std"))]
+use alloc::string::ToString;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Extension methods for iterators, adding the index of the element being processed to errors
+/// created while processing it.
+///
+/// This trait is implemented for all iterators.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// use throw::ThrowIteratorExt;
+///
+/// fn check(value: u32) -> throw::Result<(), &'static str> {
+///     throw_assert!(value % 2 == 0, "odd value");
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     let err = vec![2, 4, 5, 6].into_iter().try_for_each_throw(check).unwrap_err();
+///     assert!(err.to_string().contains("index: 2"));
+/// }
+/// ```
+pub trait ThrowIteratorExt: Iterator + Sized {
+    /// Calls a closure on each element, stopping at the first error. The index of the element
+    /// which failed is added to the error's context under the `"index"` key.
+    fn try_for_each_throw<F, E>(self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(Self::Item) -> Result<(), E>,
+    {
+        for (index, item) in self.enumerate() {
+            if let Err(mut e) = f(item) {
+                e.add_context("index", index as u64);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Like `try_for_each_throw`, but passes each element to the closure by reference, so the
+    /// element which failed can also be added to the error's context under the `"item"` key.
+    fn try_for_each_throw_display<F, E>(self, mut f: F) -> Result<(), E>
+    where
+        Self::Item: fmt::Display,
+        F: FnMut(&Self::Item) -> Result<(), E>,
+    {
+        for (index, item) in self.enumerate() {
+            if let Err(mut e) = f(&item) {
+                e.add_context("index", index as u64);
+                e.add_context("item", item.to_string());
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Maps each element with a closure returning a `throw::Result`, adding the index of the
+    /// element to the context of any error returned under the `"index"` key.
+    fn map_throw<F, T, E>(self, f: F) -> MapThrow<Self, F>
+    where
+        F: FnMut(Self::Item) -> Result<T, E>,
+    {
+        MapThrow {
+            iter: self,
+            f,
+            index: 0,
+        }
+    }
+
+    /// Collects an iterator of `throw::Result`s, like `collect::<Result<C, _>>()`, but rather than
+    /// stopping at the first error, collects every error into a `MultiError`. The index of each
+    /// element which was an error is added to its context under the `"index"` key.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate throw;
+    /// use throw::ThrowIteratorExt;
+    ///
+    /// fn check(value: u32) -> throw::Result<u32, &'static str> {
+    ///     throw_assert!(value % 2 == 0, "odd value");
+    ///     Ok(value / 2)
+    /// }
+    ///
+    /// fn main() {
+    ///     let halves: Vec<u32> = vec![2, 4, 6].into_iter().map(check).collect_throw().unwrap();
+    ///     assert_eq!(halves, [1, 2, 3]);
+    ///
+    ///     let errors = vec![1, 2, 3]
+    ///         .into_iter()
+    ///         .map(check)
+    ///         .collect_throw::<Vec<_>, _, _>()
+    ///         .unwrap_err();
+    ///     assert_eq!(errors.len(), 2);
+    ///     assert!(errors.errors()[1].to_string().contains("index: 2"));
+    /// }
+    /// ```
+    fn collect_throw<C, T, E>(self) -> core::result::Result<C, MultiError<E>>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        C: FromIterator<T>,
+    {
+        let mut errors = Vec::new();
+        let collected = self
+            .enumerate()
+            .filter_map(|(index, item)| match item {
+                Ok(value) => Some(value),
+                Err(mut e) => {
+                    e.add_context("index", index as u64);
+                    errors.push(e);
+                    None
+                }
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(collected)
+        } else {
+            Err(MultiError::new(errors))
+        }
+    }
+}
+
+impl<I: Iterator> ThrowIteratorExt for I {}
+
+/// Iterator returned by `ThrowIteratorExt::map_throw`.
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct MapThrow<I, F> {
+    iter: I,
+    f: F,
+    index: usize,
+}
+
+impl<I, F, T, E> Iterator for MapThrow<I, F>
+where
+    I: Iterator,
+    F: FnMut(I::Item) -> Result<T, E>,
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Result<T, E>> {
+        let item = self.iter.next()?;
+        let index = self.index;
+        self.index += 1;
+        Some((self.f)(item).map_err(|mut e: Error<E>| {
+            e.add_context("index", index as u64);
+            e
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
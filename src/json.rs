@@ -0,0 +1,35 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::Error;
+
+impl<E: fmt::Display> Error<E> {
+    /// Serializes this error to a JSON string, in the format of its Serialize implementation.
+    ///
+    /// Only available with the `std` and `serde-1-std` features.
+    ///
+    /// ```
+    /// # extern crate throw;
+    /// # #[cfg(all(feature = "std", feature = "serde-1-std"))]
+    /// # fn main() {
+    /// let error = throw::Error::new("failed");
+    /// assert_eq!(
+    ///     error.to_json_string(),
+    ///     r#"{"points":[],"context":[],"error":"failed"}"#
+    /// );
+    /// # }
+    /// # #[cfg(not(all(feature = "std", feature = "serde-1-std")))]
+    /// # fn main() {}
+    /// ```
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string(self).expect("throw errors always serialize to JSON")
+    }
+
+    /// Serializes this error to a JSON value, in the format of its Serialize implementation.
+    ///
+    /// Only available with the `std` and `serde-1-std` features.
+    pub fn to_json_value(&self) -> Value {
+        serde_json::to_value(self).expect("throw errors always serialize to JSON")
+    }
+}
@@ -0,0 +1,118 @@
+use std::any::type_name;
+use std::fmt::{self, Write};
+
+use lambda_runtime::Diagnostic;
+use serde_json::{Map, Value};
+
+use crate::Error;
+
+impl<E> Error<E> {
+    /// The `errorType` reported to Lambda: the error's code, from `Error::code()`, or the inner
+    /// error's type name if it has none.
+    fn lambda_error_type(&self) -> String {
+        match self.code() {
+            Some(code) => code.to_owned(),
+            None => type_name::<E>().to_owned(),
+        }
+    }
+}
+
+impl<E: fmt::Display> Error<E> {
+    /// Creates the error payload of the AWS Lambda runtime API, as sent when an invocation fails.
+    ///
+    /// The result has these fields:
+    ///
+    /// - `errorType`: the error's code, from `Error::code()`, or the inner error's type name
+    /// - `errorMessage`: the inner error's Display output
+    /// - `stackTrace`: one line for each point, most recent first, as in Display output
+    ///
+    /// `lambda_runtime::Diagnostic` has no field for the stack trace, so this is for runtimes
+    /// which post errors themselves, and for logging errors to CloudWatch in the same shape.
+    ///
+    /// Only available with the `lambda` feature.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate throw;
+    /// # #[cfg(feature = "lambda")]
+    /// # fn main() {
+    /// fn check(id: u32) -> throw::Result<(), &'static str> {
+    ///     throw_new!("not found", "id" => id);
+    /// }
+    ///
+    /// let payload = check(4).unwrap_err().to_lambda_payload();
+    /// assert_eq!(payload["errorType"], "&str");
+    /// assert_eq!(payload["errorMessage"], "not found");
+    /// assert_eq!(payload["stackTrace"].as_array().unwrap().len(), 1);
+    /// # }
+    /// # #[cfg(not(feature = "lambda"))]
+    /// # fn main() {}
+    /// ```
+    pub fn to_lambda_payload(&self) -> Value {
+        let stack_trace = self
+            .points()
+            .iter()
+            .rev()
+            .map(|point| {
+                let mut line = format!(
+                    "at {}:{} in {} ({})",
+                    point.line(),
+                    point.column(),
+                    point.module_path(),
+                    crate::fmt::remap_global(point.file())
+                );
+                if let Some(note) = point.note() {
+                    let _ = write!(line, " \u{2014} {}", note);
+                }
+                Value::from(line)
+            })
+            .collect();
+
+        let mut payload = Map::new();
+        payload.insert(
+            "errorType".to_owned(),
+            Value::from(self.lambda_error_type()),
+        );
+        payload.insert(
+            "errorMessage".to_owned(),
+            Value::from(self.error().to_string()),
+        );
+        payload.insert("stackTrace".to_owned(), Value::Array(stack_trace));
+        Value::Object(payload)
+    }
+}
+
+/// Converts an error into a `lambda_runtime::Diagnostic`, so that Lambda handlers can return a
+/// `throw::Result` through `?`.
+///
+/// The `error_type` is the error's code, from `Error::code()`, or the inner error's type name if
+/// it has none. As `Diagnostic` has no field for a stack trace, the `error_message` is the error's
+/// full Display output, including its context and points, which CloudWatch shows as the error.
+///
+/// Only available with the `lambda` feature.
+///
+/// ```
+/// # #[cfg(feature = "lambda")]
+/// # mod example {
+/// use lambda_runtime::{Diagnostic, LambdaEvent};
+/// use serde_json::Value;
+///
+/// fn lookup(id: u64) -> throw::Result<String, &'static str> {
+///     throw::throw_new!("user not found", "user_id" => id);
+/// }
+///
+/// async fn handler(event: LambdaEvent<Value>) -> Result<String, Diagnostic> {
+///     let id = event.payload["id"].as_u64().unwrap_or_default();
+///     Ok(lookup(id)?)
+/// }
+/// # }
+/// # fn main() {}
+/// ```
+impl<E: fmt::Display> From<Error<E>> for Diagnostic {
+    fn from(error: Error<E>) -> Diagnostic {
+        Diagnostic {
+            error_type: error.lambda_error_type(),
+            error_message: error.to_string(),
+        }
+    }
+}
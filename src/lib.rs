@@ -1,7 +1,10 @@
 #![cfg_attr(not(feature = "std"), no_std)]
-#![cfg_attr(not(feature = "std"), feature(alloc))]
+#![cfg_attr(
+    all(feature = "std", feature = "nightly"),
+    feature(error_generic_member_access)
+)]
 #![deny(missing_docs)]
-#![doc(html_root_url = "https://docs.rs/throw/0.1.7")]
+#![doc(html_root_url = "https://docs.rs/throw/0.2.0")]
 //! Throw!
 //! ------
 //!
@@ -46,6 +49,9 @@
 //! created using `throw!()` in a sub-function which you want to add an error point to and
 //! propagate upwards.
 //!
+//! On the 2018 edition or later, the macros can be imported by path with
+//! `use throw::{throw, up};` instead of `#[macro_use] extern crate throw;`.
+//!
 //! Here's an example of throw in action:
 //!
 //! ```rust
@@ -146,7 +152,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! throw = { version = "0.1", default-features = "false" }
+//! throw = { version = "0.2", default-features = "false" }
 //! ```
 //!
 //! ---
@@ -157,7 +163,9 @@
 //! Throw supports adding key/value pairs to errors to provide additional context information.
 //! In order to use this, simply add any number of `"key_name" => value,` arguments to any of
 //! the macros throw exports. `value` can be any integer type, float type, an `&'static str`,
-//! or an owned string.
+//! or an owned string. Keys can be any expression evaluating to an `&'static str`, values can be
+//! any expression (including `match` expressions and blocks), and a trailing comma is always
+//! allowed.
 //!
 //! ```
 //! # #[macro_use]
@@ -189,48 +197,430 @@
 //!
 //! ---
 //!
+//! Scoped context
+//! ---
+//!
+//! With the `std` feature, `context_scope!()` adds key/value pairs to every error created on the
+//! current thread until the end of the enclosing block. This removes the need to pass values such
+//! as request IDs down to every throw site: `context_scope!("request_id" => id);` at the top of a
+//! request handler attaches the ID to any error the handler throws.
+//!
+//! As futures can move between threads at each `.await`, async code uses
+//! `throw::scope(future).context("request_id", id)` instead, which adds the key/value pairs to
+//! errors created anywhere within the future's call tree, on any executor.
+//!
+//! ---
+//!
+//! Warnings
+//! ---
+//!
+//! `throw_warn!(warnings, "message", "key" => value)` records a problem which doesn't stop the
+//! current operation in a `throw::Warnings` collector, with an ErrorPoint and context as for
+//! errors. `warnings.finish(result)` returns the warnings alongside a successful result, or adds
+//! them to the context of the error if the operation failed.
+//!
+//! ---
+//!
+//! Formatting
+//! ---
+//!
+//! `Error`'s Display implementation renders the full trace shown above. With `{:#}`, it instead
+//! renders a single line with the original error and the point where it was first thrown, such as
+//! `Error: No such file or directory (os error 2) (src/main.rs:16:23)`, and
+//! `Error::to_string_pretty()` draws the context and points as a tree. The `throw::fmt` module
+//! provides an `ErrorFormatter` which renders errors in other styles, such as on a single line, as
+//! JSON, or with a custom closure, using `error.display_with(&formatter)`.
+//!
+//! With the `std` feature, `throw::fmt::set_global_formatter()` or `throw::fmt::set_global_style()`
+//! changes how every error is rendered by its Display implementation, such as to render all errors
+//! compactly or with colors.
+//!
+//! Points are rendered newest first, unless `throw::fmt::set_default_point_order()` or
+//! `ErrorFormatter::point_order()` is used to render them oldest first. Context is listed before
+//! the points, unless `ErrorFormatter::group_context()` is used to render each value under the
+//! point that added it. Huge values, such as request bodies, can be cut short with
+//! `ErrorFormatter::max_value_len()` or broken onto several lines with
+//! `ErrorFormatter::wrap_values()`.
+//!
+//! The fixed words in rendered errors, such as `Error`, `at` and `help`, can be replaced with
+//! `ErrorFormatter` methods such as `label()`, `point_prefix()` and `help_label()`, such as to
+//! render errors in another language.
+//!
+//! File paths can be shortened or rewritten before being rendered or serialized with a
+//! `throw::fmt::PathRemap`, given to `ErrorFormatter::path_remap()` or, with the `std` feature, to
+//! `throw::fmt::set_global_path_remap()`.
+//!
+//! With the `crate-info` feature, each point records the name and version of the crate it is in,
+//! rendered after the point's file as `[my_crate 1.2.0]`. This shows where errors cross crate
+//! boundaries, and spots deployments mixing versions of a crate from the trace alone. With the
+//! `timestamps` feature, each point records when it was added, rendered in the same brackets as
+//! an RFC 3339 timestamp, showing how long an error spent bubbling up through retries and queues.
+//! With the `thread-info` feature, each point records the name and ID of the thread it was added
+//! on, rendered as `thread 'main' #1`, to follow errors sent between threads.
+//!
+//! With the `std` feature, `Error::render_with_source()` shows the line of source code at each
+//! point, and `Error::display_with_backtrace()` merges an error's points into the frames of a
+//! `std::backtrace::Backtrace`. With the `backtrace` feature, `Error::new()` captures a backtrace
+//! when `RUST_BACKTRACE` is set, returned by `Error::backtrace()`. On nightly, the `nightly` feature
+//! implements `std::error::Error::provide()`, so report handlers using `request_ref()` can find
+//! the backtrace, the points and the inner error.
+//!
+//! With the `color` feature, errors are rendered with ANSI colors when stderr is a terminal and
+//! `NO_COLOR` isn't set, in `Error`'s Display output as well as by `throw::report()`.
+//! `ErrorFormatter::color()` overrides this, such as with `ColorChoice::Never` in a global formatter
+//! set with `throw::fmt::set_global_formatter()` for programs writing errors to files.
+//!
+//! With the `std` feature, `main` can return `Result<(), throw::Report>`, using `?` on any
+//! `throw::Result`. An error returned from `main` this way is rendered in the `Pretty` style,
+//! followed by a backtrace and a description of the environment, or however a
+//! `throw::ReportHandler` set with `throw::set_report_handler()` renders it. `main` can also
+//! return a `throw::Exit<E>`, which prints the error's full trace and exits with the exit code
+//! given by the inner error's `throw::ExitStatus` implementation.
+//! `throw::install_panic_hook()` renders errors unwrapped with `.unwrap()` or `.expect()` in the
+//! same style when they panic. `catch_panic!()` runs a closure, converting any panic in it into a
+//! `throw::Error<throw::PanicError>`.
+//!
+//! With the `crash-dump` feature, `crash_dump!()` creates a `throw::CrashDump`, which writes a
+//! JSON file describing an error returned from `main` or a panic, along with the crate's version
+//! and the operating system, for users to attach to bug reports.
+//!
+//! ---
+//!
+//! Attribute macros
+//! ---
+//!
+//! With the `macros` feature, `#[throw::throws(E)]` can be used on a function to change its return
+//! type from `T` to `throw::Result<T, E>`, wrapping its body in `Ok`:
+//!
+//! ```
+//! # #[cfg(feature = "macros")]
+//! # mod example {
+//! use throw::{throw_new, throws};
+//!
+//! #[throws(&'static str)]
+//! fn half(value: u32) -> u32 {
+//!     if value % 2 == 1 {
+//!         throw_new!("odd value", "value" => value);
+//!     }
+//!     value / 2
+//! }
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! `#[throw::trace]` adds ErrorPoints to errors returned with `?` in a function returning a
+//! `throw::Result`, as if each `?` were replaced with `throw!()` or `up!()`.
+//!
+//! `#[throw::context_args(user_id, ?query)]` adds the named arguments of a function returning a
+//! `throw::Result` to any error it returns, formatted with `Display`, or `Debug` if prefixed with
+//! `?`.
+//!
+//! `#[derive(ThrowContext)]` on a struct lets all of its fields be attached to an error as
+//! context at once, with `error.add_context_from(&value)`.
+//!
+//! `#[derive(ThrowDiagnostic)]` with `#[diagnostic(code = "E1042", severity = "warning", help =
+//! "...")]` gives an error type an error code, severity and help message, which
+//! `error.diagnose()` copies onto a `throw::Error` to be shown in its Display output and
+//! serialization.
+//!
+//! `#[derive(throw::http::HttpStatus)]` with `#[http(status = 404)]` chooses the HTTP status of
+//! the responses sent for an error type by the web framework integrations, on the type and on
+//! each variant of an enum.
+//!
+//! `#[derive(UpFrom)]` on an error enum implements `From` for the error wrapped by each of its
+//! single-field variants, so that `up!()` and `transform()` can turn a `throw::Error<io::Error>`
+//! into a `throw::Error<AppError>`, keeping its ErrorPoints and context.
+//!
+//! ---
+//!
+//! Testing
+//! ---
+//!
+//! `assert_throws!()` checks the original error, context and points of a thrown error. With the
+//! `test-util` feature, the `throw::test_util` module additionally provides assertions on the
+//! rendered output of errors which ignore line and column numbers.
+//!
+//! ---
+//!
+//! Logging
+//! ---
+//!
+//! With the `log` feature, `error.log(level)` logs an error through the `log` crate, with its
+//! context and points attached as structured key/value pairs, and `throw_logged!()` and
+//! `throw_new_logged!()` log each error they create as it's thrown.
+//!
+//! With the `slog` feature, `Error` implements `slog::KV` and context values implement
+//! `slog::Value`, so errors can be added to any slog record, and `error.log_to(&logger, level)`
+//! logs an error at the location of its newest point.
+//!
+//! With the `metrics` feature, each point recorded by `throw!()`, `up!()` and the other macros
+//! increments the `throw.errors_total` counter of the `metrics` crate, labelled with the point's
+//! `module` and the error's `code`, which is empty for errors without one.
+//!
+//! With the `opentelemetry` feature, `error.record_on_span(&mut span)` marks an OpenTelemetry span
+//! as failed, adding an `exception` event with the error's message and points, and the error's
+//! context as span attributes.
+//!
+//! With the `sentry` feature, `error.add_breadcrumbs()` adds a Sentry breadcrumb for each of an
+//! error's points, so that events captured afterwards show where it was thrown and propagated.
+//!
+//! With the `tracing` feature, `error.emit()` records a `tracing` event for an error in the current
+//! span, with its context, code, origin and points as fields. When `tracing_error::ErrorLayer` is
+//! installed, each error also captures the spans it was created in, which are rendered after its
+//! points as `in span{field=value} (file:line)` lines.
+//!
+//! ---
+//!
+//! Hooks
+//! ---
+//!
+//! With the `std` feature, `throw::set_hook()` sets a function called whenever one of the macros
+//! records a point, with the point, the inner error's Display output and the error's context, to
+//! wire up logging, metrics or tracing not covered by the integrations above. `SamplingHook`
+//! wraps another hook, forwarding at most a given number of events from each location per time
+//! window.
+//!
+//! With the `system-context` feature, `Error::with_system_context()` adds the process id,
+//! executable name, hostname and operating system to an error's context, and
+//! `throw::set_system_context(true)` adds them to every error when it is created.
+//!
+//! ---
+//!
 //! Serde support
 //! ---
 //!
 //! To have `serde::{Serialize, Deserialize}` implemented on Throw types, depend on throw with
 //! `features = ["serde-1-std"]` or `features = ["serde-1"]` for no-std environments.
+//!
+//! With `serde-1-std`, `Error::to_json_string()` and `Error::to_json_value()` serialize an error
+//! to JSON without needing to depend on `serde_json` directly, and `error.report()` writes an
+//! error as a line of JSON with a `throw::report::JsonReporter`, for structured logs.
+//!
+//! With the `schemars` feature, `schemars::JsonSchema` is also implemented for serialized types,
+//! describing the default serialization format.
+//!
+//! With the `problem-json` feature, `Error::to_problem_json()` creates an RFC 7807
+//! `application/problem+json` body for an error.
+//!
+//! With the `axum` feature, `throw::Error<E>` implements axum's `IntoResponse`, so handlers can
+//! return a `throw::Result`. The response's status comes from the inner error's
+//! `throw::http::HttpStatus` implementation, and its body is `application/problem+json`. The
+//! `actix-web` feature implements actix-web's `ResponseError` in the same way, and the `rocket`
+//! feature implements Rocket's `Responder`. With the `tonic`
+//! feature, errors convert into a `tonic::Status` with the code from the inner error's
+//! `throw::grpc::GrpcCode` implementation. With the `async-graphql` feature, errors implement
+//! `async_graphql::ErrorExtensions`, adding their context and code as extensions. With the `tower`
+//! feature, `throw::tower::ThrowReportLayer` reports every error returned by a `tower` service to
+//! the hook set with `throw::set_hook()`, with context taken from the request.
+//!
+//! With the `wasm` feature, errors convert into a JavaScript `Error` carrying their trace, points
+//! and context, so `#[wasm_bindgen]` functions can return them, and `error.console_error()` logs
+//! them with `console.error()`. With the `pyo3` feature, errors convert into a Python
+//! `RuntimeError` whose message includes their points formatted like a Python traceback, and whose
+//! arguments include their context, so `#[pyfunction]`s can return them. For C callers, the `ffi`
+//! feature's `throw::ffi::FfiError::new()` flattens an error, its points and its context into a
+//! `#[repr(C)]` struct, freed with `throw_ffi_error_free()`.
+//!
+//! With the `lambda` feature, errors convert into a `lambda_runtime::Diagnostic`, typed by their
+//! code or inner error type, so Lambda handlers can return them, and
+//! `error.to_lambda_payload()` creates the runtime API's error payload with a `stackTrace` of the
+//! error's points.
+//!
+//! With the `ecs` feature, `Error::to_ecs_value()` maps an error to Elastic Common Schema fields.
+//! With the `gelf` feature, `Error::to_gelf()` creates a GELF message for Graylog.
+//!
+//! `Error`'s Serialize implementation can only be deserialized from self-describing formats such
+//! as JSON. For formats such as postcard or bincode, use `throw::ser::Portable`.
+//!
+//! The inner error is serialized using its Display implementation, so a serialized
+//! `throw::Error<E>` can be deserialized as a `throw::Error<String>` with the same points and
+//! context.
 
 #[cfg(not(feature = "std"))]
-#[cfg_attr(any(feature = "serde-1", feature = "serde-1-std"), macro_use)]
+#[macro_use]
 extern crate alloc;
 
+#[cfg(feature = "actix-web")]
+extern crate actix_web;
+#[cfg(feature = "async-graphql")]
+extern crate async_graphql;
+#[cfg(feature = "axum")]
+extern crate axum_core;
+#[cfg(feature = "futures")]
+extern crate futures_core;
+#[cfg(feature = "wasm")]
+extern crate js_sys;
+#[cfg(feature = "lambda")]
+extern crate lambda_runtime;
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "metrics")]
+extern crate metrics;
+#[cfg(feature = "opentelemetry")]
+extern crate opentelemetry;
+#[cfg(feature = "tower")]
+extern crate pin_project_lite;
+#[cfg(feature = "pyo3")]
+extern crate pyo3;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "test-util")]
+extern crate regex;
+#[cfg(feature = "rocket")]
+extern crate rocket;
+#[cfg(feature = "sentry")]
+extern crate sentry_core;
 #[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
 extern crate serde;
+#[cfg(feature = "slog")]
+extern crate slog;
+#[cfg(feature = "macros")]
+extern crate throw_macros;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(feature = "tonic")]
+extern crate tonic;
+#[cfg(feature = "tower")]
+extern crate tower_layer;
+#[cfg(feature = "tower")]
+extern crate tower_service;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "tracing")]
+extern crate tracing_error;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+#[cfg(feature = "actix-web")]
+mod actix_response;
+#[cfg(feature = "axum")]
+mod axum_response;
+#[cfg(feature = "backtrace")]
+mod backtrace;
+#[cfg(feature = "std")]
+mod catch_panic;
+#[cfg(feature = "crash-dump")]
+mod crash_dump;
+mod diagnostic;
+#[cfg(feature = "ecs")]
+mod ecs;
+#[cfg(feature = "metrics")]
+mod error_metrics;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fmt;
+mod future;
+#[cfg(feature = "gelf")]
+mod gelf;
+#[cfg(feature = "async-graphql")]
+mod graphql;
+#[cfg(feature = "tonic")]
+pub mod grpc;
+#[cfg(feature = "std")]
+mod hook;
+pub mod http;
+mod iter;
+#[cfg(all(feature = "std", feature = "serde-1-std"))]
+mod json;
+#[cfg(feature = "lambda")]
+mod lambda;
+#[cfg(feature = "log")]
+mod logging;
+mod multi;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+#[cfg(feature = "std")]
+mod panic_hook;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod parse;
+#[cfg(feature = "problem-json")]
+mod problem;
+#[cfg(feature = "pyo3")]
+mod python;
+#[cfg(feature = "std")]
+pub mod report;
+#[cfg(feature = "rocket")]
+mod rocket_response;
+#[cfg(feature = "schemars")]
+mod schema;
+#[cfg(feature = "std")]
+mod scope;
+#[cfg(feature = "sentry")]
+mod sentry;
 #[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
-#[macro_use]
-extern crate serde_derive;
+pub mod ser;
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+mod serde_impls;
+#[cfg(feature = "slog")]
+mod slog_values;
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "system-context")]
+mod system_context;
+#[cfg(feature = "tokio")]
+pub mod task;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "tower")]
+pub mod tower;
+#[cfg(feature = "tracing")]
+mod tracing_events;
+mod warnings;
+#[cfg(feature = "wasm")]
+mod wasm;
 
+#[cfg(feature = "backtrace")]
+pub use crate::backtrace::set_force_backtrace;
 #[cfg(feature = "std")]
-mod core {
-    pub use std::fmt;
-    pub use std::result;
-}
+#[doc(hidden)]
+pub use crate::catch_panic::__catch_panic;
+#[cfg(feature = "std")]
+pub use crate::catch_panic::PanicError;
+#[cfg(feature = "crash-dump")]
+pub use crate::crash_dump::CrashDump;
+pub use crate::diagnostic::{Severity, ThrowDiagnostic};
+pub use crate::future::{ThrowFutureExt, UpFuture};
+pub use crate::iter::{MapThrow, ThrowIteratorExt};
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use crate::logging::__log;
+pub use crate::multi::MultiError;
+pub use crate::warnings::Warnings;
 
-use core::fmt;
+#[cfg(feature = "macros")]
+pub use throw_macros::{context_args, throws, trace, ThrowContext, ThrowDiagnostic, UpFrom};
 
+#[cfg(feature = "std")]
+pub use crate::hook::{set_hook, take_hook, ErrorPointEvent, Hook, SamplingHook};
+#[cfg(feature = "std")]
+pub use crate::panic_hook::{install_panic_hook, render_panic_payload};
+#[cfg(feature = "rayon")]
+pub use crate::parallel::ThrowParallelIteratorExt;
+#[cfg(feature = "std")]
+pub use crate::report::{
+    report, set_report_handler, DefaultReportHandler, Exit, ExitStatus, Report, ReportHandler,
+};
+#[cfg(feature = "std")]
+pub use crate::scope::{scope, ContextScope, Scoped};
+#[cfg(feature = "futures")]
+pub use crate::stream::{ThrowStreamExt, TryCollectThrow, UpThrow};
+#[cfg(feature = "system-context")]
+pub use crate::system_context::set_system_context;
+
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
-
-#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
-use serde::ser::{Serialize, SerializeStruct, Serializer};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
 
 /// Types allowed to be value in the context vector
 #[derive(Debug, Clone)]
-#[cfg_attr(
-    any(feature = "serde-1", feature = "serde-1-std"),
-    derive(Serialize)
-)]
-#[cfg_attr(
-    any(feature = "serde-1", feature = "serde-1-std"),
-    serde(untagged)
-)]
 pub enum ThrowContextValues {
     /// Boolean context value
     Bool(bool),
@@ -260,8 +650,8 @@ pub enum ThrowContextValues {
     StaticStr(&'static str),
 }
 
-impl fmt::Display for ThrowContextValues {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl core::fmt::Display for ThrowContextValues {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match *self {
             ThrowContextValues::Bool(ref x) => write!(f, "{}", x),
             ThrowContextValues::Int8(ref x) => write!(f, "{}", x),
@@ -280,75 +670,75 @@ impl fmt::Display for ThrowContextValues {
     }
 }
 
-impl Into<ThrowContextValues> for u8 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Uint8(self)
+impl From<u8> for ThrowContextValues {
+    fn from(v: u8) -> ThrowContextValues {
+        ThrowContextValues::Uint8(v)
     }
 }
 
-impl Into<ThrowContextValues> for i8 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Int8(self)
+impl From<i8> for ThrowContextValues {
+    fn from(v: i8) -> ThrowContextValues {
+        ThrowContextValues::Int8(v)
     }
 }
 
-impl Into<ThrowContextValues> for u16 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Uint16(self)
+impl From<u16> for ThrowContextValues {
+    fn from(v: u16) -> ThrowContextValues {
+        ThrowContextValues::Uint16(v)
     }
 }
 
-impl Into<ThrowContextValues> for i16 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Int16(self)
+impl From<i16> for ThrowContextValues {
+    fn from(v: i16) -> ThrowContextValues {
+        ThrowContextValues::Int16(v)
     }
 }
 
-impl Into<ThrowContextValues> for u32 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Uint32(self)
+impl From<u32> for ThrowContextValues {
+    fn from(v: u32) -> ThrowContextValues {
+        ThrowContextValues::Uint32(v)
     }
 }
 
-impl Into<ThrowContextValues> for i32 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Int32(self)
+impl From<i32> for ThrowContextValues {
+    fn from(v: i32) -> ThrowContextValues {
+        ThrowContextValues::Int32(v)
     }
 }
 
-impl Into<ThrowContextValues> for u64 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Uint64(self)
+impl From<u64> for ThrowContextValues {
+    fn from(v: u64) -> ThrowContextValues {
+        ThrowContextValues::Uint64(v)
     }
 }
 
-impl Into<ThrowContextValues> for i64 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Int64(self)
+impl From<i64> for ThrowContextValues {
+    fn from(v: i64) -> ThrowContextValues {
+        ThrowContextValues::Int64(v)
     }
 }
 
-impl Into<ThrowContextValues> for f32 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Float32(self)
+impl From<f32> for ThrowContextValues {
+    fn from(v: f32) -> ThrowContextValues {
+        ThrowContextValues::Float32(v)
     }
 }
 
-impl Into<ThrowContextValues> for f64 {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::Float64(self)
+impl From<f64> for ThrowContextValues {
+    fn from(v: f64) -> ThrowContextValues {
+        ThrowContextValues::Float64(v)
     }
 }
 
-impl<'a> Into<ThrowContextValues> for &'static str {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::StaticStr(self)
+impl From<&'static str> for ThrowContextValues {
+    fn from(v: &'static str) -> ThrowContextValues {
+        ThrowContextValues::StaticStr(v)
     }
 }
 
-impl Into<ThrowContextValues> for String {
-    fn into(self) -> ThrowContextValues {
-        ThrowContextValues::String(self)
+impl From<String> for ThrowContextValues {
+    fn from(v: String) -> ThrowContextValues {
+        ThrowContextValues::String(v)
     }
 }
 
@@ -356,16 +746,48 @@ impl Into<ThrowContextValues> for String {
 pub type Result<T, E> = core::result::Result<T, Error<E>>;
 
 /// Represents a location at which an error was thrown via throw!()
-#[derive(Debug)]
-#[cfg_attr(
-    any(feature = "serde-1", feature = "serde-1-std"),
-    derive(Serialize)
-)]
+#[derive(Debug, Clone)]
 pub struct ErrorPoint {
     line: u32,
     column: u32,
     module_path: &'static str,
     file: &'static str,
+    metadata: Option<Box<PointMetadata>>,
+}
+
+/// Optional information about an ErrorPoint, boxed to keep points without it small. Each part is
+/// only recorded when the matching feature is enabled, and is left out of serialized points when
+/// it isn't recorded.
+#[derive(Debug, Clone, Default)]
+struct PointMetadata {
+    /// The module path and file of a point which was deserialized or parsed, rather than created
+    /// by the macros, which aren't `'static`. The point's own fields are empty strings then.
+    location: Option<OwnedLocation>,
+    note: Option<Cow<'static, str>>,
+    crate_name: Option<Cow<'static, str>>,
+    crate_version: Option<Cow<'static, str>>,
+    timestamp: Option<core::time::Duration>,
+    thread_id: Option<u64>,
+    thread_name: Option<Cow<'static, str>>,
+}
+
+impl PointMetadata {
+    /// Checks whether any of the information recorded by the features was recorded, as opposed
+    /// to only a note or location.
+    #[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+    fn is_recorded(&self) -> bool {
+        self.crate_name.is_some()
+            || self.crate_version.is_some()
+            || self.timestamp.is_some()
+            || self.thread_id.is_some()
+            || self.thread_name.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OwnedLocation {
+    module_path: String,
+    file: String,
 }
 
 impl ErrorPoint {
@@ -383,14 +805,68 @@ impl ErrorPoint {
 
     /// The module throw!() occurred in, retrieved by module_path!()
     #[inline]
-    pub fn module_path(&self) -> &'static str {
-        self.module_path
+    pub fn module_path(&self) -> &str {
+        match self.owned_location() {
+            Some(location) => &location.module_path,
+            None => self.module_path,
+        }
     }
 
     /// The file throw!() occurred in, retrieved by file!()
     #[inline]
-    pub fn file(&self) -> &'static str {
-        self.file
+    pub fn file(&self) -> &str {
+        match self.owned_location() {
+            Some(location) => &location.file,
+            None => self.file,
+        }
+    }
+
+    /// The note attached to this point with `up!(..., msg: "...")`, if any
+    #[inline]
+    pub fn note(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.note.as_ref())
+            .map(|note| note.as_ref())
+    }
+
+    /// The name of the crate this point is in, if it was recorded
+    #[inline]
+    pub fn crate_name(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.crate_name.as_ref())
+            .map(|name| name.as_ref())
+    }
+
+    /// The version of the crate this point is in, if it was recorded
+    #[inline]
+    pub fn crate_version(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.crate_version.as_ref())
+            .map(|version| version.as_ref())
+    }
+
+    /// When this point was created, as the time since the Unix epoch, if it was recorded
+    #[inline]
+    pub fn timestamp(&self) -> Option<core::time::Duration> {
+        self.metadata.as_ref().and_then(|m| m.timestamp)
+    }
+
+    /// The ID of the thread this point was created on, if it was recorded
+    #[inline]
+    pub fn thread_id(&self) -> Option<u64> {
+        self.metadata.as_ref().and_then(|m| m.thread_id)
+    }
+
+    /// The name of the thread this point was created on, if it was recorded and the thread had one
+    #[inline]
+    pub fn thread_name(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.thread_name.as_ref())
+            .map(|name| name.as_ref())
     }
 
     #[doc(hidden)]
@@ -401,73 +877,218 @@ impl ErrorPoint {
         file: &'static str,
     ) -> ErrorPoint {
         ErrorPoint {
-            line: line,
-            column: column,
-            module_path: module_path,
-            file: file,
+            line,
+            column,
+            module_path,
+            file,
+            metadata: None,
+        }
+    }
+
+    /// Creates a point with a module path and file which aren't `'static`, such as a deserialized
+    /// or parsed point.
+    pub(crate) fn owned(
+        line: u32,
+        column: u32,
+        module_path: String,
+        file: String,
+        metadata: Option<Box<PointMetadata>>,
+    ) -> ErrorPoint {
+        let mut metadata = metadata.unwrap_or_default();
+        metadata.location = Some(OwnedLocation { module_path, file });
+        ErrorPoint {
+            line,
+            column,
+            module_path: "",
+            file: "",
+            metadata: Some(metadata),
+        }
+    }
+
+    fn owned_location(&self) -> Option<&OwnedLocation> {
+        self.metadata.as_ref().and_then(|m| m.location.as_ref())
+    }
+
+    /// The module path and file of this point, if they're static strings rather than ones parsed or
+    /// deserialized at runtime.
+    #[cfg_attr(not(any(feature = "slog", feature = "std")), allow(dead_code))]
+    pub(crate) fn static_location(&self) -> Option<(&'static str, &'static str)> {
+        match self.owned_location() {
+            Some(_) => None,
+            None => Some((self.module_path, self.file)),
+        }
+    }
+
+    /// Creates a point at the location of a `#[track_caller]` function's caller. The module it's
+    /// in can't be found from there, so its module path is `<unknown>`.
+    pub(crate) fn from_caller(location: &'static core::panic::Location<'static>) -> ErrorPoint {
+        ErrorPoint::__construct(
+            location.line(),
+            location.column(),
+            "<unknown>",
+            location.file(),
+        )
+    }
+
+    #[cfg(feature = "timestamps")]
+    fn record_timestamp(&mut self) {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
+            self.metadata.get_or_insert_with(Default::default).timestamp = Some(now);
+        }
+    }
+
+    #[cfg(feature = "thread-info")]
+    fn record_thread(&mut self) {
+        thread_local! {
+            // std's `ThreadId::as_u64()` is unstable, so the number is taken from its Debug output
+            static THREAD_ID: Option<u64> = format!("{:?}", std::thread::current().id())
+                .strip_prefix("ThreadId(")
+                .and_then(|id| id.strip_suffix(')'))
+                .and_then(|id| id.parse().ok());
         }
+        let metadata = self.metadata.get_or_insert_with(Default::default);
+        metadata.thread_id = THREAD_ID.with(|id| *id);
+        metadata.thread_name = std::thread::current()
+            .name()
+            .map(|name| Cow::Owned(name.to_owned()));
+    }
+
+    #[doc(hidden)]
+    pub fn __with_crate(
+        mut self,
+        name: Option<&'static str>,
+        version: Option<&'static str>,
+    ) -> ErrorPoint {
+        let metadata = self.metadata.get_or_insert_with(Default::default);
+        metadata.crate_name = name.map(Cow::Borrowed);
+        metadata.crate_version = version.map(Cow::Borrowed);
+        self
+    }
+
+    #[doc(hidden)]
+    pub fn __with_note<N: Into<Cow<'static, str>>>(mut self, note: N) -> ErrorPoint {
+        self.metadata.get_or_insert_with(Default::default).note = Some(note.into());
+        self
     }
 }
 
 /// represent a key-value pair
 #[derive(Debug, Clone)]
-#[cfg_attr(
-    any(feature = "serde-1", feature = "serde-1-std"),
-    derive(Serialize)
-)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct KvPair {
-    key: &'static str,
+    key: Cow<'static, str>,
     value: ThrowContextValues,
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    point: Option<usize>,
 }
 
 impl KvPair {
     /// Creates a new key value pair
     fn new(key: &'static str, value: ThrowContextValues) -> KvPair {
-        KvPair { key, value }
+        KvPair {
+            key: Cow::Borrowed(key),
+            value,
+            point: None,
+        }
     }
 
     /// Retrieve the key associated with this `KvPair`.
-    pub fn key(&self) -> &'static str {
-        self.key
+    pub fn key(&self) -> &str {
+        &self.key
     }
 
     /// Retrieve the value associated with this `KvPair`.
     pub fn value(&self) -> &ThrowContextValues {
         &self.value
     }
+
+    /// Retrieve the index in `Error::points()` of the point this `KvPair` was added at, or `None`
+    /// if it was added before the error was first thrown.
+    pub fn point(&self) -> Option<usize> {
+        self.point
+    }
+}
+
+/// A type whose values can be attached to errors as key/value context.
+///
+/// With the `macros` feature, this can be implemented with `#[derive(ThrowContext)]`, which adds
+/// each named field as a key/value pair keyed by the field's name. Fields can be left out with
+/// `#[throw(skip)]`, or given a different key with `#[throw(rename = "key")]`.
+pub trait ThrowContext {
+    /// Adds this value's key/value pairs to the given error.
+    fn add_context_to<E>(&self, error: &mut Error<E>);
 }
 
 /// Represents an error. Stores an original error of type E, and any number of ErrorPoints at
 /// which the error was propagated.
-
 pub struct Error<E> {
     points: Vec<ErrorPoint>,
     context: Vec<KvPair>,
+    metadata: Option<Box<Metadata>>,
     error: E,
 }
 
-#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
-impl<E: fmt::Display> Serialize for Error<E> {
-    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let mut state = serializer.serialize_struct("Error", 3)?;
+/// Diagnostic metadata set on an `Error`, boxed to keep errors without it small.
+#[derive(Default)]
+struct Metadata {
+    code: Option<Cow<'static, str>>,
+    severity: Option<Severity>,
+    help: Option<Cow<'static, str>>,
+    #[cfg(feature = "tracing")]
+    span_trace: Option<tracing_error::SpanTrace>,
+    #[cfg(feature = "backtrace")]
+    backtrace: Option<std::backtrace::Backtrace>,
+}
 
-        state.serialize_field("points", &self.points)?;
-        state.serialize_field("context", &self.context)?;
-        state.serialize_field::<&str>("error", &format!("{}", self.error).as_str())?;
-        state.end()
+impl Metadata {
+    /// Creates metadata with the given diagnostics, such as for a deserialized error.
+    fn new(
+        code: Option<Cow<'static, str>>,
+        severity: Option<Severity>,
+        help: Option<Cow<'static, str>>,
+    ) -> Metadata {
+        Metadata {
+            code,
+            severity,
+            help,
+            #[cfg(feature = "tracing")]
+            span_trace: None,
+            #[cfg(feature = "backtrace")]
+            backtrace: None,
+        }
     }
 }
 
 impl<E> Error<E> {
-    /// Creates a new Error with no ErrorPoints
+    /// Creates a new Error with no ErrorPoints. The error starts out with the key/value pairs of
+    /// any `context_scope!()` alive on this thread.
+    ///
+    /// With the `tracing` feature, this also captures the `tracing` spans the current thread is
+    /// in, if `tracing_error::ErrorLayer` is installed. With the `system-context` feature, it also
+    /// starts out with the system context if enabled with `throw::set_system_context()`. With the
+    /// `backtrace` feature, it captures a backtrace, as described in `Error::backtrace()`.
     pub fn new(error: E) -> Error<E> {
+        #[cfg_attr(not(feature = "std"), allow(unused_mut))]
+        let mut context = Vec::new();
+        #[cfg(feature = "std")]
+        scope::extend_with_scoped_context(&mut context);
+        #[cfg(feature = "system-context")]
+        system_context::extend_if_enabled(&mut context);
+        #[cfg(not(feature = "tracing"))]
+        #[cfg_attr(not(feature = "backtrace"), allow(unused_mut))]
+        let mut metadata = None;
+        #[cfg(feature = "tracing")]
+        #[cfg_attr(not(feature = "backtrace"), allow(unused_mut))]
+        let mut metadata = tracing_events::capture_span_trace();
+        #[cfg(feature = "backtrace")]
+        backtrace::capture_into(&mut metadata);
         Error {
             points: Vec::new(),
-            context: Vec::new(),
-            error: error,
+            context,
+            metadata,
+            error,
         }
     }
 
@@ -479,12 +1100,69 @@ impl<E> Error<E> {
     /// For macro use only
     #[doc(hidden)]
     pub fn add_context<V: Into<ThrowContextValues>>(&mut self, key: &'static str, value: V) {
-        self.context.push(KvPair::new(key, value.into()))
+        let mut pair = KvPair::new(key, value.into());
+        pair.point = self.points.len().checked_sub(1);
+        self.context.push(pair)
+    }
+
+    /// Adds all key/value pairs from a `ThrowContext` value to this error's context.
+    pub fn add_context_from<C: ThrowContext + ?Sized>(&mut self, context: &C) {
+        context.add_context_to(self);
+    }
+
+    /// Gets the code identifying this kind of error, if one has been set.
+    pub fn code(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.code.as_ref())
+            .map(|code| code.as_ref())
+    }
+
+    /// Sets the code identifying this kind of error, such as `"E1042"`.
+    pub fn set_code<C: Into<Cow<'static, str>>>(&mut self, code: C) {
+        self.metadata_mut().code = Some(code.into());
+    }
+
+    /// Gets how severe this error is, if that has been set.
+    pub fn severity(&self) -> Option<Severity> {
+        self.metadata.as_ref().and_then(|m| m.severity)
+    }
+
+    /// Sets how severe this error is.
+    pub fn set_severity(&mut self, severity: Severity) {
+        self.metadata_mut().severity = Some(severity);
+    }
+
+    /// Gets the message suggesting how to fix this error, if one has been set.
+    pub fn help(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.help.as_ref())
+            .map(|help| help.as_ref())
+    }
+
+    /// Sets a message suggesting how to fix this error.
+    pub fn set_help<H: Into<Cow<'static, str>>>(&mut self, help: H) {
+        self.metadata_mut().help = Some(help.into());
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        self.metadata.get_or_insert_with(Default::default)
     }
 
     /// For macro use only
     #[doc(hidden)]
-    pub fn __push_point(&mut self, point: ErrorPoint) {
+    #[cfg_attr(
+        not(any(feature = "thread-info", feature = "timestamps")),
+        allow(unused_mut)
+    )]
+    pub fn __push_point(&mut self, mut point: ErrorPoint) {
+        #[cfg(feature = "timestamps")]
+        point.record_timestamp();
+        #[cfg(feature = "thread-info")]
+        point.record_thread();
+        #[cfg(feature = "metrics")]
+        error_metrics::count_point(self, &point);
         self.points.push(point);
     }
 
@@ -533,142 +1211,1363 @@ impl<E> Error<E> {
         Error {
             points: self.points,
             context: self.context,
+            metadata: self.metadata,
             error: self.error.into(),
         }
     }
 }
 
-impl<E> fmt::Display for Error<E>
+impl<E> core::fmt::Display for Error<E>
 where
-    E: fmt::Display,
+    E: core::fmt::Display,
 {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        try!(write!(fmt, "Error: {}", self.error));
-
-        for kv in self.context.iter().rev() {
-            try!(write!(fmt, "\n\t{}: {}", kv.key(), kv.value(),));
-        }
-
-        for point in self.points.iter().rev() {
-            try!(write!(
-                fmt,
-                "\n\tat {}:{} in {} ({})",
-                point.line(),
-                point.column(),
-                point.module_path(),
-                point.file()
-            ));
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let default = fmt::ErrorFormatter::new();
+        let formatter = fmt::global_formatter().unwrap_or(&default);
+        if f.alternate() {
+            let view = fmt::ErrorView::new(self, &self.error);
+            formatter.write_prefixed(f, |f| view.write_compact(f, formatter))
+        } else {
+            core::fmt::Display::fmt(&self.display_with(formatter), f)
         }
+    }
+}
 
-        Ok(())
+impl<E> core::fmt::Debug for Error<E>
+where
+    E: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt::ErrorView::new(self, &fmt::DebugAsDisplay(&self.error))
+            .write_full(f, &fmt::ErrorFormatter::new())
     }
 }
 
-impl<E> fmt::Debug for Error<E>
+#[cfg(all(feature = "std", not(feature = "nightly")))]
+impl<E> std::error::Error for Error<E>
 where
-    E: fmt::Debug,
+    E: std::error::Error,
 {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        try!(write!(fmt, "Error: {:?}", self.error));
-        for kv in self.context.iter().rev() {
-            try!(write!(fmt, "\n\t{}: {}", kv.key(), kv.value(),));
-        }
-        for point in self.points.iter().rev() {
-            try!(write!(
-                fmt,
-                "\n\tat {}:{} in {} ({})",
-                point.line(),
-                point.column(),
-                point.module_path(),
-                point.file()
-            ));
-        }
+    #[allow(deprecated)]
+    fn description(&self) -> &str {
+        self.error().description()
+    }
 
-        Ok(())
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        Some(self.error())
     }
 }
 
-#[cfg(feature = "std")]
+/// With the `nightly` feature, the inner error, the points and, with the `backtrace` feature, the
+/// captured backtrace can be requested from an `Error` with `std::error::request_ref()`. Anything
+/// else is requested from the inner error.
+#[cfg(all(feature = "std", feature = "nightly"))]
 impl<E> std::error::Error for Error<E>
 where
-    E: std::error::Error
+    E: std::error::Error + 'static,
 {
+    #[allow(deprecated)]
     fn description(&self) -> &str {
         self.error().description()
     }
 
-    fn cause(&self) -> Option<&std::error::Error> {
+    fn cause(&self) -> Option<&dyn std::error::Error> {
         Some(self.error())
     }
+
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        #[cfg(feature = "backtrace")]
+        if let Some(backtrace) = self.backtrace() {
+            request.provide_ref::<std::backtrace::Backtrace>(backtrace);
+        }
+        request
+            .provide_ref::<E>(self.error())
+            .provide_ref::<[ErrorPoint]>(self.points());
+        self.error().provide(request);
+    }
+}
+
+/// Marker error thrown by `throw_unimplemented!()` and `throw_todo!()`, allowing callers to detect
+/// code paths which are not implemented.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotImplemented {
+    todo: bool,
+    message: Option<Cow<'static, str>>,
+}
+
+impl NotImplemented {
+    /// Whether this was thrown by `throw_todo!()` rather than `throw_unimplemented!()`.
+    #[inline]
+    pub fn is_todo(&self) -> bool {
+        self.todo
+    }
+
+    /// The message given to the macro, if any.
+    #[inline]
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(|message| message.as_ref())
+    }
+
+    #[doc(hidden)]
+    pub fn __new(todo: bool, message: Option<Cow<'static, str>>) -> NotImplemented {
+        NotImplemented { todo, message }
+    }
+}
+
+impl core::fmt::Display for NotImplemented {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.todo {
+            fmt.write_str("not yet implemented")?;
+        } else {
+            fmt.write_str("not implemented")?;
+        }
+        if let Some(message) = self.message() {
+            write!(fmt, ": {}", message)?;
+        }
+        Ok(())
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for NotImplemented {}
+
+/// Propagates an error created by `throw!()` upwards, adding a new ErrorPoint at this location.
+///
+/// Takes a `Result<T, throw::Error<E>>`, evaluating to `T` on `Ok`, and returning
+/// `Err(throw::Error<NE>)` with a new ErrorPoint otherwise. Any number of `"key" => value` pairs
+/// may be given after the expression to add context to the error.
+///
+/// A human-readable note describing what this code was doing may be attached to the new
+/// ErrorPoint with `msg: "..."`, given directly after the expression:
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn read_config() -> throw::Result<(), &'static str> {
+///     throw_new!("file not found");
+/// }
+///
+/// fn startup() -> throw::Result<(), &'static str> {
+///     up!(read_config(), msg: "while loading user configuration");
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     let err = startup().unwrap_err().to_string();
+///     assert!(err.contains(" \u{2014} while loading user configuration"), "{}", err);
+/// }
+/// ```
 #[macro_export]
 macro_rules! up {
-    ($e:expr) => (
+    ($e:expr, msg: $msg:expr $(,)*) => (
         match $e {
             Ok(v) => v,
             Err(e) => {
-                // re-assignment for a better error message if up!() is used incorrectly
-                return Err(__with_new_errorpoint!(e.transform()));
+                let hook = $crate::__point_hook!($crate::Error::error(&e));
+                let me = $crate::__with_new_errorpoint!(e.transform(), $msg);
+                hook.run(&me);
+                return Err(me);
             },
         }
     );
-    ($e:expr, $($key:expr => $value:expr),+) => (
+    ($e:expr, msg: $msg:expr, $($key:expr => $value:expr),+ $(,)*) => (
         match $e {
             Ok(v) => v,
             Err(e) => {
-                // re-assignment for a better error message if up!() is used incorrectly
-                let mut me = __with_new_errorpoint!(e.transform());
+                let hook = $crate::__point_hook!($crate::Error::error(&e));
+                let mut me = $crate::__with_new_errorpoint!(e.transform(), $msg);
                 $(
                     me.add_context($key, $value);
                 )*
+                hook.run(&me);
+                return Err(me);
+            },
+        }
+    );
+    ($e:expr $(,)*) => (
+        match $e {
+            Ok(v) => v,
+            Err(e) => {
+                // `Error::error(&e)` for a better error message if up!() is used incorrectly
+                let hook = $crate::__point_hook!($crate::Error::error(&e));
+                let me = $crate::__with_new_errorpoint!(e.transform());
+                hook.run(&me);
+                return Err(me);
+            },
+        }
+    );
+    ($e:expr, $($key:expr => $value:expr),+ $(,)*) => (
+        match $e {
+            Ok(v) => v,
+            Err(e) => {
+                // `Error::error(&e)` for a better error message if up!() is used incorrectly
+                let hook = $crate::__point_hook!($crate::Error::error(&e));
+                let mut me = $crate::__with_new_errorpoint!(e.transform());
+                $(
+                    me.add_context($key, $value);
+                )*
+                hook.run(&me);
                 return Err(me);
             },
         }
     );
 }
 
+/// Like `up!()`, but for propagating a `throw::Error<E>` out of a function which returns the same
+/// error type `E`.
+///
+/// Rather than converting the error with `transform()`, this pushes the new ErrorPoint onto the
+/// existing error in place. This avoids needing type inference to work out the target error type,
+/// and generates less code in the common case where the error type is unchanged. Accepts the same
+/// `msg: "..."` and `"key" => value` arguments as `up!()`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn parse(input: &str) -> throw::Result<u32, &'static str> {
+///     throw_assert!(!input.is_empty(), "empty input");
+///     Ok(input.len() as u32)
+/// }
+///
+/// fn parse_twice(input: &str) -> throw::Result<u32, &'static str> {
+///     let first = up_same!(parse(input));
+///     let second = up_same!(parse(input), msg: "while parsing again", "input" => input.to_owned());
+///     Ok(first + second)
+/// }
+///
+/// fn main() {
+///     assert_eq!(parse_twice("ab").unwrap(), 4);
+///     assert_eq!(parse_twice("").unwrap_err().points().len(), 2);
+/// }
+/// ```
+#[macro_export]
+macro_rules! up_same {
+    ($e:expr, msg: $msg:expr $(,)*) => (
+        match $e {
+            Ok(v) => v,
+            Err(e) => {
+                let hook = $crate::__point_hook!($crate::Error::error(&e));
+                let me = $crate::__with_new_errorpoint!(e, $msg);
+                hook.run(&me);
+                return Err(me);
+            },
+        }
+    );
+    ($e:expr, msg: $msg:expr, $($key:expr => $value:expr),+ $(,)*) => (
+        match $e {
+            Ok(v) => v,
+            Err(e) => {
+                let hook = $crate::__point_hook!($crate::Error::error(&e));
+                let mut me = $crate::__with_new_errorpoint!(e, $msg);
+                $(
+                    me.add_context($key, $value);
+                )*
+                hook.run(&me);
+                return Err(me);
+            },
+        }
+    );
+    ($e:expr $(,)*) => (
+        match $e {
+            Ok(v) => v,
+            Err(e) => {
+                let hook = $crate::__point_hook!($crate::Error::error(&e));
+                let me = $crate::__with_new_errorpoint!(e);
+                hook.run(&me);
+                return Err(me);
+            },
+        }
+    );
+    ($e:expr, $($key:expr => $value:expr),+ $(,)*) => (
+        match $e {
+            Ok(v) => v,
+            Err(e) => {
+                let hook = $crate::__point_hook!($crate::Error::error(&e));
+                let mut me = $crate::__with_new_errorpoint!(e);
+                $(
+                    me.add_context($key, $value);
+                )*
+                hook.run(&me);
+                return Err(me);
+            },
+        }
+    );
+}
+
+/// Like `up!()`, but with an explicit target error type given as the first argument.
+///
+/// `up_into!(NewError, expr)` transforms a `throw::Error<E>` into a `throw::Error<NewError>`,
+/// so a missing `E: Into<NewError>` conversion is reported at this call site rather than through
+/// a failed type inference. Accepts the same `msg: "..."` and `"key" => value` arguments as
+/// `up!()`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// #[derive(Debug)]
+/// struct AppError(String);
+///
+/// impl From<&'static str> for AppError {
+///     fn from(s: &'static str) -> AppError {
+///         AppError(s.to_owned())
+///     }
+/// }
+///
+/// fn inner() -> throw::Result<(), &'static str> {
+///     throw_new!("oops");
+/// }
+///
+/// fn outer() -> throw::Result<(), AppError> {
+///     up_into!(AppError, inner());
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     assert_eq!(outer().unwrap_err().error().0, "oops");
+/// }
+/// ```
+#[macro_export]
+macro_rules! up_into {
+    ($t:ty, $e:expr $(,)*) => (
+        $crate::up_same!(($e).map_err($crate::Error::transform::<$t>))
+    );
+    ($t:ty, $e:expr, $($rest:tt)+) => (
+        $crate::up_same!(($e).map_err($crate::Error::transform::<$t>), $($rest)+)
+    );
+}
+
+/// Partially handles an error created by `throw!()`, by matching on the original error.
+///
+/// Takes a `Result<T, throw::Error<E>>` followed by any number of `pattern => value` arms, which
+/// are matched against the original error (as a `&E`). If an arm matches, the whole macro
+/// evaluates to that arm's value, recovering from the error. Any error not matched by an arm is
+/// propagated upwards like `up!()`, with a new ErrorPoint at this location.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// use std::fs::File;
+/// use std::io::{self, Read};
+///
+/// fn read_log() -> throw::Result<String, io::Error> {
+///     let mut file = throw!(File::open("some_file.log"));
+///     let mut buf = String::new();
+///     throw!(file.read_to_string(&mut buf));
+///     Ok(buf)
+/// }
+///
+/// fn read_log_or_default() -> throw::Result<String, io::Error> {
+///     let contents = rethrow!(read_log(),
+///         e if e.kind() == io::ErrorKind::NotFound => String::new(),
+///     );
+///     Ok(contents)
+/// }
+///
+/// fn main() {
+///     assert_eq!(read_log_or_default().unwrap(), "");
+/// }
+/// ```
+#[macro_export]
+macro_rules! rethrow {
+    ($e:expr, $($pat:pat $(if $guard:expr)? => $recover:expr),+ $(,)*) => (
+        match $e {
+            Ok(v) => v,
+            Err(e) => match e.error() {
+                $(
+                    $pat $(if $guard)? => $recover,
+                )+
+                _ => {
+                    let hook = $crate::__point_hook!($crate::Error::error(&e));
+                    let me = $crate::__with_new_errorpoint!(e.transform());
+                    hook.run(&me);
+                    return Err(me);
+                },
+            },
+        }
+    );
+}
+
+/// Adds key/value pairs to every `throw::Error` created on this thread until the end of the
+/// enclosing block.
+///
+/// Takes any number of `"key" => value` pairs, like the other macros. Only available with the
+/// `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn handle_request() -> throw::Result<(), &'static str> {
+///     throw_new!("oops");
+/// }
+///
+/// fn main() {
+///     let err = {
+///         context_scope!("request_id" => 42);
+///         handle_request().unwrap_err()
+///     };
+///     assert!(err.to_string().contains("request_id: 42"));
+///     assert!(handle_request().unwrap_err().get_context().is_empty());
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! context_scope {
+    ($($key:expr => $value:expr),+ $(,)*) => (
+        let _throw_context_scope = {
+            let mut scope = $crate::ContextScope::enter();
+            $(
+                scope.add_context($key, $value);
+            )+
+            scope
+        };
+    );
+}
+
+/// Evaluates a block in which `throw!()`, `up!()` and the other macros can be used, producing a
+/// `throw::Result` rather than returning from the enclosing function.
+///
+/// The block must evaluate to a `Result`, and any early return from a macro (or from `?` or
+/// `return`) becomes the value of the whole `catch!` expression. As the block is run in a closure,
+/// `break` and `continue` cannot refer to loops outside of it.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn main() {
+///     let result: throw::Result<u32, std::num::ParseIntError> = catch! {
+///         let a: u32 = throw!("12".parse());
+///         let b: u32 = throw!("x".parse(), "input" => "x");
+///         Ok(a + b)
+///     };
+///     assert_eq!(result.unwrap_err().points().len(), 1);
+/// }
+/// ```
+#[macro_export]
+macro_rules! catch {
+    ($($body:tt)*) => (
+        $crate::__catch(|| { $($body)* })
+    );
+}
+
+/// Runs a closure, catching any panic in it with `std::panic::catch_unwind()` and converting it
+/// into a `throw::Error<throw::PanicError>` with an ErrorPoint at this location.
+///
+/// The `PanicError` carries the panic's message, and the location it happened at. As with
+/// `catch_unwind()`, the closure must be `UnwindSafe`, and the panic is still reported by the
+/// installed panic hook.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn main() {
+///     let error = catch_panic!(|| -> u32 { panic!("plugin failed") }).unwrap_err();
+///     assert_eq!(error.error().message(), "plugin failed");
+///     assert!(error.error().location().is_some());
+///     assert_eq!(error.points().len(), 1);
+///
+///     assert_eq!(catch_panic!(|| 2 + 2).unwrap(), 4);
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! catch_panic {
+    ($f:expr $(,)*) => {
+        $crate::__catch_panic($f, $crate::__errorpoint!())
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use crate::Error;
+    #[cfg(not(feature = "std"))]
+    use alloc::string::String;
+
+    // Used by `#[throw::trace]` to decide between `up!()` and `throw!()` behavior for each `?`.
+    // Calling `(&error).__throw_trace_kind()` picks `UpKind` if `error` is already a
+    // `throw::Error`, as that impl doesn't need an autoref, and `ThrowKind` otherwise.
+
+    pub struct Up;
+
+    pub struct Throw;
+
+    pub trait UpKind {
+        #[inline]
+        fn __throw_trace_kind(&self) -> Up {
+            Up
+        }
+    }
+
+    impl<E> UpKind for Error<E> {}
+
+    pub trait ThrowKind {
+        #[inline]
+        fn __throw_trace_kind(&self) -> Throw {
+            Throw
+        }
+    }
+
+    impl<E> ThrowKind for &E {}
+
+    impl Up {
+        #[inline]
+        pub fn into_error<E>(self, error: Error<E>) -> Error<E> {
+            error
+        }
+    }
+
+    impl Throw {
+        #[inline]
+        pub fn into_error<E>(self, error: E) -> Error<E> {
+            Error::new(error)
+        }
+    }
+
+    // Used by the macros recording points to call the hook set with `throw::set_hook()`. The
+    // inner error is rendered before it's converted to the function's error type, which may not
+    // be known yet. Calling `(&error).__throw_point_hook()` with a `&E` picks `DisplayHook` if
+    // `E: Display`, as that impl doesn't need an autoref, and `OpaqueHook` otherwise.
+
+    pub struct PointHook {
+        #[cfg(feature = "std")]
+        rendered: Option<String>,
+    }
+
+    impl PointHook {
+        #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+        pub fn new(error: Option<&dyn core::fmt::Display>) -> PointHook {
+            PointHook {
+                #[cfg(feature = "std")]
+                rendered: error
+                    .filter(|_| crate::hook::is_active())
+                    .map(|error| error.to_string()),
+            }
+        }
+
+        #[inline]
+        #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+        pub fn run<E>(self, error: &Error<E>) {
+            #[cfg(feature = "std")]
+            crate::hook::run(error, self.rendered.as_deref());
+        }
+    }
+
+    pub trait DisplayHook {
+        fn __throw_point_hook(&self) -> PointHook;
+    }
+
+    impl<E: core::fmt::Display> DisplayHook for &E {
+        #[inline]
+        fn __throw_point_hook(&self) -> PointHook {
+            PointHook::new(Some(*self))
+        }
+    }
+
+    pub trait OpaqueHook {
+        #[inline]
+        fn __throw_point_hook(&self) -> PointHook {
+            PointHook::new(None)
+        }
+    }
+
+    impl<E> OpaqueHook for &&E {}
+
+    // Used by `#[throw::context_args]`, which can't rely on `format!` being in scope.
+
+    pub fn display<T: ?Sized + core::fmt::Display>(value: &T) -> String {
+        format!("{}", value)
+    }
+
+    pub fn debug<T: ?Sized + core::fmt::Debug>(value: &T) -> String {
+        format!("{:?}", value)
+    }
+}
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn __catch<T, F: FnOnce() -> T>(f: F) -> T {
+    f()
+}
+
+/// Calls a closure returning a `throw::Result` up to the given number of times, until it succeeds.
+///
+/// Evaluates to the first successful value. If every attempt fails, the error from the last
+/// attempt is propagated upwards like `up!()`, with the ErrorPoints of all attempts merged into it
+/// in the order they happened. Each attempt's points are followed by an ErrorPoint at this location
+/// noting the attempt number, with the number also added to the context at that point under the
+/// `"attempt"` key, so the context added by the earlier attempts, which is kept, can be told
+/// apart. The total number of attempts is added to the error's context under the `"attempts"`
+/// key. The hook set with `throw::set_hook()` is run for each attempt's ErrorPoint.
+///
+/// The closure is always called at least once, so a number of attempts of `0` is treated as `1`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn fetch(attempt: &mut u32) -> throw::Result<&'static str, &'static str> {
+///     *attempt += 1;
+///     throw_assert!(*attempt >= 3, "connection reset");
+///     Ok("contents")
+/// }
+///
+/// fn fetch_with_retries(attempts: u32) -> throw::Result<&'static str, &'static str> {
+///     let mut attempt = 0;
+///     let contents = retry!(attempts, || fetch(&mut attempt));
+///     Ok(contents)
+/// }
+///
+/// fn main() {
+///     assert_eq!(fetch_with_retries(3).unwrap(), "contents");
+///     let err = fetch_with_retries(2).unwrap_err();
+///     assert_eq!(err.points().len(), 4);
+///     assert_eq!(err.points()[3].note(), Some("attempt 2 of 2 failed"));
+/// }
+/// ```
+#[macro_export]
+macro_rules! retry {
+    ($attempts:expr, $f:expr $(,)*) => {
+        match $crate::__retry($attempts, $crate::__errorpoint!(), $f, |e| {
+            $crate::__point_hook!($crate::Error::error(e))
+        }) {
+            Ok(v) => v,
+            Err(e) => return Err(e.transform()),
+        }
+    };
+}
+
+#[doc(hidden)]
+pub fn __retry<T, E, F, H>(attempts: u32, point: ErrorPoint, mut f: F, hook: H) -> Result<T, E>
+where
+    F: FnMut() -> Result<T, E>,
+    H: Fn(&Error<E>) -> __private::PointHook,
+{
+    let attempts = attempts.max(1);
+    let mut points = Vec::new();
+    let mut context = Vec::new();
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Ok(v) => return Ok(v),
+            Err(mut e) => {
+                // the earlier attempts' points and context come first
+                let offset = points.len();
+                for kv in &mut e.context {
+                    if let Some(ref mut point) = kv.point {
+                        *point += offset;
+                    }
+                }
+                points.append(&mut e.points);
+                context.append(&mut e.context);
+                e.points = points;
+                e.context = context;
+
+                let hook = hook(&e);
+                e.__push_point(
+                    point
+                        .clone()
+                        .__with_note(format!("attempt {} of {} failed", attempt, attempts)),
+                );
+                let mut kv = KvPair::new("attempt", attempt.into());
+                kv.point = Some(e.points.len() - 1);
+                e.context.push(kv);
+                if attempt >= attempts {
+                    e.add_context("attempts", attempt);
+                    hook.run(&e);
+                    return Err(e);
+                }
+                hook.run(&e);
+
+                points = core::mem::take(&mut e.points);
+                context = core::mem::take(&mut e.context);
+                // context not attached to a point, such as scoped context, is added to every
+                // attempt's error, so only the last attempt's is kept
+                context.retain(|kv| kv.point.is_some());
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Always returns a new `throw::Error` created from a `throw::NotImplemented` error, with an
+/// ErrorPoint at this location.
+///
+/// This is a non-panicking alternative to `unimplemented!()`. The enclosing function's error type
+/// must implement `From<throw::NotImplemented>`. A message, and then any number of
+/// `"key" => value` pairs, may optionally be given.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn export_pdf() -> throw::Result<Vec<u8>, throw::NotImplemented> {
+///     throw_unimplemented!("PDF export");
+/// }
+///
+/// fn main() {
+///     let err = export_pdf().unwrap_err();
+///     assert!(!err.error().is_todo());
+///     assert_eq!(err.error().to_string(), "not implemented: PDF export");
+/// }
+/// ```
+#[macro_export]
+macro_rules! throw_unimplemented {
+    ($($args:tt)*) => (
+        $crate::__throw_not_implemented!(false, $($args)*)
+    );
+}
+
+/// Like `throw_unimplemented!()`, but marks the code path as not yet implemented, as `todo!()`
+/// does.
+#[macro_export]
+macro_rules! throw_todo {
+    ($($args:tt)*) => (
+        $crate::__throw_not_implemented!(true, $($args)*)
+    );
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __throw_not_implemented {
+    ($todo:expr $(,)*) => (
+        $crate::throw_new!($crate::NotImplemented::__new($todo, None))
+    );
+    ($todo:expr, $msg:expr $(, $key:expr => $value:expr)* $(,)*) => (
+        $crate::throw_new!(
+            $crate::NotImplemented::__new($todo, Some($msg.into())),
+            $($key => $value,)*
+        )
+    );
+}
+
+/// Returns a new `throw::Error<std::io::Error>` built from `errno` if the given condition is true,
+/// for checking the results of C FFI calls.
+///
+/// `errno` is captured with `std::io::Error::last_os_error()` directly after evaluating the
+/// condition. The raw `errno` value and the stringified condition are added to the error's context
+/// under the `"errno"` and `"expression"` keys. A message may be given after the condition, which
+/// is attached to the new ErrorPoint as a note, followed by any number of `"key" => value` pairs.
+///
+/// Only available with the `std` feature on unix platforms.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// use std::os::raw::c_int;
+///
+/// extern "C" {
+///     fn close(fd: c_int) -> c_int;
+/// }
+///
+/// fn close_fd(fd: c_int) -> throw::Result<(), std::io::Error> {
+///     throw_errno!(unsafe { close(fd) } < 0, "close failed", "fd" => fd);
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     let err = close_fd(-1).unwrap_err();
+///     assert_eq!(err.error().raw_os_error(), Some(9)); // EBADF
+///     assert_eq!(err.points()[0].note(), Some("close failed"));
+/// }
+/// ```
+#[cfg(all(feature = "std", unix))]
+#[macro_export]
+macro_rules! throw_errno {
+    ($($args:tt)*) => (
+        $crate::__throw_os_error!("errno", $($args)*)
+    );
+}
+
+/// Returns a new `throw::Error<std::io::Error>` built from `GetLastError()` if the given condition
+/// is true, for checking the results of Windows API calls.
+///
+/// This is the Windows equivalent of `throw_errno!()`, and takes the same arguments. The raw error
+/// code is added to the error's context under the `"last_error"` key.
+///
+/// Only available with the `std` feature on Windows.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// use std::os::raw::c_void;
+///
+/// extern "system" {
+///     fn CloseHandle(handle: *mut c_void) -> i32;
+/// }
+///
+/// fn close_handle(handle: *mut c_void) -> throw::Result<(), std::io::Error> {
+///     throw_win32!(unsafe { CloseHandle(handle) } == 0, "CloseHandle failed");
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     let err = close_handle(std::ptr::null_mut()).unwrap_err();
+///     assert_eq!(err.error().raw_os_error(), Some(6)); // ERROR_INVALID_HANDLE
+/// }
+/// ```
+#[cfg(all(feature = "std", windows))]
+#[macro_export]
+macro_rules! throw_win32 {
+    ($($args:tt)*) => (
+        $crate::__throw_os_error!("last_error", $($args)*)
+    );
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __throw_os_error {
+    ($code_key:expr, $cond:expr $(,)*) => (
+        if $cond {
+            let mut me = $crate::__with_new_errorpoint!($crate::__os_error());
+            $crate::__add_os_error_context(&mut me, $code_key, stringify!($cond));
+            $crate::__point_hook!(me.error()).run(&me);
+            return Err(me.transform());
+        }
+    );
+    ($code_key:expr, $cond:expr, $msg:expr $(, $key:expr => $value:expr)* $(,)*) => (
+        if $cond {
+            let mut me = $crate::__with_new_errorpoint!($crate::__os_error(), $msg);
+            $crate::__add_os_error_context(&mut me, $code_key, stringify!($cond));
+            $(
+                me.add_context($key, $value);
+            )*
+            $crate::__point_hook!(me.error()).run(&me);
+            return Err(me.transform());
+        }
+    );
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub fn __os_error() -> Error<std::io::Error> {
+    Error::new(std::io::Error::last_os_error())
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub fn __add_os_error_context(
+    me: &mut Error<std::io::Error>,
+    code_key: &'static str,
+    expression: &'static str,
+) {
+    let code = me.error().raw_os_error().unwrap_or(0);
+    me.add_context(code_key, code);
+    me.add_context("expression", expression);
+}
+
+/// Reads an environment variable, returning a new `throw::Error<std::env::VarError>` with an
+/// ErrorPoint at this location if it is missing or not valid unicode.
+///
+/// The variable's name is added to the error's context under the `"variable"` key, followed by
+/// any number of given `"key" => value` pairs. Only available with the `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn config_dir() -> throw::Result<String, std::env::VarError> {
+///     let home = throw_env!("THROW_DOC_EXAMPLE_HOME");
+///     Ok(format!("{}/.config", home))
+/// }
+///
+/// fn main() {
+///     let err = config_dir().unwrap_err().to_string();
+///     assert!(err.contains("variable: THROW_DOC_EXAMPLE_HOME"), "{}", err);
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! throw_env {
+    ($name:expr $(, $key:expr => $value:expr)* $(,)*) => ({
+        let name = $name;
+        match $crate::__env_var(&name) {
+            Ok(v) => v,
+            Err(e) => $crate::throw_new!(e, "variable" => name, $($key => $value,)*),
+        }
+    });
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use std::env::var as __env_var;
+
+/// Measures how long an expression producing a `throw::Result` takes, adding the elapsed time to
+/// the error if it fails.
+///
+/// `timed!("name", expr)` evaluates `expr` in a closure, as `catch!()` does, so the macros can be
+/// used inside of it. On `Ok`, it evaluates to the contained value. On `Err`, the error is
+/// propagated upwards like `up_same!()`, with the elapsed milliseconds added to its context under
+/// the `"name_elapsed_ms"` key. Only available with the `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn run_query() -> throw::Result<Vec<u32>, &'static str> {
+///     throw_new!("connection timed out");
+/// }
+///
+/// fn load_users() -> throw::Result<Vec<u32>, &'static str> {
+///     let users = timed!("db_query", {
+///         let rows = up!(run_query());
+///         Ok(rows)
+///     });
+///     Ok(users)
+/// }
+///
+/// fn main() {
+///     let err = load_users().unwrap_err();
+///     assert_eq!(err.get_context()[0].key(), "db_query_elapsed_ms");
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! timed {
+    ($name:literal, $body:expr $(,)*) => {{
+        let start = $crate::__Instant::now();
+        match $crate::__catch(|| $body) {
+            Ok(v) => v,
+            Err(e) => {
+                let elapsed_ms = start.elapsed().as_millis() as u64;
+                // the error's type may not be inferred yet, so it isn't rendered for the hook
+                let hook = $crate::__private::PointHook::new(None);
+                let mut me = $crate::__with_new_errorpoint!(e);
+                me.add_context(concat!($name, "_elapsed_ms"), elapsed_ms);
+                hook.run(&me);
+                return Err(me);
+            }
+        }
+    }};
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use std::time::Instant as __Instant;
+
+/// Asserts that an expression returns an `Err(throw::Error<E>)`, and evaluates to that error.
+///
+/// Any number of checks may be given after the expression, separated by commas:
+///
+/// - `inner == value` asserts that the original error is equal to `value`
+/// - `inner matches pattern` asserts that the original error matches `pattern`
+/// - `context_has("key")` asserts that the error has a key/value pair with the given key
+/// - `context("key") == "value"` asserts that the error has a key/value pair with the given key,
+///   whose value displays as the given string
+/// - `points == n` asserts that the error has exactly `n` ErrorPoints
+/// - `points_contain("src/parser.rs")` asserts that the file of one of the error's ErrorPoints
+///   contains the given string
+///
+/// Requires `E: Debug`, so that the error can be included in failure messages.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn parse(input: &str) -> throw::Result<u32, &'static str> {
+///     throw_assert!(!input.is_empty(), "empty input", "input" => input.to_owned());
+///     Ok(input.len() as u32)
+/// }
+///
+/// fn main() {
+///     assert_throws!(
+///         parse(""),
+///         inner == "empty input",
+///         context_has("condition"),
+///         context("input") == "",
+///         points == 1,
+///         points_contain("src/lib.rs"),
+///     );
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_throws {
+    ($e:expr $(, $($checks:tt)*)?) => {{
+        let error = match $e {
+            Ok(_) => panic!(
+                "expected `{}` to throw an error, but it returned Ok",
+                stringify!($e)
+            ),
+            Err(e) => e,
+        };
+        $crate::__assert_throws_checks!(error, $($($checks)*)?);
+        error
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_throws_checks {
+    ($error:ident, $(,)*) => {};
+    ($error:ident, inner == $expected:expr $(, $($rest:tt)*)?) => {
+        assert!(
+            *$error.error() == $expected,
+            "expected inner error to equal `{}`, but found error:\n{:?}",
+            stringify!($expected),
+            $error
+        );
+        $crate::__assert_throws_checks!($error, $($($rest)*)?);
+    };
+    ($error:ident, inner matches $pattern:pat $(, $($rest:tt)*)?) => {
+        assert!(
+            match *$error.error() {
+                $pattern => true,
+                _ => false,
+            },
+            "expected inner error to match `{}`, but found error:\n{:?}",
+            stringify!($pattern),
+            $error
+        );
+        $crate::__assert_throws_checks!($error, $($($rest)*)?);
+    };
+    ($error:ident, context_has($key:expr) $(, $($rest:tt)*)?) => {
+        assert!(
+            $error.get_context().iter().any(|kv| kv.key() == $key),
+            "expected error to have context key `{}`, but found error:\n{:?}",
+            $key,
+            $error
+        );
+        $crate::__assert_throws_checks!($error, $($($rest)*)?);
+    };
+    ($error:ident, context($key:expr) == $value:expr $(, $($rest:tt)*)?) => {
+        assert!(
+            $error
+                .get_context()
+                .iter()
+                .any(|kv| kv.key() == $key && kv.value().to_string() == $value),
+            "expected error to have context `{}: {}`, but found error:\n{:?}",
+            $key,
+            $value,
+            $error
+        );
+        $crate::__assert_throws_checks!($error, $($($rest)*)?);
+    };
+    ($error:ident, points == $count:expr $(, $($rest:tt)*)?) => {
+        assert!(
+            $error.points().len() == $count,
+            "expected error to have {} points, but found error:\n{:?}",
+            $count,
+            $error
+        );
+        $crate::__assert_throws_checks!($error, $($($rest)*)?);
+    };
+    ($error:ident, points_contain($file:expr) $(, $($rest:tt)*)?) => {
+        assert!(
+            $error.points().iter().any(|point| point.file().contains($file)),
+            "expected error to have a point in a file containing `{}`, but found error:\n{:?}",
+            $file,
+            $error
+        );
+        $crate::__assert_throws_checks!($error, $($($rest)*)?);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __point_hook {
+    ($error:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::__private::{DisplayHook as _, OpaqueHook as _};
+        (&$error).__throw_point_hook()
+    }};
+}
+
+#[cfg(not(feature = "crate-info"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __errorpoint {
+    () => {
+        $crate::ErrorPoint::__construct(line!(), column!(), module_path!(), file!())
+    };
+}
+
+#[cfg(feature = "crate-info")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __errorpoint {
+    () => {
+        $crate::ErrorPoint::__construct(line!(), column!(), module_path!(), file!()).__with_crate(
+            option_env!("CARGO_CRATE_NAME"),
+            option_env!("CARGO_PKG_VERSION"),
+        )
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __with_new_errorpoint {
     ($e:expr) => {{
         let mut e = $e;
-        e.__push_point($crate::ErrorPoint::__construct(
-            line!(),
-            column!(),
-            module_path!(),
-            file!(),
-        ));
+        e.__push_point($crate::__errorpoint!());
+        e
+    }};
+    ($e:expr, $note:expr) => {{
+        let mut e = $e;
+        e.__push_point($crate::__errorpoint!().__with_note($note));
         e
     }};
 }
 
+/// Unwraps a regular `Result<T, E>`, returning a new `throw::Error` with an ErrorPoint at this
+/// location on `Err`.
+///
+/// Any number of `"key" => value` pairs may be given after the expression to add context to the
+/// error.
 #[macro_export]
 macro_rules! throw {
-    ($e:expr) => (
+    ($e:expr $(,)*) => (
         match $e {
             Ok(v) => v,
-            Err(e) => throw_new!(e),
+            Err(e) => $crate::throw_new!(e),
         }
     );
     ($e:expr, $($key:expr => $value:expr),+ $(,)*) => ({
          match $e {
             Ok(v) => v,
-            Err(e) => throw_new!(e, $($key => $value,)*),
+            Err(e) => $crate::throw_new!(e, $($key => $value,)*),
         }
     });
 }
 
+/// Always returns a new `throw::Error` created from the given error, with an ErrorPoint at this
+/// location.
+///
+/// Any number of `"key" => value` pairs may be given after the error to add context to it.
 #[macro_export]
 macro_rules! throw_new {
-    ($e:expr) => ({
-        return Err(__with_new_errorpoint!($crate::Error::new($e.into())));
+    ($e:expr $(,)*) => ({
+        let e = $e;
+        let hook = $crate::__point_hook!(&e);
+        let me = $crate::__with_new_errorpoint!($crate::Error::new(e.into()));
+        hook.run(&me);
+        return Err(me);
     });
     ($e:expr, $($key:expr => $value:expr),+ $(,)*) => ({
-        let mut me = $crate::Error::new($e.into());
+        let e = $e;
+        let hook = $crate::__point_hook!(&e);
+        let mut me = $crate::__with_new_errorpoint!($crate::Error::new(e.into()));
+        $(
+            me.add_context($key, $value);
+        )*
+        hook.run(&me);
+        return Err(me);
+    });
+}
+
+/// Records a warning in a `throw::Warnings` collector, without returning.
+///
+/// The first argument is the collector, and the second the warning, converted into the
+/// collector's warning type with `Into`. As with `throw_new!()`, the warning gets an ErrorPoint at
+/// this location, and any number of `"key" => value` pairs may be given after it to add context.
+/// Its severity is set to `Severity::Warning`, and the hook set with `throw::set_hook()` is run for
+/// its point.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn main() {
+///     let mut warnings = throw::Warnings::<String>::new();
+///     throw_warn!(warnings, "deprecated key", "key" => "colour");
+///     assert_eq!(warnings.len(), 1);
+///     assert!(warnings.warnings()[0].to_string().starts_with("Warning: deprecated key"));
+/// }
+/// ```
+#[macro_export]
+macro_rules! throw_warn {
+    ($warnings:expr, $w:expr $(, $key:expr => $value:expr)* $(,)*) => ({
+        let w = $w;
+        let hook = $crate::__point_hook!(&w);
+        #[allow(unused_mut)]
+        let mut me = $crate::__with_new_errorpoint!($crate::Error::new(w.into()));
+        $(
+            me.add_context($key, $value);
+        )*
+        me.set_severity($crate::Severity::Warning);
+        hook.run(&me);
+        $warnings.push(me);
+    });
+}
+
+/// Like `throw!()`, but also logs the new error through the `log` crate at the level given as the
+/// first argument.
+///
+/// Only available with the `log` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// # extern crate log;
+/// fn read_config() -> throw::Result<String, std::io::Error> {
+///     let config = throw_logged!(
+///         log::Level::Error,
+///         std::fs::read_to_string("/nonexistent/config.toml"),
+///         "path" => "/nonexistent/config.toml",
+///     );
+///     Ok(config)
+/// }
+///
+/// fn main() {
+///     assert!(read_config().is_err());
+/// }
+/// ```
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! throw_logged {
+    ($level:expr, $e:expr $(, $key:expr => $value:expr)* $(,)*) => (
+        match $e {
+            Ok(v) => v,
+            Err(e) => $crate::throw_new_logged!($level, e $(, $key => $value)*),
+        }
+    );
+}
+
+/// Like `throw_new!()`, but also logs the new error through the `log` crate at the level given as
+/// the first argument.
+///
+/// Only available with the `log` feature.
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! throw_new_logged {
+    ($level:expr, $e:expr $(, $key:expr => $value:expr)* $(,)*) => ({
+        let e = $e;
+        let hook = $crate::__point_hook!(&e);
+        #[allow(unused_mut)]
+        let mut me = $crate::__with_new_errorpoint!($crate::Error::new(e.into()));
         $(
             me.add_context($key, $value);
         )*
-        return Err(__with_new_errorpoint!(me));
+        hook.run(&me);
+        $crate::__log(&me, $level);
+        return Err(me);
     });
 }
+
+/// Like `throw!()`, but with an explicit target error type given as the first argument.
+///
+/// `throw_into!(NewError, expr)` converts the error of a `Result<T, E>` into `NewError` before
+/// wrapping it, so a missing `E: Into<NewError>` conversion is reported at this call site. Any
+/// number of `"key" => value` pairs may be given after the expression to add context to the
+/// error.
+#[macro_export]
+macro_rules! throw_into {
+    ($t:ty, $e:expr $(,)*) => (
+        $crate::throw!(($e).map_err(<_ as Into<$t>>::into))
+    );
+    ($t:ty, $e:expr, $($key:expr => $value:expr),+ $(,)*) => (
+        $crate::throw!(($e).map_err(<_ as Into<$t>>::into), $($key => $value,)*)
+    );
+}
+
+/// Destructures a value with a refutable pattern, returning a new `throw::Error` created from the
+/// given error, with an ErrorPoint at this location, if the pattern doesn't match.
+///
+/// `guard!(let Pattern = expr, error)` binds the variables in `Pattern` in the enclosing scope,
+/// like `let Pattern = expr else { throw_new!(error) };`. Any number of `"key" => value` pairs may
+/// be given after the error to add context to it, and these may refer to `expr` if it is a
+/// variable.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// #[derive(Debug)]
+/// enum State {
+///     Loaded(String),
+///     Loading,
+/// }
+///
+/// fn config(state: State) -> throw::Result<String, &'static str> {
+///     guard!(let State::Loaded(cfg) = state, "config not loaded", "state" => format!("{:?}", state));
+///     Ok(cfg)
+/// }
+///
+/// fn main() {
+///     assert_eq!(config(State::Loaded("cfg".to_owned())).unwrap(), "cfg");
+///     let err = config(State::Loading).unwrap_err().to_string();
+///     assert!(err.contains("state: Loading"), "{}", err);
+/// }
+/// ```
+#[macro_export]
+macro_rules! guard {
+    (let $pat:pat = $e:expr, $err:expr $(, $key:expr => $value:expr)* $(,)*) => {
+        let $pat = $e else {
+            $crate::throw_new!($err, $($key => $value,)*);
+        };
+    };
+}
+
+/// Unwraps an `Option<T>`, returning a new `throw::Error` created from the given error, with an
+/// ErrorPoint at this location, on `None`.
+///
+/// Any number of `"key" => value` pairs may be given after the error to add context to it.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn find_user(id: u32) -> throw::Result<&'static str, &'static str> {
+///     let users = ["alice", "bob"];
+///     let user = throw_opt!(users.get(id as usize), "missing user record", "user_id" => id);
+///     Ok(user)
+/// }
+///
+/// fn main() {
+///     assert_eq!(find_user(1).unwrap(), "bob");
+///     assert_eq!(*find_user(2).unwrap_err().error(), "missing user record");
+/// }
+/// ```
+#[macro_export]
+macro_rules! throw_opt {
+    ($e:expr, $err:expr $(,)*) => (
+        match $e {
+            Some(v) => v,
+            None => $crate::throw_new!($err),
+        }
+    );
+    ($e:expr, $err:expr, $($key:expr => $value:expr),+ $(,)*) => (
+        match $e {
+            Some(v) => v,
+            None => $crate::throw_new!($err, $($key => $value,)*),
+        }
+    );
+}
+
+/// Returns a new `throw::Error` created from the given error, with an ErrorPoint at this location,
+/// if the given condition is false.
+///
+/// The stringified condition is added to the error's context under the `"condition"` key. Any
+/// number of additional `"key" => value` pairs may be given after the error.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn check_len(buf: &[u8]) -> throw::Result<(), &'static str> {
+///     throw_assert!(buf.len() >= 4, "buffer too short", "len" => buf.len() as u64);
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     assert!(check_len(&[0; 8]).is_ok());
+///     let err = check_len(&[0; 2]).unwrap_err().to_string();
+///     assert!(err.contains("condition: buf.len() >= 4"), "mangled error message: {}", err);
+/// }
+/// ```
+#[macro_export]
+macro_rules! throw_assert {
+    ($cond:expr, $err:expr $(,)*) => (
+        if !$cond {
+            $crate::throw_new!($err, "condition" => stringify!($cond));
+        }
+    );
+    ($cond:expr, $err:expr, $($key:expr => $value:expr),+ $(,)*) => (
+        if !$cond {
+            $crate::throw_new!($err, "condition" => stringify!($cond), $($key => $value,)*);
+        }
+    );
+}
+
+/// Like `throw_assert!()`, but only checked in builds with debug assertions enabled.
+///
+/// As with `debug_assert!()`, the condition is not evaluated in release builds.
+#[macro_export]
+macro_rules! throw_debug_assert {
+    ($($arg:tt)*) => (
+        if cfg!(debug_assertions) {
+            $crate::throw_assert!($($arg)*);
+        }
+    );
+}
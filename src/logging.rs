@@ -0,0 +1,108 @@
+use core::fmt;
+
+use log::kv::{self, Key, Source, ToValue, Value, VisitSource};
+use log::{Level, Record};
+
+use crate::fmt::StackTrace;
+use crate::{Error, ThrowContextValues};
+
+impl<E: fmt::Display> Error<E> {
+    /// Logs this error through the `log` facade at the given level.
+    ///
+    /// The record's message is the inner error's Display output. The rest of the error is
+    /// attached as structured key/value pairs, so that backends supporting them can index each
+    /// one:
+    ///
+    /// - each key/value pair of context, under its own key
+    /// - `error.code`: the error's code, from `Error::code()`
+    /// - `error.stack_trace`: one line for each point, most recent first, as in Display output
+    ///
+    /// The record's target, file and line are those of the error's newest point, so that log
+    /// filters match the module the error was last propagated through. Errors without points are
+    /// logged with the target `throw`.
+    ///
+    /// Only available with the `log` feature.
+    ///
+    /// ```
+    /// # extern crate log;
+    /// # extern crate throw;
+    /// # #[cfg(feature = "log")]
+    /// # fn main() {
+    /// let mut error = throw::Error::new("upload failed");
+    /// error.add_context("bytes", 1024);
+    /// error.log(log::Level::Warn);
+    /// # }
+    /// # #[cfg(not(feature = "log"))]
+    /// # fn main() {}
+    /// ```
+    pub fn log(&self, level: Level) {
+        if level > log::STATIC_MAX_LEVEL || level > log::max_level() {
+            return;
+        }
+        let point = self.points().last();
+        let key_values = KeyValues {
+            error: self,
+            stack_trace: StackTrace(self.points()),
+        };
+        log::logger().log(
+            &Record::builder()
+                .args(format_args!("{}", self.error()))
+                .level(level)
+                .target(point.map_or("throw", |point| point.module_path()))
+                .module_path(point.map(|point| point.module_path()))
+                .file(point.map(|point| point.file()))
+                .line(point.map(|point| point.line()))
+                .key_values(&key_values)
+                .build(),
+        );
+    }
+}
+
+#[doc(hidden)]
+pub fn __log<E: fmt::Display>(error: &Error<E>, level: Level) {
+    error.log(level);
+}
+
+/// The key/value pairs attached to an error's log records.
+struct KeyValues<'a, E> {
+    error: &'a Error<E>,
+    stack_trace: StackTrace<'a>,
+}
+
+impl<'a, E> Source for KeyValues<'a, E> {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), kv::Error> {
+        for kv in self.error.get_context() {
+            visitor.visit_pair(Key::from_str(kv.key()), kv.value().to_value())?;
+        }
+        if let Some(code) = self.error.code() {
+            visitor.visit_pair(Key::from_str("error.code"), Value::from(code))?;
+        }
+        if !self.stack_trace.0.is_empty() {
+            visitor.visit_pair(
+                Key::from_str("error.stack_trace"),
+                Value::from_display(&self.stack_trace),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ToValue for ThrowContextValues {
+    fn to_value(&self) -> Value<'_> {
+        match *self {
+            ThrowContextValues::Bool(x) => Value::from(x),
+            ThrowContextValues::Int8(x) => Value::from(x),
+            ThrowContextValues::Uint8(x) => Value::from(x),
+            ThrowContextValues::Int16(x) => Value::from(x),
+            ThrowContextValues::Uint16(x) => Value::from(x),
+            ThrowContextValues::Int32(x) => Value::from(x),
+            ThrowContextValues::Uint32(x) => Value::from(x),
+            ThrowContextValues::Int64(x) => Value::from(x),
+            ThrowContextValues::Uint64(x) => Value::from(x),
+            ThrowContextValues::Float32(x) => Value::from(x),
+            ThrowContextValues::Float64(x) => Value::from(x),
+            ThrowContextValues::String(ref x) => Value::from(&**x),
+            ThrowContextValues::StaticStr(x) => Value::from(x),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::iter::FromIterator;
+use core::slice;
+
+use crate::Error;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::{self, Vec};
+#[cfg(feature = "std")]
+use std::vec;
+
+/// Several errors reported together, such as every failure of a batch of operations.
+///
+/// Created by `ThrowIteratorExt::collect_throw`, which collects every error of an iterator rather
+/// than stopping at the first, or from errors with `MultiError::new()`, `From` and `collect()`.
+///
+/// The Display output numbers each error's full Display output, below a count of the errors.
+/// With the serde features, a MultiError is serialized as a sequence of its errors.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn check(value: u32) -> throw::Result<(), &'static str> {
+///     throw_assert!(value < 10, "value too large");
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     let errors: throw::MultiError<_> = vec![10, 2, 30]
+///         .into_iter()
+///         .filter_map(|value| check(value).err())
+///         .collect();
+///     assert_eq!(errors.len(), 2);
+///     assert!(errors.to_string().starts_with("2 errors:\n1. Error: value too large"));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MultiError<E> {
+    errors: Vec<Error<E>>,
+}
+
+impl<E> MultiError<E> {
+    /// Creates a MultiError holding the given errors.
+    pub fn new(errors: Vec<Error<E>>) -> MultiError<E> {
+        MultiError { errors }
+    }
+
+    /// Adds an error.
+    pub fn push(&mut self, error: Error<E>) {
+        self.errors.push(error);
+    }
+
+    /// Gets the errors, in the order they were added.
+    #[inline]
+    pub fn errors(&self) -> &[Error<E>] {
+        &self.errors
+    }
+
+    /// Takes the errors, in the order they were added.
+    #[inline]
+    pub fn into_errors(self) -> Vec<Error<E>> {
+        self.errors
+    }
+
+    /// Iterates over the errors, in the order they were added.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Error<E>> {
+        self.errors.iter()
+    }
+
+    /// The number of errors.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Checks whether there are no errors.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl<E> Default for MultiError<E> {
+    fn default() -> MultiError<E> {
+        MultiError::new(Vec::new())
+    }
+}
+
+impl<E> From<Error<E>> for MultiError<E> {
+    fn from(error: Error<E>) -> MultiError<E> {
+        MultiError::new(vec![error])
+    }
+}
+
+impl<E> From<MultiError<E>> for Vec<Error<E>> {
+    fn from(errors: MultiError<E>) -> Vec<Error<E>> {
+        errors.errors
+    }
+}
+
+/// Takes the error out of a MultiError holding exactly one, giving back the MultiError otherwise.
+impl<E> TryFrom<MultiError<E>> for Error<E> {
+    type Error = MultiError<E>;
+
+    fn try_from(mut errors: MultiError<E>) -> Result<Error<E>, MultiError<E>> {
+        match errors.errors.pop() {
+            Some(error) if errors.errors.is_empty() => Ok(error),
+            Some(error) => {
+                errors.errors.push(error);
+                Err(errors)
+            }
+            None => Err(errors),
+        }
+    }
+}
+
+impl<E> FromIterator<Error<E>> for MultiError<E> {
+    fn from_iter<I: IntoIterator<Item = Error<E>>>(iter: I) -> MultiError<E> {
+        MultiError::new(iter.into_iter().collect())
+    }
+}
+
+impl<E> Extend<Error<E>> for MultiError<E> {
+    fn extend<I: IntoIterator<Item = Error<E>>>(&mut self, iter: I) {
+        self.errors.extend(iter);
+    }
+}
+
+impl<E> IntoIterator for MultiError<E> {
+    type Item = Error<E>;
+    type IntoIter = vec::IntoIter<Error<E>>;
+
+    fn into_iter(self) -> vec::IntoIter<Error<E>> {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a, E> IntoIterator for &'a MultiError<E> {
+    type Item = &'a Error<E>;
+    type IntoIter = slice::Iter<'a, Error<E>>;
+
+    fn into_iter(self) -> slice::Iter<'a, Error<E>> {
+        self.errors.iter()
+    }
+}
+
+/// Writes to a formatter, indenting every line after the first.
+struct Indented<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    indent: &'static str,
+    line_start: bool,
+}
+
+impl<'a, 'b> Write for Indented<'a, 'b> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (i, line) in s.split('\n').enumerate() {
+            if i != 0 {
+                self.f.write_str("\n")?;
+                self.line_start = true;
+            }
+            if self.line_start && !line.is_empty() {
+                self.f.write_str(self.indent)?;
+                self.line_start = false;
+            }
+            self.f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a count of the errors, then each error's Display output, numbered and indented to line
+/// up below its number. The alternate flag is passed on to each error.
+impl<E: fmt::Display> fmt::Display for MultiError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.errors.len() {
+            1 => f.write_str("1 error:")?,
+            len => write!(f, "{} errors:", len)?,
+        }
+        let alternate = f.alternate();
+        for (i, error) in self.errors.iter().enumerate() {
+            let number = i + 1;
+            write!(f, "\n{}. ", number)?;
+            let indent = if number < 10 { "   " } else { "    " };
+            let mut out = Indented {
+                f: &mut *f,
+                indent,
+                line_start: false,
+            };
+            if alternate {
+                write!(out, "{:#}", error)?;
+            } else {
+                write!(out, "{}", error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for MultiError<E> {}
@@ -0,0 +1,85 @@
+use core::convert::TryFrom;
+use core::fmt;
+
+use opentelemetry::trace::{Span, Status};
+use opentelemetry::{KeyValue, Value};
+
+use crate::fmt::StackTrace;
+use crate::{Error, ThrowContextValues};
+
+impl<E: fmt::Display> Error<E> {
+    /// Records this error on an OpenTelemetry span.
+    ///
+    /// The span's status is set to an error described by the inner error's Display output, and
+    /// an `exception` event is added with the attributes:
+    ///
+    /// - `exception.type`: the inner error's type name
+    /// - `exception.message`: the inner error's Display output
+    /// - `exception.stacktrace`: one line for each point, most recent first, as in Display output
+    ///
+    /// Each key/value pair of context is set as an attribute of the span, along with the error's
+    /// code as `error.code`. Nothing is recorded on spans which aren't recording.
+    ///
+    /// Only available with the `opentelemetry` feature.
+    ///
+    /// ```
+    /// # extern crate opentelemetry;
+    /// # extern crate throw;
+    /// # #[cfg(feature = "opentelemetry")]
+    /// # fn main() {
+    /// let mut span = opentelemetry::trace::noop::NoopSpan::DEFAULT;
+    /// let mut error = throw::Error::new("upload failed");
+    /// error.add_context("bytes", 1024);
+    /// error.record_on_span(&mut span);
+    /// # }
+    /// # #[cfg(not(feature = "opentelemetry"))]
+    /// # fn main() {}
+    /// ```
+    pub fn record_on_span<S: Span>(&self, span: &mut S) {
+        if !span.is_recording() {
+            return;
+        }
+        let message = self.error().to_string();
+        for kv in self.get_context() {
+            span.set_attribute(KeyValue::new(kv.key.clone(), attribute_value(kv.value())));
+        }
+        if let Some(code) = self.code() {
+            span.set_attribute(KeyValue::new("error.code", code.to_owned()));
+        }
+        span.add_event(
+            "exception",
+            vec![
+                KeyValue::new("exception.type", core::any::type_name::<E>()),
+                KeyValue::new("exception.message", message.clone()),
+                KeyValue::new(
+                    "exception.stacktrace",
+                    StackTrace(self.points()).to_string(),
+                ),
+            ],
+        );
+        span.set_status(Status::error(message));
+    }
+}
+
+/// Converts a context value to an attribute value. OpenTelemetry only has signed 64-bit
+/// integers, so `u64` values which don't fit are recorded as strings.
+fn attribute_value(value: &ThrowContextValues) -> Value {
+    match *value {
+        ThrowContextValues::Bool(x) => x.into(),
+        ThrowContextValues::Int8(x) => i64::from(x).into(),
+        ThrowContextValues::Uint8(x) => i64::from(x).into(),
+        ThrowContextValues::Int16(x) => i64::from(x).into(),
+        ThrowContextValues::Uint16(x) => i64::from(x).into(),
+        ThrowContextValues::Int32(x) => i64::from(x).into(),
+        ThrowContextValues::Uint32(x) => i64::from(x).into(),
+        ThrowContextValues::Int64(x) => x.into(),
+        ThrowContextValues::Uint64(x) => match i64::try_from(x) {
+            Ok(x) => x.into(),
+            Err(_) => x.to_string().into(),
+        },
+        ThrowContextValues::Float32(x) => f64::from(x).into(),
+        ThrowContextValues::Float64(x) => x.into(),
+        ThrowContextValues::String(ref x) => x.clone().into(),
+        ThrowContextValues::StaticStr(x) => x.into(),
+    }
+}
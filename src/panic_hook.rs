@@ -0,0 +1,113 @@
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::fmt::{self, Write};
+
+#[cfg(feature = "color")]
+use crate::fmt::ColorChoice;
+use crate::fmt::{ErrorFormatter, Style};
+use crate::{Error, Report};
+
+/// Installs a panic hook which renders throw errors in panic messages in the `Pretty` style, with
+/// ANSI colors with the `color` feature when stderr is a terminal and `NO_COLOR` isn't set.
+///
+/// Errors are found as described in `render_panic_payload()`, such as those printed by
+/// `.unwrap()` or `.expect()` on a `throw::Result`. Panics without a throw error in their payload
+/// are passed to the previously installed hook.
+///
+/// Only available with the `std` feature.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let rendered = match render_panic_payload(info.payload()) {
+            Some(rendered) => rendered,
+            None => return previous(info),
+        };
+        let thread = std::thread::current();
+        let mut out = format!(
+            "\nthread '{}' panicked",
+            thread.name().unwrap_or("<unnamed>")
+        );
+        if let Some(location) = info.location() {
+            let _ = write!(out, " at {}", location);
+        }
+        let _ = write!(out, ":\n{}", rendered);
+        let backtrace = Backtrace::capture();
+        if backtrace.status() == BacktraceStatus::Captured {
+            let _ = write!(out, "\nstack backtrace:\n{}", backtrace);
+        }
+        eprintln!("{}", out);
+    }));
+}
+
+/// Renders the throw error in a panic's payload in the `Pretty` style, if it has one, as
+/// `install_panic_hook()` does. Returns `None` if the payload has no throw error.
+///
+/// The payload may be a `throw::Report`, or a `throw::Error` with an inner `String`, `&str` or
+/// `Box<dyn Display + Send + Sync>` error, such as one given to `std::panic::panic_any()`. It may
+/// also be a message containing an error's Debug or Display output with at least one point, such
+/// as the message of `.unwrap()` or `.expect()` on a `throw::Result`, in which case the error is
+/// parsed from the message, and the text before it is kept as a line of its own.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn fails() -> throw::Result<(), &'static str> {
+///     throw_new!("failed");
+/// }
+///
+/// fn main() {
+///     let payload = std::panic::catch_unwind(|| fails().unwrap()).unwrap_err();
+///     let rendered = throw::render_panic_payload(&*payload).unwrap();
+///     assert!(rendered.starts_with("called `Result::unwrap()` on an `Err` value:\nError: \"failed\"\n"));
+/// }
+/// ```
+pub fn render_panic_payload(payload: &(dyn Any + Send)) -> Option<String> {
+    #[cfg_attr(not(feature = "color"), allow(unused_mut))]
+    let mut formatter = ErrorFormatter::new().style(Style::Pretty);
+    #[cfg(feature = "color")]
+    {
+        formatter = formatter.color(ColorChoice::Auto);
+    }
+    if let Some(report) = payload.downcast_ref::<Report>() {
+        return Some(report.error().display_with(&formatter).to_string());
+    }
+    if let Some(error) = payload.downcast_ref::<Error<String>>() {
+        return Some(error.display_with(&formatter).to_string());
+    }
+    if let Some(error) = payload.downcast_ref::<Error<&'static str>>() {
+        return Some(error.display_with(&formatter).to_string());
+    }
+    if let Some(error) = payload.downcast_ref::<Error<Box<dyn fmt::Display + Send + Sync>>>() {
+        return Some(error.display_with(&formatter).to_string());
+    }
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))?;
+    let (prefix, error) = find_error(message)?;
+    let prefix = prefix.trim_end();
+    let rendered = error.display_with(&formatter);
+    Some(match prefix {
+        "" => rendered.to_string(),
+        prefix => format!("{}\n{}", prefix, rendered),
+    })
+}
+
+/// Finds a rendered error with at least one point in a panic message, returning the text before
+/// it and the parsed error.
+fn find_error(message: &str) -> Option<(&str, Error<String>)> {
+    ["Error", "Warning", "Advice"]
+        .iter()
+        .flat_map(|label| message.match_indices(label))
+        .map(|(start, _)| start)
+        .filter(|&start| start == 0 || message[..start].ends_with(char::is_whitespace))
+        .filter_map(|start| {
+            let error = crate::parse::parse_error(&message[start..]).ok()?;
+            Some((start, error))
+        })
+        .filter(|(_, error)| !error.points().is_empty())
+        .min_by_key(|&(start, _)| start)
+        .map(|(start, error)| (&message[..start], error))
+}
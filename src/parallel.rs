@@ -0,0 +1,73 @@
+use rayon::iter::{Either, IndexedParallelIterator, ParallelExtend, ParallelIterator};
+
+use crate::{Error, MultiError, Result};
+
+/// Extension methods for `rayon` parallel iterators of `throw::Result`s, collecting every error
+/// rather than stopping at the first.
+///
+/// This trait is implemented for all indexed parallel iterators.
+///
+/// Only available with the `rayon` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// # #[cfg(feature = "rayon")]
+/// # fn main() {
+/// use rayon::prelude::*;
+/// use throw::ThrowParallelIteratorExt;
+///
+/// fn check(value: u32) -> throw::Result<u32, &'static str> {
+///     throw_assert!(value % 2 == 0, "odd value");
+///     Ok(value / 2)
+/// }
+///
+/// let halves: Vec<u32> = vec![2, 4, 6].into_par_iter().map(check).collect_throw().unwrap();
+/// assert_eq!(halves, [1, 2, 3]);
+///
+/// let errors = (0..100u32)
+///     .into_par_iter()
+///     .map(check)
+///     .collect_throw::<Vec<_>, _, _>()
+///     .unwrap_err();
+/// assert_eq!(errors.len(), 50);
+/// assert!(errors.errors()[1].to_string().contains("index: 3"));
+/// # }
+/// # #[cfg(not(feature = "rayon"))]
+/// # fn main() {}
+/// ```
+pub trait ThrowParallelIteratorExt: IndexedParallelIterator {
+    /// Collects a parallel iterator of `throw::Result`s, like `collect::<Result<C, _>>()`, but
+    /// rather than stopping at the first error, collects every error into a `MultiError`.
+    ///
+    /// Each error keeps the points added by the worker which created it. The index of the
+    /// element which was an error is added to its context under the `"index"` key, and the index
+    /// of the `rayon` worker thread it was processed on under the `"thread"` key. The errors are
+    /// in the order of the elements which were errors.
+    fn collect_throw<C, T, E>(self) -> core::result::Result<C, MultiError<E>>
+    where
+        Self: ParallelIterator<Item = Result<T, E>>,
+        C: Default + Send + ParallelExtend<T>,
+        T: Send,
+        E: Send,
+    {
+        let (collected, errors): (C, Vec<Error<E>>) =
+            self.enumerate().partition_map(|(index, item)| match item {
+                Ok(value) => Either::Left(value),
+                Err(mut e) => {
+                    e.add_context("index", index as u64);
+                    if let Some(thread) = rayon::current_thread_index() {
+                        e.add_context("thread", thread as u64);
+                    }
+                    Either::Right(e)
+                }
+            });
+        if errors.is_empty() {
+            Ok(collected)
+        } else {
+            Err(MultiError::new(errors))
+        }
+    }
+}
+
+impl<I: IndexedParallelIterator> ThrowParallelIteratorExt for I {}
@@ -0,0 +1,324 @@
+//! Parsing errors back out of their Display output.
+//!
+//! This turns text such as
+//!
+//! ```text
+//! Error: failed to read config
+//!     path: /etc/app.toml
+//!     at 79:17 in zaldinar::startup (src/startup.rs)
+//! ```
+//!
+//! (with tabs rather than spaces) back into an `Error<String>`, with owned points and context.
+//! Context values are always parsed as strings, as Display output doesn't say which type they
+//! were. A final `help:` line after all context is parsed as the error's help message, and points
+//! following a `points, oldest first:` line are parsed as being in that order. Lines indented by
+//! two spaces more than the rest continue the message or value on the line before them. The crate
+//! name, version, timestamp and thread rendered after a point's file with the `crate-info`,
+//! `timestamps` and `thread-info` features are parsed back into the point.
+//!
+//! ```
+//! # extern crate throw;
+//! # fn main() {
+//! let text = "Error[E1042]: failed to read config\n\tpath: /etc/app.toml\n\tat 79:17 in \
+//!             zaldinar::startup (src/startup.rs)";
+//! let error: throw::Error<String> = text.parse().unwrap();
+//! assert_eq!(error.error(), "failed to read config");
+//! assert_eq!(error.code(), Some("E1042"));
+//! assert_eq!(error.get_context()[0].key(), "path");
+//! assert_eq!(error.points()[0].module_path(), "zaldinar::startup");
+//! assert_eq!(error.to_string(), text);
+//! # }
+//! ```
+
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use crate::fmt::OLDEST_FIRST_HEADER;
+use crate::{Error, ErrorPoint, KvPair, Metadata, PointMetadata, Severity, ThrowContextValues};
+
+/// An error encountered while parsing an error's Display output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    message: &'static str,
+}
+
+impl ParseError {
+    /// The line of the input the error was found on, starting from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// A description of what was wrong with the line.
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "invalid throw error on line {}: {}",
+            self.line, self.message
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Parses an error from its Display output. This is the same as using `str::parse`.
+pub fn parse_error(input: &str) -> Result<Error<String>, ParseError> {
+    // Lines of the inner error can't start with a tab, so each `\n\t` starts a new line of
+    // context or points, unless it's indented by two more spaces to continue the line before.
+    let mut lines: Vec<(usize, Cow<str>)> = Vec::new();
+    let mut line_number = 1;
+    for segment in input.split("\n\t") {
+        match (segment.strip_prefix("  "), lines.last_mut()) {
+            (Some(continued), Some((_, line))) => {
+                let line = line.to_mut();
+                line.push('\n');
+                line.push_str(continued);
+            }
+            _ => lines.push((line_number, Cow::Borrowed(segment))),
+        }
+        line_number += segment.matches('\n').count() + 1;
+    }
+    let mut lines = lines.iter();
+    let header = lines.next().map_or("", |(_, header)| header);
+    let (severity, code, message) = parse_header(header).ok_or(ParseError {
+        line: 1,
+        message: "expected `Error: `, `Warning: ` or `Advice: `, optionally with a code",
+    })?;
+
+    let mut context = Vec::new();
+    let mut points = Vec::new();
+    let mut oldest_first = false;
+    let mut help = None;
+    let mut lines = lines.peekable();
+    while let Some(&(line_number, ref line)) = lines.next() {
+        let line = &**line;
+        let is_last = lines.peek().is_none();
+        if help.is_some() {
+            return Err(ParseError {
+                line: line_number,
+                message: "unexpected line after help message",
+            });
+        }
+        if let Some(point) = parse_point(line) {
+            points.push(point);
+            continue;
+        }
+        if line == OLDEST_FIRST_HEADER && points.is_empty() && !oldest_first {
+            oldest_first = true;
+            continue;
+        }
+        // Spans captured with the `tracing` feature aren't kept.
+        if !points.is_empty() && line.starts_with("in ") {
+            continue;
+        }
+        if (oldest_first || !points.is_empty()) && !line.starts_with("help: ") {
+            return Err(ParseError {
+                line: line_number,
+                message: "expected `at L:C in module (file)` or `help: `",
+            });
+        }
+        let (key, value) = match line.find(": ") {
+            Some(index) => (&line[..index], &line[index + 2..]),
+            None => {
+                return Err(ParseError {
+                    line: line_number,
+                    message: "expected `key: value` or `at L:C in module (file)`",
+                })
+            }
+        };
+        if key == "help" && (is_last || !points.is_empty()) {
+            help = Some(value);
+        } else {
+            context.push(KvPair {
+                key: Cow::Owned(key.to_string()),
+                value: ThrowContextValues::String(value.to_string()),
+                point: None,
+            });
+        }
+    }
+    context.reverse();
+    if !oldest_first {
+        points.reverse();
+    }
+
+    let metadata = if code.is_some() || severity.is_some() || help.is_some() {
+        Some(Box::new(Metadata::new(
+            code.map(|code| Cow::Owned(code.to_string())),
+            severity,
+            help.map(|help| Cow::Owned(help.to_string())),
+        )))
+    } else {
+        None
+    };
+    Ok(Error {
+        points,
+        context,
+        metadata,
+        error: message.to_string(),
+    })
+}
+
+/// Parses `Error: msg`, `Warning[E1042]: msg`, etc.
+fn parse_header(header: &str) -> Option<(Option<Severity>, Option<&str>, &str)> {
+    let (severity, rest) = if let Some(rest) = header.strip_prefix("Error") {
+        (None, rest)
+    } else if let Some(rest) = header.strip_prefix("Warning") {
+        (Some(Severity::Warning), rest)
+    } else if let Some(rest) = header.strip_prefix("Advice") {
+        (Some(Severity::Advice), rest)
+    } else {
+        return None;
+    };
+    let (code, rest) = match rest.strip_prefix("[") {
+        Some(rest) => {
+            let end = rest.find("]: ")?;
+            (Some(&rest[..end]), &rest[end + 1..])
+        }
+        None => (None, rest),
+    };
+    let message = rest.strip_prefix(": ")?;
+    Some((severity, code, message))
+}
+
+/// Parses `at L:C in module (file)`, optionally followed by ` \u{2014} note`.
+fn parse_point(line: &str) -> Option<ErrorPoint> {
+    let rest = line.strip_prefix("at ")?;
+    let colon = rest.find(':')?;
+    let line_number = rest[..colon].parse().ok()?;
+    let rest = &rest[colon + 1..];
+    let space = rest.find(" in ")?;
+    let column = rest[..space].parse().ok()?;
+    let rest = &rest[space + 4..];
+    let open = rest.find(" (")?;
+    let module_path = &rest[..open];
+    let rest = &rest[open + 2..];
+    let (rest, note) = match [") \u{2014} ", "] \u{2014} "]
+        .iter()
+        .filter_map(|separator| rest.find(separator))
+        .min()
+    {
+        Some(close) => (&rest[..=close], Some(&rest[close + ") \u{2014} ".len()..])),
+        None => (rest, None),
+    };
+    let (file, metadata) = match rest.strip_suffix(']').and_then(|rest| rest.rfind(") [")) {
+        Some(close) => (
+            &rest[..close],
+            Some(parse_metadata(&rest[close + 3..rest.len() - 1])),
+        ),
+        None => (rest.strip_suffix(")")?, None),
+    };
+    let mut metadata = metadata.unwrap_or_default();
+    metadata.note = note.map(|note| Cow::Owned(note.to_string()));
+    Some(ErrorPoint::owned(
+        line_number,
+        column,
+        module_path.to_string(),
+        file.to_string(),
+        Some(metadata),
+    ))
+}
+
+/// Parses the metadata written after a point's file, such as
+/// `my_crate 1.2.0, 2026-10-15T05:25:36.422123Z, thread 'main' #1`.
+fn parse_metadata(metadata: &str) -> Box<PointMetadata> {
+    let mut parsed = PointMetadata::default();
+    for part in metadata.split(", ") {
+        if let Some(timestamp) = parse_timestamp(part) {
+            parsed.timestamp = Some(timestamp);
+            continue;
+        }
+        if let Some(thread) = part.strip_prefix("thread") {
+            let (name, id) = match thread.rfind(" #") {
+                Some(hash) => (&thread[..hash], thread[hash + 2..].parse().ok()),
+                None => (thread, None),
+            };
+            let name = name
+                .strip_prefix(" '")
+                .and_then(|name| name.strip_suffix('\''));
+            if id.is_some() || name.is_some() {
+                parsed.thread_id = id;
+                parsed.thread_name = name.map(|name| Cow::Owned(name.to_string()));
+                continue;
+            }
+        }
+        let (name, version) = match part.split_once(' ') {
+            Some((name, version)) => (name, Some(version)),
+            None => (part, None),
+        };
+        parsed.crate_name = Some(Cow::Owned(name.to_string()));
+        parsed.crate_version = version.map(|version| Cow::Owned(version.to_string()));
+    }
+    Box::new(parsed)
+}
+
+/// Parses an RFC 3339 UTC timestamp with microseconds, as written by `Display`, into the time
+/// since the Unix epoch.
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let bytes = timestamp.as_bytes();
+    if bytes.len() != 27
+        || [
+            (4, b'-'),
+            (7, b'-'),
+            (10, b'T'),
+            (13, b':'),
+            (16, b':'),
+            (19, b'.'),
+            (26, b'Z'),
+        ]
+        .iter()
+        .any(|&(index, byte)| bytes[index] != byte)
+    {
+        return None;
+    }
+    let number = |start: usize, end: usize| -> Option<u64> {
+        let digits = &timestamp[start..end];
+        if digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            digits.parse().ok()
+        } else {
+            None
+        }
+    };
+    let (year, month, day) = (number(0, 4)?, number(5, 7)?, number(8, 10)?);
+    let (hours, minutes, secs) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+    let micros = number(20, 26)?;
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // converts a civil date to days since the epoch, from Howard Hinnant's `days_from_civil`
+    let year = year - u64::from(month <= 2);
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    let secs = days * 86_400 + hours * 3600 + minutes * 60 + secs;
+    Some(Duration::from_secs(secs) + Duration::from_micros(micros))
+}
+
+impl FromStr for Error<String> {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Error<String>, ParseError> {
+        parse_error(input)
+    }
+}
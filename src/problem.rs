@@ -0,0 +1,52 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::Error;
+
+/// Members defined by RFC 7807, which context keys can't replace.
+const STANDARD_MEMBERS: &[&str] = &["type", "title", "status", "detail", "instance"];
+
+impl<E: fmt::Display> Error<E> {
+    /// Creates an RFC 7807 `application/problem+json` body describing this error.
+    ///
+    /// The `title` is the inner error's Display output, and the `detail` is this error's full
+    /// Display output, including context and points. Each key/value pair of context is added as
+    /// an extension member, with later values replacing earlier ones with the same key. Context
+    /// with the same key as a standard member is left out.
+    ///
+    /// For an inner error implementing `throw::http::HttpStatus`, `error.status()` gives the status
+    /// it chose.
+    ///
+    /// Only available with the `problem-json` feature.
+    ///
+    /// ```
+    /// # extern crate throw;
+    /// # #[cfg(feature = "problem-json")]
+    /// # fn main() {
+    /// let mut error = throw::Error::new("user not found");
+    /// error.add_context("user_id", 42);
+    /// let problem = error.to_problem_json(404);
+    /// assert_eq!(problem["title"], "user not found");
+    /// assert_eq!(problem["status"], 404);
+    /// assert_eq!(problem["user_id"], 42);
+    /// # }
+    /// # #[cfg(not(feature = "problem-json"))]
+    /// # fn main() {}
+    /// ```
+    pub fn to_problem_json(&self, status: u16) -> Value {
+        let mut problem = Map::new();
+        problem.insert("type".to_owned(), Value::from("about:blank"));
+        problem.insert("title".to_owned(), Value::from(self.error().to_string()));
+        problem.insert("status".to_owned(), Value::from(status));
+        problem.insert("detail".to_owned(), Value::from(self.to_string()));
+        for kv in self.get_context() {
+            if STANDARD_MEMBERS.contains(&kv.key()) {
+                continue;
+            }
+            let value = serde_json::to_value(kv.value()).unwrap_or(Value::Null);
+            problem.insert(kv.key().to_owned(), value);
+        }
+        Value::Object(problem)
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt::{self, Write};
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::types::{PyDict, PyDictMethods, PyTuple};
+use pyo3::{Bound, IntoPyObject, IntoPyObjectExt, PyAny, PyErr, Python};
+
+use crate::{Error, ThrowContextValues};
+
+/// Converts context values into the matching Python `bool`, `int`, `float` or `str`.
+///
+/// Only available with the `pyo3` feature.
+impl<'py> IntoPyObject<'py> for ThrowContextValues {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Bound<'py, PyAny>, PyErr> {
+        match self {
+            ThrowContextValues::Bool(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Int8(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Uint8(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Int16(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Uint16(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Int32(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Uint32(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Int64(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Uint64(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Float32(v) => v.into_bound_py_any(py),
+            ThrowContextValues::Float64(v) => v.into_bound_py_any(py),
+            ThrowContextValues::String(v) => v.into_bound_py_any(py),
+            ThrowContextValues::StaticStr(v) => v.into_bound_py_any(py),
+        }
+    }
+}
+
+/// The arguments of the exception raised for an error: its message, and a dict of its context.
+struct ExceptionArgs {
+    message: String,
+    context: Vec<(String, ThrowContextValues)>,
+}
+
+impl<'py> IntoPyObject<'py> for ExceptionArgs {
+    type Target = PyTuple;
+    type Output = Bound<'py, PyTuple>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Bound<'py, PyTuple>, PyErr> {
+        let context = PyDict::new(py);
+        for (key, value) in self.context {
+            context.set_item(key, value)?;
+        }
+        (self.message, context).into_pyobject(py)
+    }
+}
+
+/// Writes an error's points in the format of a Python traceback, with the outermost point first.
+fn write_traceback<E>(out: &mut String, error: &Error<E>) -> fmt::Result {
+    out.write_str("Traceback (most recent call last):")?;
+    for point in error.points().iter().rev() {
+        write!(
+            out,
+            "\n  File \"{}\", line {}, in {}",
+            point.file(),
+            point.line(),
+            point.module_path()
+        )?;
+        if let Some(note) = point.note() {
+            write!(out, "\n    {}", note)?;
+        }
+    }
+    Ok(())
+}
+
+/// Converts an error into a Python `RuntimeError`, so that a `#[pyfunction]` returning
+/// `PyResult` can use `?` on a `throw::Result`.
+///
+/// The exception's first argument is its message: the inner error's Display output, followed by
+/// the error's points formatted like a Python traceback, with the outermost point first. Its
+/// second argument is a `dict` of the error's context, with later values replacing earlier ones
+/// with the same key.
+///
+/// Only available with the `pyo3` feature.
+///
+/// ```
+/// # #[cfg(feature = "pyo3")]
+/// # mod example {
+/// use pyo3::PyResult;
+///
+/// fn parse(input: &str) -> throw::Result<u32, std::num::ParseIntError> {
+///     let value = throw::throw!(input.parse::<u32>(), "input" => input.to_owned());
+///     Ok(value)
+/// }
+///
+/// fn double(input: &str) -> PyResult<u32> {
+///     Ok(parse(input)? * 2)
+/// }
+/// # }
+/// # fn main() {}
+/// ```
+impl<E: fmt::Display> From<Error<E>> for PyErr {
+    fn from(error: Error<E>) -> PyErr {
+        let mut message = error.error().to_string();
+        if !error.points().is_empty() {
+            message.push_str("\n\n");
+            let _ = write_traceback(&mut message, &error);
+        }
+        let context = error
+            .get_context()
+            .iter()
+            .map(|kv| (kv.key().to_owned(), kv.value().clone()))
+            .collect();
+        PyErr::new::<PyRuntimeError, _>(ExceptionArgs { message, context })
+    }
+}
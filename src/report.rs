@@ -0,0 +1,385 @@
+//! Reporting errors returned from `main`, or written as structured logs.
+//!
+//! Only available with the `std` feature. The items here other than those for JSON are also
+//! available from the crate root.
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::fmt;
+use std::process::{ExitCode, Termination};
+use std::sync::OnceLock;
+
+#[cfg(feature = "color")]
+use crate::fmt::ColorChoice;
+use crate::fmt::{ErrorFormatter, Style};
+use crate::Error;
+
+#[cfg(feature = "serde-1-std")]
+mod json;
+
+#[cfg(feature = "serde-1-std")]
+pub use self::json::{set_json_reporter, JsonReporter};
+
+/// Runs a fallible `main` function, printing the full trace of any error it returns to stderr.
+///
+/// Returning a `Result` from `main` directly prints the error with its `Debug` implementation,
+/// on a single line. `report` instead prints the error's `Display` output, including all of its
+/// context and points, and returns an exit code for `main` to return: `ExitCode::SUCCESS` if the
+/// function succeeded, and `ExitCode::FAILURE` otherwise.
+///
+/// With the `color` feature, the error is rendered with ANSI colors if stderr is a terminal and
+/// the `NO_COLOR` environment variable isn't set.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// use std::process::ExitCode;
+///
+/// fn run() -> throw::Result<(), std::num::ParseIntError> {
+///     let value: u32 = throw!("42".parse());
+///     println!("value: {}", value);
+///     Ok(())
+/// }
+///
+/// fn main() -> ExitCode {
+///     throw::report(run)
+/// }
+/// ```
+pub fn report<E, F>(f: F) -> ExitCode
+where
+    E: fmt::Display,
+    F: FnOnce() -> crate::Result<(), E>,
+{
+    match f() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            print_error(&e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints an error's full trace to stderr, with colors if enabled and stderr is a terminal.
+fn print_error<E: fmt::Display>(e: &Error<E>) {
+    #[cfg(feature = "color")]
+    {
+        let formatter = ErrorFormatter::new().color(ColorChoice::Auto);
+        eprintln!("{}", e.display_with(&formatter));
+    }
+    #[cfg(not(feature = "color"))]
+    eprintln!("{}", e);
+}
+
+/// An error which decides the exit code of the process when returned from `main` in a
+/// `throw::Exit`.
+///
+/// Only available with the `std` feature.
+pub trait ExitStatus {
+    /// The exit code to exit with. Defaults to 1.
+    fn exit_code(&self) -> u8 {
+        1
+    }
+}
+
+impl ExitStatus for &'static str {}
+
+impl ExitStatus for String {}
+
+/// Maps I/O errors to the exit codes from BSD's `sysexits.h`: 66 (`EX_NOINPUT`) for
+/// `NotFound`, 77 (`EX_NOPERM`) for `PermissionDenied`, and 74 (`EX_IOERR`) otherwise.
+impl ExitStatus for std::io::Error {
+    fn exit_code(&self) -> u8 {
+        match self.kind() {
+            std::io::ErrorKind::NotFound => 66,
+            std::io::ErrorKind::PermissionDenied => 77,
+            _ => 74,
+        }
+    }
+}
+
+/// The result of `main`, printing the full trace of an error to stderr and exiting with the exit
+/// code given by its `ExitStatus` implementation.
+///
+/// Any `throw::Result<(), E>` can be converted into an `Exit` with `.into()`. Errors are printed
+/// as by `throw::report()`.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// use throw::ExitStatus;
+///
+/// #[derive(Debug)]
+/// struct UsageError(&'static str);
+///
+/// impl std::fmt::Display for UsageError {
+///     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         write!(f, "usage: {}", self.0)
+///     }
+/// }
+///
+/// impl ExitStatus for UsageError {
+///     fn exit_code(&self) -> u8 {
+///         64
+///     }
+/// }
+///
+/// fn run() -> throw::Result<(), UsageError> {
+///     if std::env::args().count() > 8 {
+///         throw_new!(UsageError("app [FILE]"));
+///     }
+///     Ok(())
+/// }
+///
+/// fn main() -> throw::Exit<UsageError> {
+///     run().into()
+/// }
+/// ```
+#[must_use = "an Exit only exits the process when returned from main"]
+pub struct Exit<E>(pub crate::Result<(), E>);
+
+impl<E> From<crate::Result<(), E>> for Exit<E> {
+    fn from(result: crate::Result<(), E>) -> Exit<E> {
+        Exit(result)
+    }
+}
+
+impl<E: fmt::Display + ExitStatus> Termination for Exit<E> {
+    fn report(self) -> ExitCode {
+        match self.0 {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                print_error(&e);
+                ExitCode::from(e.error().exit_code())
+            }
+        }
+    }
+}
+
+/// An error to be returned from `main`, rendering a full report of the error when the program
+/// exits with it.
+///
+/// Any `throw::Error<E>` can be converted into a `Report` with `?`, capturing a backtrace where
+/// it's converted if `RUST_BACKTRACE` is set. Returning `Err(report)` from `main` prints it with
+/// its Debug implementation, which renders the report with the handler set with
+/// `set_report_handler()`, or `DefaultReportHandler` if none is set.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn run() -> throw::Result<(), std::num::ParseIntError> {
+///     let value: u32 = throw!("42".parse());
+///     println!("value: {}", value);
+///     Ok(())
+/// }
+///
+/// fn main() -> Result<(), throw::Report> {
+///     run()?;
+///     Ok(())
+/// }
+/// ```
+pub struct Report {
+    error: Error<Box<dyn fmt::Display + Send + Sync>>,
+    backtrace: Backtrace,
+}
+
+impl Report {
+    /// Gets the reported error, with its inner error boxed.
+    pub fn error(&self) -> &Error<Box<dyn fmt::Display + Send + Sync>> {
+        &self.error
+    }
+
+    /// Gets the backtrace captured when the error was converted into this report. This is only
+    /// captured if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set.
+    ///
+    /// With the `backtrace` feature, this is instead the backtrace captured when the error was
+    /// created, if it has one.
+    pub fn backtrace(&self) -> &Backtrace {
+        #[cfg(feature = "backtrace")]
+        if let Some(backtrace) = self.error.backtrace() {
+            return backtrace;
+        }
+        &self.backtrace
+    }
+}
+
+impl<E> From<Error<E>> for Report
+where
+    E: fmt::Display + Send + Sync + 'static,
+{
+    fn from(error: Error<E>) -> Report {
+        #[cfg(feature = "backtrace")]
+        let backtrace = match error.backtrace() {
+            Some(_) => Backtrace::disabled(),
+            None => Backtrace::capture(),
+        };
+        #[cfg(not(feature = "backtrace"))]
+        let backtrace = Backtrace::capture();
+        Report {
+            error: Error {
+                points: error.points,
+                context: error.context,
+                metadata: error.metadata,
+                error: Box::new(error.error),
+            },
+            backtrace,
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match REPORT_HANDLER.get() {
+            Some(handler) => handler.render(self, f),
+            None => DefaultReportHandler::new().render(self, f),
+        }
+    }
+}
+
+/// Renders `Report`s returned from `main`. Set with `set_report_handler()`.
+///
+/// Only available with the `std` feature.
+pub trait ReportHandler: Send + Sync {
+    /// Renders a report. This is written to stderr after `Error: ` by the standard library.
+    fn render(&self, report: &Report, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+/// The handler rendering `Report`s unless another is set with `set_report_handler()`.
+///
+/// The report starts on the line after the `Error: ` written by the standard library. By default,
+/// this renders the error in the `Pretty` style (with ANSI colors, with the `color`
+/// feature, when stderr is a terminal and `NO_COLOR` isn't set), followed by a `Backtrace` section
+/// if one was captured, or a note on how to capture one, and an `Environment` section with the
+/// operating system, architecture and program being run.
+///
+/// Only available with the `std` feature.
+pub struct DefaultReportHandler {
+    formatter: ErrorFormatter,
+    backtrace: bool,
+    environment: bool,
+}
+
+impl DefaultReportHandler {
+    /// Creates a handler rendering every section.
+    pub fn new() -> DefaultReportHandler {
+        #[cfg_attr(not(feature = "color"), allow(unused_mut))]
+        let mut formatter = ErrorFormatter::new().style(Style::Pretty);
+        #[cfg(feature = "color")]
+        {
+            formatter = formatter.color(ColorChoice::Auto);
+        }
+        DefaultReportHandler {
+            formatter,
+            backtrace: true,
+            environment: true,
+        }
+    }
+
+    /// Sets the formatter the error is rendered with.
+    pub fn formatter(mut self, formatter: ErrorFormatter) -> DefaultReportHandler {
+        self.formatter = formatter;
+        self
+    }
+
+    /// Sets whether the `Backtrace` section is rendered.
+    pub fn backtrace(mut self, backtrace: bool) -> DefaultReportHandler {
+        self.backtrace = backtrace;
+        self
+    }
+
+    /// Sets whether the `Environment` section is rendered.
+    pub fn environment(mut self, environment: bool) -> DefaultReportHandler {
+        self.environment = environment;
+        self
+    }
+}
+
+impl Default for DefaultReportHandler {
+    fn default() -> DefaultReportHandler {
+        DefaultReportHandler::new()
+    }
+}
+
+impl ReportHandler for DefaultReportHandler {
+    fn render(&self, report: &Report, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\n{}", report.error().display_with(&self.formatter))?;
+        if self.backtrace {
+            match report.backtrace().status() {
+                BacktraceStatus::Captured => {
+                    f.write_str("\n\nBacktrace:")?;
+                    for line in report.backtrace().to_string().lines() {
+                        write!(f, "\n  {}", line)?;
+                    }
+                }
+                _ => f.write_str("\n\nRun with RUST_BACKTRACE=1 to capture a backtrace.")?,
+            }
+        }
+        if self.environment {
+            f.write_str("\n\nEnvironment:")?;
+            write!(
+                f,
+                "\n  os: {} ({})",
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            )?;
+            if let Some(program) = std::env::args_os().next() {
+                write!(f, "\n  program: {}", program.to_string_lossy())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+static REPORT_HANDLER: OnceLock<Box<dyn ReportHandler>> = OnceLock::new();
+
+/// Sets the handler `Report`s are rendered with, for the whole program. This can only be set
+/// once, so is best done at the start of `main()`. If it's already been set, the given handler is
+/// returned.
+///
+/// Only available with the `std` feature.
+///
+/// ```
+/// # extern crate throw;
+/// use throw::fmt::ErrorFormatter;
+/// use throw::DefaultReportHandler;
+///
+/// throw::set_report_handler(Box::new(
+///     DefaultReportHandler::new()
+///         .formatter(ErrorFormatter::new())
+///         .environment(false),
+/// ))
+/// .ok()
+/// .expect("the report handler is only set once");
+/// ```
+pub fn set_report_handler(handler: Box<dyn ReportHandler>) -> Result<(), Box<dyn ReportHandler>> {
+    REPORT_HANDLER.set(handler)
+}
+
+/// Hashes an error's point locations with 64-bit FNV-1a, which unlike std's hashers is stable
+/// across Rust versions and processes. The error's type isn't included, since its name from
+/// `type_name()` can change between compiler versions.
+#[cfg(feature = "serde-1-std")]
+pub(crate) fn fingerprint<E>(error: &Error<E>) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    let mut write = |bytes: &[u8]| {
+        for &byte in bytes.iter().chain(&[0xff]) {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
+    for point in error.points() {
+        write(point.file().as_bytes());
+        write(&point.line().to_le_bytes());
+        write(&point.column().to_le_bytes());
+    }
+    hash
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+use super::fingerprint;
+use crate::Error;
+
+static JSON_REPORTER: OnceLock<JsonReporter> = OnceLock::new();
+
+/// Writes reported errors to a sink as JSON, one document per line.
+///
+/// Each document is the error serialized as by `Error::to_json_value()`, with two more fields:
+///
+/// - `fingerprint`: a hash of the file, line and column of each point, as 16 hex digits, which
+///   stays the same for errors thrown and propagated from the same places however their messages
+///   and context differ, and across builds with different versions of Rust
+/// - `timestamp_us`: when the error was reported, in microseconds since the Unix epoch
+///
+/// Only available with the `std` and `serde-1-std` features.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// # extern crate serde_json;
+/// use std::io::Write;
+/// use std::sync::{Arc, Mutex};
+///
+/// use throw::report::JsonReporter;
+///
+/// #[derive(Clone, Default)]
+/// struct Buffer(Arc<Mutex<Vec<u8>>>);
+///
+/// impl Write for Buffer {
+///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+///         self.0.lock().unwrap().write(buf)
+///     }
+///
+///     fn flush(&mut self) -> std::io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// fn fails() -> throw::Result<(), &'static str> {
+///     throw_new!("failed", "user" => 42);
+/// }
+///
+/// fn main() {
+///     let buffer = Buffer::default();
+///     let reporter = JsonReporter::new(buffer.clone());
+///     reporter.report(&fails().unwrap_err()).unwrap();
+///
+///     let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+///     let document: serde_json::Value = serde_json::from_str(output.trim_end()).unwrap();
+///     assert_eq!(document["error"], "failed");
+///     assert_eq!(document["fingerprint"].as_str().unwrap().len(), 16);
+/// }
+/// ```
+pub struct JsonReporter {
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonReporter {
+    /// Creates a reporter writing to the given sink, such as `io::stderr()` or a file.
+    pub fn new<W: Write + Send + 'static>(sink: W) -> JsonReporter {
+        JsonReporter {
+            sink: Mutex::new(Box::new(sink)),
+        }
+    }
+
+    /// Writes an error to the sink as a single line of JSON, then flushes it.
+    pub fn report<E: fmt::Display>(&self, error: &Error<E>) -> io::Result<()> {
+        let mut line = serde_json::to_vec(&document(error))?;
+        line.push(b'\n');
+        let mut sink = self
+            .sink
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        sink.write_all(&line)?;
+        sink.flush()
+    }
+}
+
+/// Sets the reporter used by `Error::report()`. This can only be set once, and returns the
+/// reporter back if one was already set.
+///
+/// Only available with the `std` and `serde-1-std` features.
+pub fn set_json_reporter(reporter: JsonReporter) -> Result<(), JsonReporter> {
+    JSON_REPORTER.set(reporter)
+}
+
+impl<E: fmt::Display> Error<E> {
+    /// Writes this error as a line of JSON with the reporter set with
+    /// `throw::report::set_json_reporter()`, or to stderr if none is set. See `JsonReporter` for
+    /// the format.
+    ///
+    /// Errors writing to the reporter's sink are ignored.
+    ///
+    /// Only available with the `std` and `serde-1-std` features.
+    pub fn report(&self) {
+        let reporter = JSON_REPORTER.get_or_init(|| JsonReporter::new(io::stderr()));
+        let _ = reporter.report(self);
+    }
+}
+
+/// Creates the JSON document written for an error.
+fn document<E: fmt::Display>(error: &Error<E>) -> Value {
+    let mut document = error.to_json_value();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    if let Value::Object(ref mut fields) = document {
+        fields.insert(
+            "fingerprint".to_owned(),
+            Value::from(format!("{:016x}", fingerprint(error))),
+        );
+        fields.insert(
+            "timestamp_us".to_owned(),
+            Value::from(crate::serde_impls::timestamp_micros(now)),
+        );
+    }
+    document
+}
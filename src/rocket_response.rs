@@ -0,0 +1,34 @@
+use std::fmt;
+use std::io::Cursor;
+
+use log::Level;
+use rocket::http::{ContentType, Status};
+use rocket::response::{self, Responder, Response};
+use rocket::Request;
+
+use crate::http::{problem_body, HttpStatus};
+use crate::Error;
+
+/// Responds with the status given by the inner error's `HttpStatus` implementation, and an
+/// `application/problem+json` body, as with the `axum` feature's `IntoResponse` implementation.
+///
+/// The error's full trace is logged through the `log` facade, which Rocket's logger receives: at
+/// the `Error` level for 5xx statuses, and at the `Warn` level otherwise.
+///
+/// Only available with the `rocket` feature.
+impl<'r, 'o: 'r, E: fmt::Display + HttpStatus> Responder<'r, 'o> for Error<E> {
+    fn respond_to(self, _request: &'r Request<'_>) -> response::Result<'o> {
+        let status = Status::from_code(self.status()).unwrap_or(Status::InternalServerError);
+        let level = match status.code {
+            500..=599 => Level::Error,
+            _ => Level::Warn,
+        };
+        log::log!(target: "throw", level, "{}", self);
+        let body = problem_body(&self, status.reason()).to_string();
+        Response::build()
+            .status(status)
+            .header(ContentType::new("application", "problem+json"))
+            .sized_body(body.len(), Cursor::new(body))
+            .ok()
+    }
+}
@@ -0,0 +1,127 @@
+//! JSON Schema implementations for serialized errors, describing the default format used by
+//! `Error`'s Serialize implementation.
+
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::ser::Structured;
+use crate::{Error, ErrorPoint, KvPair, Severity, ThrowContextValues};
+
+impl JsonSchema for ThrowContextValues {
+    fn schema_name() -> Cow<'static, str> {
+        "ThrowContextValues".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "throw::ThrowContextValues".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": ["boolean", "integer", "number", "string"]
+        })
+    }
+}
+
+impl JsonSchema for ErrorPoint {
+    fn schema_name() -> Cow<'static, str> {
+        "ErrorPoint".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "throw::ErrorPoint".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "line": { "type": "integer", "format": "uint32", "minimum": 0 },
+                "column": { "type": "integer", "format": "uint32", "minimum": 0 },
+                "module_path": { "type": "string" },
+                "file": { "type": "string" },
+                "note": { "type": "string" },
+                "crate_name": { "type": "string" },
+                "crate_version": { "type": "string" },
+                "timestamp_us": { "type": "integer", "format": "uint64", "minimum": 0 },
+                "thread_id": { "type": "integer", "format": "uint64", "minimum": 0 },
+                "thread_name": { "type": "string" }
+            },
+            "required": ["line", "column", "module_path", "file"]
+        })
+    }
+}
+
+impl JsonSchema for Severity {
+    fn schema_name() -> Cow<'static, str> {
+        "Severity".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "throw::Severity".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "enum": ["error", "warning", "advice"]
+        })
+    }
+}
+
+/// Builds the schema of a serialized error, given the schema of its `"error"` field.
+fn error_schema(generator: &mut SchemaGenerator, inner: Schema) -> Schema {
+    json_schema!({
+        "type": "object",
+        "properties": {
+            "points": {
+                "type": "array",
+                "items": generator.subschema_for::<ErrorPoint>()
+            },
+            "context": {
+                "type": "array",
+                "items": generator.subschema_for::<KvPair>()
+            },
+            "error": inner,
+            "code": { "type": "string" },
+            "severity": generator.subschema_for::<Severity>(),
+            "help": { "type": "string" }
+        },
+        "required": ["points", "context", "error"]
+    })
+}
+
+impl<E> JsonSchema for Error<E> {
+    fn schema_name() -> Cow<'static, str> {
+        "Error".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "throw::Error".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        error_schema(generator, json_schema!({ "type": "string" }))
+    }
+}
+
+impl<'a, E: JsonSchema> JsonSchema for Structured<'a, E> {
+    fn schema_name() -> Cow<'static, str> {
+        format!("StructuredError_{}", E::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!("throw::ser::Structured<{}>", E::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let inner = generator.subschema_for::<E>();
+        error_schema(generator, inner)
+    }
+}
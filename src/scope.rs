@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::{KvPair, ThrowContextValues};
+
+thread_local! {
+    static SCOPED_CONTEXT: RefCell<Vec<KvPair>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A guard adding key/value pairs to every `throw::Error` created on this thread while it is
+/// alive. Created by `context_scope!()`.
+///
+/// Scopes may be nested, and must be dropped in the reverse order they were created in: dropping
+/// a scope removes its own key/value pairs along with those of any scope created after it.
+#[must_use = "the context is removed again as soon as the scope is dropped"]
+pub struct ContextScope {
+    start: usize,
+    // the scope refers to thread-local state, so it must not be sent to another thread
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ContextScope {
+    /// Enters a new scope with no key/value pairs of its own.
+    pub fn enter() -> ContextScope {
+        ContextScope {
+            start: SCOPED_CONTEXT.with(|context| context.borrow().len()),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Adds a key/value pair to this scope.
+    pub fn add_context<V: Into<ThrowContextValues>>(&mut self, key: &'static str, value: V) {
+        let pair = KvPair::new(key, value.into());
+        SCOPED_CONTEXT.with(|context| context.borrow_mut().push(pair));
+    }
+}
+
+impl Drop for ContextScope {
+    fn drop(&mut self) {
+        SCOPED_CONTEXT.with(|context| context.borrow_mut().truncate(self.start));
+    }
+}
+
+/// Copies all key/value pairs from scopes currently alive on this thread into `context`.
+pub(crate) fn extend_with_scoped_context(context: &mut Vec<KvPair>) {
+    SCOPED_CONTEXT.with(|scoped| context.extend(scoped.borrow().iter().cloned()));
+}
+
+/// Wraps a future so that every `throw::Error` created while it runs gets the key/value pairs
+/// added with `Scoped::context()`, as if the future ran inside a `context_scope!()`.
+///
+/// Thread-local scopes don't follow a future across `.await` points, as the executor may poll it
+/// on another thread, and other tasks run on the same thread in between. Instead, the returned
+/// future enters a new scope with its key/value pairs each time it is polled, and leaves it again
+/// before returning, so the pairs are only seen by errors created within its own call tree. This
+/// works with any executor.
+///
+/// ```
+/// # extern crate throw;
+/// # extern crate futures_executor;
+/// async fn lookup(id: u32) -> throw::Result<String, &'static str> {
+///     throw::throw_new!("not found", "id" => id);
+/// }
+///
+/// async fn handle(id: u32) -> throw::Result<String, &'static str> {
+///     let name = throw::up!(lookup(id).await);
+///     Ok(name)
+/// }
+///
+/// fn main() {
+///     let request = throw::scope(handle(4))
+///         .context("request_id", "c0ffee")
+///         .context("tenant", "acme");
+///     let error = futures_executor::block_on(request).unwrap_err();
+///     assert!(error.to_string().contains("request_id: c0ffee"));
+///     assert!(error.to_string().contains("tenant: acme"));
+/// }
+/// ```
+pub fn scope<F: Future>(future: F) -> Scoped<F> {
+    Scoped {
+        future,
+        context: Vec::new(),
+    }
+}
+
+/// Future returned by `throw::scope()`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Scoped<F> {
+    future: F,
+    context: Vec<(&'static str, ThrowContextValues)>,
+}
+
+impl<F> Scoped<F> {
+    /// Adds a key/value pair to errors created while this future runs.
+    pub fn context<V: Into<ThrowContextValues>>(
+        mut self,
+        key: &'static str,
+        value: V,
+    ) -> Scoped<F> {
+        self.context.push((key, value.into()));
+        self
+    }
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        // SAFETY: `future` is never moved out of `self`, and the other fields aren't treated as
+        // pinned, as in `UpFuture`.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+        let mut scope = ContextScope::enter();
+        for (key, value) in &this.context {
+            scope.add_context(key, value.clone());
+        }
+        future.poll(cx)
+    }
+}
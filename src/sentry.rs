@@ -0,0 +1,81 @@
+use core::fmt;
+use core::slice;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sentry_core::protocol::{Breadcrumb, Level, Map};
+
+use crate::fmt::StackTrace;
+use crate::{Error, Severity};
+
+impl<E: fmt::Display> Error<E> {
+    /// Creates one Sentry breadcrumb for each of this error's points, oldest first, so that the
+    /// error's propagation appears in Sentry's breadcrumb timeline.
+    ///
+    /// Each breadcrumb has the category `throw`, and its message is the point's line of the
+    /// error's Display output. Its data holds the point's `module_path`, `file`, `line` and
+    /// `column`, and the first breadcrumb's data also holds the inner error's Display output as
+    /// `error`. Breadcrumbs are timestamped with their point's timestamp when it was recorded,
+    /// and the current time otherwise.
+    ///
+    /// Breadcrumbs have the `error` level, or the `warning` or `info` level for errors with
+    /// `Severity::Warning` or `Severity::Advice`.
+    ///
+    /// Only available with the `sentry` feature.
+    pub fn breadcrumbs(&self) -> Vec<Breadcrumb> {
+        let level = match self.severity() {
+            None | Some(Severity::Error) => Level::Error,
+            Some(Severity::Warning) => Level::Warning,
+            Some(Severity::Advice) => Level::Info,
+        };
+        self.points()
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let mut data = Map::new();
+                if i == 0 {
+                    data.insert("error".to_owned(), self.error().to_string().into());
+                }
+                data.insert("module_path".to_owned(), point.module_path().into());
+                data.insert("file".to_owned(), point.file().into());
+                data.insert("line".to_owned(), point.line().into());
+                data.insert("column".to_owned(), point.column().into());
+                Breadcrumb {
+                    timestamp: point
+                        .timestamp()
+                        .map_or_else(SystemTime::now, |timestamp| UNIX_EPOCH + timestamp),
+                    ty: "error".to_owned(),
+                    category: Some("throw".to_owned()),
+                    level,
+                    message: Some(StackTrace(slice::from_ref(point)).to_string()),
+                    data,
+                }
+            })
+            .collect()
+    }
+
+    /// Adds this error's points to the current Sentry scope as breadcrumbs, as created by
+    /// `Error::breadcrumbs()`. Events captured later in the scope will show where this error
+    /// was thrown and propagated.
+    ///
+    /// Only available with the `sentry` feature.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate throw;
+    /// # #[cfg(feature = "sentry")]
+    /// # fn main() {
+    /// fn fails() -> throw::Result<(), &'static str> {
+    ///     throw_new!("upload failed");
+    /// }
+    ///
+    /// if let Err(error) = fails() {
+    ///     error.add_breadcrumbs();
+    /// }
+    /// # }
+    /// # #[cfg(not(feature = "sentry"))]
+    /// # fn main() {}
+    /// ```
+    pub fn add_breadcrumbs(&self) {
+        sentry_core::add_breadcrumb(self.breadcrumbs());
+    }
+}
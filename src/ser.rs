@@ -0,0 +1,851 @@
+//! Alternate ways of serializing errors.
+//!
+//! Only available with the `serde-1` or `serde-1-std` features.
+
+use core::fmt;
+use core::time::Duration;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple, Serializer,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use crate::{Error, ErrorPoint, KvPair, Metadata, PointMetadata, Severity, ThrowContextValues};
+
+/// Options changing how an error is serialized, used with `Error::serialize_with()` and
+/// `Structured::with_options()`.
+///
+/// The default options serialize errors the same way `Error`'s own Serialize implementation does.
+///
+/// ```
+/// # extern crate serde_json;
+/// # extern crate throw;
+/// # #[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+/// # fn main() {
+/// use throw::ser::{ContextFormat, Duplicates, SerializeOptions};
+///
+/// let mut error = throw::Error::new("failed");
+/// error.add_context("code", 78);
+/// error.add_context("application", "rust_core");
+/// let options = SerializeOptions::new().context(ContextFormat::Map(Duplicates::KeepLast));
+/// assert_eq!(
+///     serde_json::to_string(&error.serialize_with(options)).unwrap(),
+///     r#"{"points":[],"context":{"code":78,"application":"rust_core"},"error":"failed"}"#
+/// );
+/// # }
+/// # #[cfg(not(any(feature = "serde-1", feature = "serde-1-std")))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SerializeOptions {
+    context: ContextFormat,
+    points: PointFormat,
+    order: Option<PointOrder>,
+    max_value_len: Option<usize>,
+}
+
+impl SerializeOptions {
+    /// Creates the default options.
+    pub fn new() -> SerializeOptions {
+        SerializeOptions::default()
+    }
+
+    /// Sets how the error's context is serialized.
+    pub fn context(mut self, format: ContextFormat) -> SerializeOptions {
+        self.context = format;
+        self
+    }
+
+    /// Sets how the error's points are serialized.
+    pub fn points(mut self, format: PointFormat) -> SerializeOptions {
+        self.points = format;
+        self
+    }
+
+    /// Sets the order the error's points are serialized in, and adds an `"order"` field stating
+    /// it. Without this, points are serialized oldest first, with no `"order"` field.
+    pub fn point_order(mut self, order: PointOrder) -> SerializeOptions {
+        self.order = Some(order);
+        self
+    }
+
+    /// Sets the most bytes of each string context value serialized. Longer values are cut short
+    /// and followed by `… (N bytes truncated)`, so that huge values don't bloat logs.
+    pub fn max_value_len(mut self, len: usize) -> SerializeOptions {
+        self.max_value_len = Some(len);
+        self
+    }
+}
+
+/// How an error's context is serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContextFormat {
+    /// A list of objects with `"key"` and `"value"` fields, in the order they were added. This is
+    /// the default.
+    #[default]
+    List,
+    /// An object with a field for each key, in the order each key was first added.
+    Map(Duplicates),
+}
+
+/// How an error's points are serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PointFormat {
+    /// An object with `"line"`, `"column"`, `"module_path"`, `"file"` and, if present, `"note"`
+    /// fields. This is the default.
+    #[default]
+    Object,
+    /// A single string such as `"src/startup.rs:79:17 (zaldinar::startup)"`, followed by
+    /// `" \u{2014} "` and the point's note if it has one.
+    Compact,
+}
+
+pub use crate::fmt::PointOrder;
+
+/// Serializes points in the format and order given by `SerializeOptions`.
+struct Points<'a> {
+    points: &'a [ErrorPoint],
+    format: PointFormat,
+    order: PointOrder,
+}
+
+impl<'a> Serialize for Points<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match (self.format, self.order) {
+            (PointFormat::Object, PointOrder::OldestFirst) => self.points.serialize(serializer),
+            (PointFormat::Object, PointOrder::NewestFirst) => {
+                serializer.collect_seq(self.points.iter().rev())
+            }
+            (PointFormat::Compact, PointOrder::OldestFirst) => {
+                serializer.collect_seq(self.points.iter().map(|point| CompactPoint { point }))
+            }
+            (PointFormat::Compact, PointOrder::NewestFirst) => {
+                serializer.collect_seq(self.points.iter().rev().map(|point| CompactPoint { point }))
+            }
+        }
+    }
+}
+
+struct CompactPoint<'a> {
+    point: &'a ErrorPoint,
+}
+
+impl<'a> fmt::Display for CompactPoint<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let point = self.point;
+        write!(
+            fmt,
+            "{}:{}:{} ({})",
+            crate::fmt::remap_global(point.file()),
+            point.line(),
+            point.column(),
+            point.module_path()
+        )?;
+        if let Some(note) = point.note() {
+            write!(fmt, " \u{2014} {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Serialize for CompactPoint<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// What to do with keys added to an error's context more than once, when serializing context as
+/// a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplicates {
+    /// Use the first value added with the key.
+    KeepFirst,
+    /// Use the last value added with the key.
+    KeepLast,
+    /// Use a list of all values added with the key, if there are more than one.
+    Collect,
+}
+
+/// Serializes context as a map, as described by `ContextFormat::Map`.
+struct ContextMap<'a> {
+    context: &'a [KvPair],
+    duplicates: Duplicates,
+    max_len: Option<usize>,
+}
+
+impl<'a> Serialize for ContextMap<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        for (i, kv) in self.context.iter().enumerate() {
+            if self.context[..i].iter().any(|seen| seen.key() == kv.key()) {
+                continue;
+            }
+            let values = ContextValues {
+                context: &self.context[i..],
+                key: kv.key(),
+                max_len: self.max_len,
+            };
+            let capped = |kv: &'a KvPair| CappedValue {
+                value: kv.value(),
+                max_len: self.max_len,
+            };
+            match self.duplicates {
+                Duplicates::KeepFirst => map.serialize_entry(kv.key(), &capped(kv))?,
+                Duplicates::KeepLast => {
+                    let last = values.iter().last().unwrap_or(kv);
+                    map.serialize_entry(kv.key(), &capped(last))?
+                }
+                Duplicates::Collect if values.iter().nth(1).is_some() => {
+                    map.serialize_entry(kv.key(), &values)?
+                }
+                Duplicates::Collect => map.serialize_entry(kv.key(), &capped(kv))?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// Serializes all values with the given key as a list.
+struct ContextValues<'a> {
+    context: &'a [KvPair],
+    key: &'a str,
+    max_len: Option<usize>,
+}
+
+impl<'a> ContextValues<'a> {
+    fn iter(&self) -> impl Iterator<Item = &'a KvPair> + '_ {
+        self.context.iter().filter(move |kv| kv.key() == self.key)
+    }
+}
+
+impl<'a> Serialize for ContextValues<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(None)?;
+        for kv in self.iter() {
+            seq.serialize_element(&CappedValue {
+                value: kv.value(),
+                max_len: self.max_len,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+/// Serializes context as a list, as described by `ContextFormat::List`, with string values
+/// capped at `max_len` bytes.
+struct ContextList<'a> {
+    context: &'a [KvPair],
+    max_len: usize,
+}
+
+impl<'a> Serialize for ContextList<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.context.iter().map(|kv| CappedPair {
+            kv,
+            max_len: self.max_len,
+        }))
+    }
+}
+
+struct CappedPair<'a> {
+    kv: &'a KvPair,
+    max_len: usize,
+}
+
+impl<'a> Serialize for CappedPair<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("KvPair", 2)?;
+        state.serialize_field("key", self.kv.key())?;
+        state.serialize_field(
+            "value",
+            &CappedValue {
+                value: self.kv.value(),
+                max_len: Some(self.max_len),
+            },
+        )?;
+        state.end()
+    }
+}
+
+/// Serializes a context value, cutting string values longer than `max_len` bytes short.
+struct CappedValue<'a> {
+    value: &'a ThrowContextValues,
+    max_len: Option<usize>,
+}
+
+impl<'a> Serialize for CappedValue<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let value = match *self.value {
+            ThrowContextValues::String(ref value) => value.as_str(),
+            ThrowContextValues::StaticStr(value) => value,
+            _ => return self.value.serialize(serializer),
+        };
+        match self.max_len {
+            Some(max_len) if value.len() > max_len => {
+                serializer.collect_str(&crate::fmt::Truncated::new(&value, max_len))
+            }
+            _ => self.value.serialize(serializer),
+        }
+    }
+}
+
+/// Serializes an error, using `inner` as the value of the `"error"` field.
+fn serialize_error<S, E, T>(
+    error: &Error<E>,
+    inner: &T,
+    options: &SerializeOptions,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ?Sized + Serialize,
+{
+    let len = 3
+        + options.order.is_some() as usize
+        + error.code().is_some() as usize
+        + error.severity().is_some() as usize
+        + error.help().is_some() as usize;
+    let mut state = serializer.serialize_struct("Error", len)?;
+
+    state.serialize_field(
+        "points",
+        &Points {
+            points: error.points(),
+            format: options.points,
+            order: options.order.unwrap_or(PointOrder::OldestFirst),
+        },
+    )?;
+    if let Some(order) = options.order {
+        state.serialize_field("order", order.as_str())?;
+    }
+    match (options.context, options.max_value_len) {
+        (ContextFormat::List, None) => state.serialize_field("context", error.get_context())?,
+        (ContextFormat::List, Some(max_len)) => state.serialize_field(
+            "context",
+            &ContextList {
+                context: error.get_context(),
+                max_len,
+            },
+        )?,
+        (ContextFormat::Map(duplicates), max_len) => state.serialize_field(
+            "context",
+            &ContextMap {
+                context: error.get_context(),
+                duplicates,
+                max_len,
+            },
+        )?,
+    }
+    state.serialize_field("error", inner)?;
+    if let Some(code) = error.code() {
+        state.serialize_field("code", code)?;
+    }
+    if let Some(severity) = error.severity() {
+        state.serialize_field("severity", &severity)?;
+    }
+    if let Some(help) = error.help() {
+        state.serialize_field("help", help)?;
+    }
+    state.end()
+}
+
+impl<E: fmt::Display> Serialize for Error<E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_error(
+            self,
+            &*self.error().to_string(),
+            &SerializeOptions::default(),
+            serializer,
+        )
+    }
+}
+
+/// Serializes an error using the given `SerializeOptions`.
+///
+/// Created by `Error::serialize_with()`.
+pub struct WithOptions<'a, E> {
+    error: &'a Error<E>,
+    options: SerializeOptions,
+}
+
+impl<'a, E: fmt::Display> Serialize for WithOptions<'a, E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_error(
+            self.error,
+            &*self.error.error().to_string(),
+            &self.options,
+            serializer,
+        )
+    }
+}
+
+/// Serializes an error with its inner error serialized structurally, rather than as a string.
+///
+/// Created by `Error::serialize_structured()`.
+pub struct Structured<'a, E> {
+    error: &'a Error<E>,
+    options: SerializeOptions,
+}
+
+impl<'a, E> Structured<'a, E> {
+    /// Serializes the error using the given options.
+    pub fn with_options(self, options: SerializeOptions) -> Structured<'a, E> {
+        Structured { options, ..self }
+    }
+}
+
+impl<E> Error<E> {
+    /// Gets a wrapper which serializes this error using `E`'s own Serialize implementation for
+    /// the `"error"` field, rather than its Display output, so that clients can match on the
+    /// actual error variant.
+    ///
+    /// ```
+    /// # extern crate serde_json;
+    /// # extern crate throw;
+    /// # #[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+    /// # fn main() {
+    /// let error = throw::Error::new(vec![1, 2]);
+    /// let json = serde_json::to_string(&error.serialize_structured()).unwrap();
+    /// assert_eq!(json, r#"{"points":[],"context":[],"error":[1,2]}"#);
+    /// # }
+    /// # #[cfg(not(any(feature = "serde-1", feature = "serde-1-std")))]
+    /// # fn main() {}
+    /// ```
+    pub fn serialize_structured(&self) -> Structured<'_, E> {
+        Structured {
+            error: self,
+            options: SerializeOptions::default(),
+        }
+    }
+
+    /// Gets a wrapper which serializes this error using the given options.
+    pub fn serialize_with(&self, options: SerializeOptions) -> WithOptions<'_, E> {
+        WithOptions {
+            error: self,
+            options,
+        }
+    }
+}
+
+impl<'a, E: Serialize> Serialize for Structured<'a, E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_error(self.error, self.error.error(), &self.options, serializer)
+    }
+}
+
+/// An error with a compact serialization format which works with any serde format, including
+/// non-self-describing ones such as postcard and bincode.
+///
+/// `Error`'s own Serialize implementation leaves out fields which aren't set, and serializes
+/// context values without saying which type they are, so it can only be deserialized from
+/// self-describing formats such as JSON. `Portable` instead serializes every field, without field
+/// names, and tags each context value with its type, so it can be deserialized from any format
+/// it was serialized to. As with `Error`, the inner error is serialized using its Display
+/// implementation, and is usually deserialized as a `String`.
+///
+/// ```
+/// # extern crate serde_json;
+/// # extern crate throw;
+/// # #[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+/// # fn main() {
+/// use throw::ser::Portable;
+///
+/// let mut error = throw::Error::new("failed");
+/// error.add_context("attempt", 3);
+/// let bytes = serde_json::to_vec(&Portable(error)).unwrap();
+/// let Portable(error) = serde_json::from_slice::<Portable<String>>(&bytes).unwrap();
+/// assert_eq!(error.to_string(), "Error: failed\n\tattempt: 3");
+/// # }
+/// # #[cfg(not(any(feature = "serde-1", feature = "serde-1-std")))]
+/// # fn main() {}
+/// ```
+pub struct Portable<E>(pub Error<E>);
+
+impl<E> From<Error<E>> for Portable<E> {
+    fn from(error: Error<E>) -> Portable<E> {
+        Portable(error)
+    }
+}
+
+impl<E: fmt::Display> Serialize for Portable<E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let error = &self.0;
+        let mut tuple = serializer.serialize_tuple(6)?;
+        tuple.serialize_element(&PortablePoints(error.points()))?;
+        tuple.serialize_element(&PortableContext(error.get_context()))?;
+        tuple.serialize_element(&*error.error().to_string())?;
+        tuple.serialize_element(&error.code())?;
+        tuple.serialize_element(&error.severity().map(severity_index))?;
+        tuple.serialize_element(&error.help())?;
+        tuple.end()
+    }
+}
+
+impl<'de, E: Deserialize<'de>> Deserialize<'de> for Portable<E> {
+    fn deserialize<D>(deserializer: D) -> Result<Portable<E>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PortableVisitor<E>(core::marker::PhantomData<E>);
+
+        impl<'de, E: Deserialize<'de>> Visitor<'de> for PortableVisitor<E> {
+            type Value = Portable<E>;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a portable throw error")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Portable<E>, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let points: Vec<PortablePoint> = next(&mut seq, 0, &self)?;
+                let context: Vec<PortableKvPair> = next(&mut seq, 1, &self)?;
+                let error = next(&mut seq, 2, &self)?;
+                let code: Option<String> = next(&mut seq, 3, &self)?;
+                let severity = match next::<_, Option<u8>, _>(&mut seq, 4, &self)? {
+                    None => None,
+                    Some(index) => Some(severity_from_index(index).ok_or_else(|| {
+                        de::Error::invalid_value(
+                            de::Unexpected::Unsigned(index.into()),
+                            &"a severity index from 0 to 2",
+                        )
+                    })?),
+                };
+                let help: Option<String> = next(&mut seq, 5, &self)?;
+
+                let metadata = if code.is_some() || severity.is_some() || help.is_some() {
+                    Some(Box::new(Metadata::new(
+                        code.map(Cow::Owned),
+                        severity,
+                        help.map(Cow::Owned),
+                    )))
+                } else {
+                    None
+                };
+                Ok(Portable(Error {
+                    points: points.into_iter().map(|p| p.0).collect(),
+                    context: context.into_iter().map(|kv| kv.0).collect(),
+                    metadata,
+                    error,
+                }))
+            }
+        }
+
+        deserializer.deserialize_tuple(6, PortableVisitor(core::marker::PhantomData))
+    }
+}
+
+/// Gets the next element of a tuple, failing if there isn't one.
+fn next<'de, A, T, V>(seq: &mut A, index: usize, visitor: &V) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+    V: Visitor<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| de::Error::invalid_length(index, visitor))
+}
+
+fn severity_index(severity: Severity) -> u8 {
+    match severity {
+        Severity::Error => 0,
+        Severity::Warning => 1,
+        Severity::Advice => 2,
+    }
+}
+
+fn severity_from_index(index: u8) -> Option<Severity> {
+    match index {
+        0 => Some(Severity::Error),
+        1 => Some(Severity::Warning),
+        2 => Some(Severity::Advice),
+        _ => None,
+    }
+}
+
+struct PortablePoints<'a>(&'a [ErrorPoint]);
+
+impl<'a> Serialize for PortablePoints<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.iter().map(PortablePointRef))
+    }
+}
+
+/// Serializes a point as a `(line, column, module_path, file, note, metadata)` tuple, where
+/// `metadata` is an optional `(crate_name, crate_version, timestamp_us, thread_id, thread_name)`
+/// tuple.
+struct PortablePointRef<'a>(&'a ErrorPoint);
+
+impl<'a> Serialize for PortablePointRef<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let point = self.0;
+        let mut tuple = serializer.serialize_tuple(6)?;
+        tuple.serialize_element(&point.line())?;
+        tuple.serialize_element(&point.column())?;
+        tuple.serialize_element(point.module_path())?;
+        tuple.serialize_element(&crate::fmt::remap_global(point.file()))?;
+        tuple.serialize_element(&point.note())?;
+        let metadata = point
+            .metadata
+            .as_ref()
+            .filter(|m| m.is_recorded())
+            .map(|m| {
+                (
+                    m.crate_name.as_ref(),
+                    m.crate_version.as_ref(),
+                    m.timestamp.map(crate::serde_impls::timestamp_micros),
+                    m.thread_id,
+                    m.thread_name.as_ref(),
+                )
+            });
+        tuple.serialize_element(&metadata)?;
+        tuple.end()
+    }
+}
+
+struct PortablePoint(ErrorPoint);
+
+type PortablePointMetadata = (
+    Option<String>,
+    Option<String>,
+    Option<u64>,
+    Option<u64>,
+    Option<String>,
+);
+
+impl<'de> Deserialize<'de> for PortablePoint {
+    fn deserialize<D>(deserializer: D) -> Result<PortablePoint, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PointVisitor;
+
+        impl<'de> Visitor<'de> for PointVisitor {
+            type Value = PortablePoint;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a portable error point")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<PortablePoint, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let line = next(&mut seq, 0, &self)?;
+                let column = next(&mut seq, 1, &self)?;
+                let module_path: String = next(&mut seq, 2, &self)?;
+                let file: String = next(&mut seq, 3, &self)?;
+                let note: Option<String> = next(&mut seq, 4, &self)?;
+                let metadata: Option<PortablePointMetadata> = next(&mut seq, 5, &self)?;
+                let metadata = metadata.map(
+                    |(crate_name, crate_version, timestamp_us, thread_id, thread_name)| {
+                        Box::new(PointMetadata {
+                            location: None,
+                            note: None,
+                            crate_name: crate_name.map(Cow::Owned),
+                            crate_version: crate_version.map(Cow::Owned),
+                            timestamp: timestamp_us.map(Duration::from_micros),
+                            thread_id,
+                            thread_name: thread_name.map(Cow::Owned),
+                        })
+                    },
+                );
+                let mut metadata = metadata.unwrap_or_default();
+                metadata.note = note.map(Cow::Owned);
+                Ok(PortablePoint(ErrorPoint::owned(
+                    line,
+                    column,
+                    module_path,
+                    file,
+                    Some(metadata),
+                )))
+            }
+        }
+
+        deserializer.deserialize_tuple(6, PointVisitor)
+    }
+}
+
+struct PortableContext<'a>(&'a [KvPair]);
+
+impl<'a> Serialize for PortableContext<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.iter().map(PortableKvPairRef))
+    }
+}
+
+/// Serializes a key/value pair as a `(key, type, value)` tuple, where `type` is the index of the
+/// value's `ThrowContextValues` variant. `StaticStr` values are serialized as `String` values.
+struct PortableKvPairRef<'a>(&'a KvPair);
+
+impl<'a> Serialize for PortableKvPairRef<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(self.0.key())?;
+        match *self.0.value() {
+            ThrowContextValues::Bool(ref v) => {
+                tuple.serialize_element(&0u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Int8(ref v) => {
+                tuple.serialize_element(&1u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Uint8(ref v) => {
+                tuple.serialize_element(&2u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Int16(ref v) => {
+                tuple.serialize_element(&3u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Uint16(ref v) => {
+                tuple.serialize_element(&4u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Int32(ref v) => {
+                tuple.serialize_element(&5u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Uint32(ref v) => {
+                tuple.serialize_element(&6u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Int64(ref v) => {
+                tuple.serialize_element(&7u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Uint64(ref v) => {
+                tuple.serialize_element(&8u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Float32(ref v) => {
+                tuple.serialize_element(&9u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::Float64(ref v) => {
+                tuple.serialize_element(&10u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::String(ref v) => {
+                tuple.serialize_element(&11u8)?;
+                tuple.serialize_element(v)?;
+            }
+            ThrowContextValues::StaticStr(v) => {
+                tuple.serialize_element(&11u8)?;
+                tuple.serialize_element(v)?;
+            }
+        }
+        tuple.end()
+    }
+}
+
+struct PortableKvPair(KvPair);
+
+impl<'de> Deserialize<'de> for PortableKvPair {
+    fn deserialize<D>(deserializer: D) -> Result<PortableKvPair, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct KvPairVisitor;
+
+        impl<'de> Visitor<'de> for KvPairVisitor {
+            type Value = PortableKvPair;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a portable key/value pair")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<PortableKvPair, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let key: String = next(&mut seq, 0, &self)?;
+                let value = match next::<_, u8, _>(&mut seq, 1, &self)? {
+                    0 => ThrowContextValues::Bool(next(&mut seq, 2, &self)?),
+                    1 => ThrowContextValues::Int8(next(&mut seq, 2, &self)?),
+                    2 => ThrowContextValues::Uint8(next(&mut seq, 2, &self)?),
+                    3 => ThrowContextValues::Int16(next(&mut seq, 2, &self)?),
+                    4 => ThrowContextValues::Uint16(next(&mut seq, 2, &self)?),
+                    5 => ThrowContextValues::Int32(next(&mut seq, 2, &self)?),
+                    6 => ThrowContextValues::Uint32(next(&mut seq, 2, &self)?),
+                    7 => ThrowContextValues::Int64(next(&mut seq, 2, &self)?),
+                    8 => ThrowContextValues::Uint64(next(&mut seq, 2, &self)?),
+                    9 => ThrowContextValues::Float32(next(&mut seq, 2, &self)?),
+                    10 => ThrowContextValues::Float64(next(&mut seq, 2, &self)?),
+                    11 => ThrowContextValues::String(next(&mut seq, 2, &self)?),
+                    other => {
+                        return Err(de::Error::invalid_value(
+                            de::Unexpected::Unsigned(other.into()),
+                            &"a context value type from 0 to 11",
+                        ))
+                    }
+                };
+                Ok(PortableKvPair(KvPair {
+                    key: Cow::Owned(key),
+                    value,
+                    point: None,
+                }))
+            }
+        }
+
+        deserializer.deserialize_tuple(3, KvPairVisitor)
+    }
+}
@@ -0,0 +1,457 @@
+//! Serialize and Deserialize implementations for throw's types, written by hand so that the serde
+//! features don't depend on serde_derive.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::time::Duration;
+
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use crate::{
+    Error, ErrorPoint, KvPair, Metadata, MultiError, PointMetadata, Severity, ThrowContextValues,
+};
+
+/// Serializes the inner value, without saying which variant it came from.
+impl Serialize for ThrowContextValues {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            ThrowContextValues::Bool(v) => serializer.serialize_bool(v),
+            ThrowContextValues::Int8(v) => serializer.serialize_i8(v),
+            ThrowContextValues::Uint8(v) => serializer.serialize_u8(v),
+            ThrowContextValues::Int16(v) => serializer.serialize_i16(v),
+            ThrowContextValues::Uint16(v) => serializer.serialize_u16(v),
+            ThrowContextValues::Int32(v) => serializer.serialize_i32(v),
+            ThrowContextValues::Uint32(v) => serializer.serialize_u32(v),
+            ThrowContextValues::Int64(v) => serializer.serialize_i64(v),
+            ThrowContextValues::Uint64(v) => serializer.serialize_u64(v),
+            ThrowContextValues::Float32(v) => serializer.serialize_f32(v),
+            ThrowContextValues::Float64(v) => serializer.serialize_f64(v),
+            ThrowContextValues::String(ref v) => serializer.serialize_str(v),
+            ThrowContextValues::StaticStr(v) => serializer.serialize_str(v),
+        }
+    }
+}
+
+/// Deserializes booleans, 64-bit integers, 64-bit floats and strings, as the widest variant which
+/// can hold the value.
+impl<'de> Deserialize<'de> for ThrowContextValues {
+    fn deserialize<D>(deserializer: D) -> Result<ThrowContextValues, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = ThrowContextValues;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a boolean, number or string")
+            }
+
+            fn visit_bool<Err>(self, v: bool) -> Result<ThrowContextValues, Err> {
+                Ok(ThrowContextValues::Bool(v))
+            }
+
+            fn visit_i64<Err>(self, v: i64) -> Result<ThrowContextValues, Err> {
+                Ok(ThrowContextValues::Int64(v))
+            }
+
+            fn visit_u64<Err>(self, v: u64) -> Result<ThrowContextValues, Err> {
+                Ok(ThrowContextValues::Uint64(v))
+            }
+
+            fn visit_f64<Err>(self, v: f64) -> Result<ThrowContextValues, Err> {
+                Ok(ThrowContextValues::Float64(v))
+            }
+
+            fn visit_str<Err>(self, v: &str) -> Result<ThrowContextValues, Err> {
+                Ok(ThrowContextValues::String(v.into()))
+            }
+
+            fn visit_string<Err>(self, v: String) -> Result<ThrowContextValues, Err> {
+                Ok(ThrowContextValues::String(v))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Deserializes a struct's field name as its index in the given list of names, or `None` if it
+/// isn't one of them.
+struct FieldSeed(&'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for FieldSeed {
+    type Value = Option<usize>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Option<usize>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldSeed {
+    type Value = Option<usize>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a field name")
+    }
+
+    fn visit_u64<Err>(self, v: u64) -> Result<Option<usize>, Err> {
+        Ok(if (v as usize) < self.0.len() {
+            Some(v as usize)
+        } else {
+            None
+        })
+    }
+
+    fn visit_str<Err>(self, v: &str) -> Result<Option<usize>, Err> {
+        Ok(self.0.iter().position(|&name| name == v))
+    }
+
+    fn visit_bytes<Err>(self, v: &[u8]) -> Result<Option<usize>, Err> {
+        Ok(self.0.iter().position(|&name| name.as_bytes() == v))
+    }
+}
+
+/// Sets a field read from a map, failing if it has already been set.
+fn set_field<'de, A, T>(
+    map: &mut A,
+    field: &mut Option<T>,
+    name: &'static str,
+) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+    T: Deserialize<'de>,
+{
+    if field.is_some() {
+        return Err(de::Error::duplicate_field(name));
+    }
+    *field = Some(map.next_value()?);
+    Ok(())
+}
+
+/// Gets the next element of a sequence, failing if there isn't one.
+fn next_element<'de, A, T>(seq: &mut A, index: usize, expected: &str) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| de::Error::invalid_length(index, &expected))
+}
+
+const POINT_FIELDS: &[&str] = &[
+    "line",
+    "column",
+    "module_path",
+    "file",
+    "note",
+    "crate_name",
+    "crate_version",
+    "timestamp_us",
+    "thread_id",
+    "thread_name",
+];
+
+/// Converts a timestamp to the number of microseconds since the Unix epoch, as it's serialized.
+pub(crate) fn timestamp_micros(timestamp: Duration) -> u64 {
+    timestamp.as_secs() * 1_000_000 + u64::from(timestamp.subsec_micros())
+}
+
+/// Fields which aren't recorded, such as the note or metadata only recorded with some features,
+/// are left out, rather than serialized as null.
+impl Serialize for ErrorPoint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = 4
+            + self.note().is_some() as usize
+            + self.crate_name().is_some() as usize
+            + self.crate_version().is_some() as usize
+            + self.timestamp().is_some() as usize
+            + self.thread_id().is_some() as usize
+            + self.thread_name().is_some() as usize;
+        let mut state = serializer.serialize_struct("ErrorPoint", len)?;
+        state.serialize_field("line", &self.line)?;
+        state.serialize_field("column", &self.column)?;
+        state.serialize_field("module_path", self.module_path())?;
+        state.serialize_field("file", &crate::fmt::remap_global(self.file()))?;
+        if let Some(note) = self.note() {
+            state.serialize_field("note", note)?;
+        }
+        if let Some(crate_name) = self.crate_name() {
+            state.serialize_field("crate_name", crate_name)?;
+        }
+        if let Some(crate_version) = self.crate_version() {
+            state.serialize_field("crate_version", crate_version)?;
+        }
+        if let Some(timestamp) = self.timestamp() {
+            state.serialize_field("timestamp_us", &timestamp_micros(timestamp))?;
+        }
+        if let Some(thread_id) = self.thread_id() {
+            state.serialize_field("thread_id", &thread_id)?;
+        }
+        if let Some(thread_name) = self.thread_name() {
+            state.serialize_field("thread_name", thread_name)?;
+        }
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorPoint {
+    fn deserialize<D>(deserializer: D) -> Result<ErrorPoint, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PointVisitor;
+
+        impl<'de> Visitor<'de> for PointVisitor {
+            type Value = ErrorPoint;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("struct ErrorPoint")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<ErrorPoint, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let expected = "struct ErrorPoint with 4 or 5 elements";
+                let line = next_element(&mut seq, 0, expected)?;
+                let column = next_element(&mut seq, 1, expected)?;
+                let module_path = next_element(&mut seq, 2, expected)?;
+                let file = next_element(&mut seq, 3, expected)?;
+                let metadata = PointMetadata {
+                    note: seq.next_element()?.unwrap_or_default(),
+                    ..PointMetadata::default()
+                };
+                Ok(ErrorPoint::owned(
+                    line,
+                    column,
+                    module_path,
+                    file,
+                    Some(Box::new(metadata)),
+                ))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<ErrorPoint, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut line = None;
+                let mut column = None;
+                let mut module_path: Option<String> = None;
+                let mut file: Option<String> = None;
+                let mut note = None;
+                let mut crate_name = None;
+                let mut crate_version = None;
+                let mut timestamp_us: Option<Option<u64>> = None;
+                let mut thread_id = None;
+                let mut thread_name = None;
+                while let Some(field) = map.next_key_seed(FieldSeed(POINT_FIELDS))? {
+                    match field {
+                        Some(0) => set_field(&mut map, &mut line, "line")?,
+                        Some(1) => set_field(&mut map, &mut column, "column")?,
+                        Some(2) => set_field(&mut map, &mut module_path, "module_path")?,
+                        Some(3) => set_field(&mut map, &mut file, "file")?,
+                        Some(4) => set_field(&mut map, &mut note, "note")?,
+                        Some(5) => set_field(&mut map, &mut crate_name, "crate_name")?,
+                        Some(6) => set_field(&mut map, &mut crate_version, "crate_version")?,
+                        Some(7) => set_field(&mut map, &mut timestamp_us, "timestamp_us")?,
+                        Some(8) => set_field(&mut map, &mut thread_id, "thread_id")?,
+                        Some(_) => set_field(&mut map, &mut thread_name, "thread_name")?,
+                        None => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                let metadata = PointMetadata {
+                    location: None,
+                    note: note.unwrap_or_default(),
+                    crate_name: crate_name.unwrap_or_default(),
+                    crate_version: crate_version.unwrap_or_default(),
+                    timestamp: timestamp_us.unwrap_or_default().map(Duration::from_micros),
+                    thread_id: thread_id.unwrap_or_default(),
+                    thread_name: thread_name.unwrap_or_default(),
+                };
+                Ok(ErrorPoint::owned(
+                    line.ok_or_else(|| de::Error::missing_field("line"))?,
+                    column.ok_or_else(|| de::Error::missing_field("column"))?,
+                    module_path.ok_or_else(|| de::Error::missing_field("module_path"))?,
+                    file.ok_or_else(|| de::Error::missing_field("file"))?,
+                    Some(Box::new(metadata)),
+                ))
+            }
+        }
+
+        deserializer.deserialize_struct("ErrorPoint", POINT_FIELDS, PointVisitor)
+    }
+}
+
+const KV_PAIR_FIELDS: &[&str] = &["key", "value"];
+
+impl Serialize for KvPair {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("KvPair", 2)?;
+        state.serialize_field("key", &self.key)?;
+        state.serialize_field("value", &self.value)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for KvPair {
+    fn deserialize<D>(deserializer: D) -> Result<KvPair, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct KvPairVisitor;
+
+        impl<'de> Visitor<'de> for KvPairVisitor {
+            type Value = KvPair;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("struct KvPair")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<KvPair, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let expected = "struct KvPair with 2 elements";
+                Ok(KvPair {
+                    key: next_element(&mut seq, 0, expected)?,
+                    value: next_element(&mut seq, 1, expected)?,
+                    point: None,
+                })
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<KvPair, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut key = None;
+                let mut value = None;
+                while let Some(field) = map.next_key_seed(FieldSeed(KV_PAIR_FIELDS))? {
+                    match field {
+                        Some(0) => set_field(&mut map, &mut key, "key")?,
+                        Some(_) => set_field(&mut map, &mut value, "value")?,
+                        None => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(KvPair {
+                    key: key.ok_or_else(|| de::Error::missing_field("key"))?,
+                    value: value.ok_or_else(|| de::Error::missing_field("value"))?,
+                    point: None,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("KvPair", KV_PAIR_FIELDS, KvPairVisitor)
+    }
+}
+
+const ERROR_FIELDS: &[&str] = &["points", "context", "error", "code", "severity", "help"];
+
+/// Deserializes errors in the format they're serialized in. As the inner error is serialized using
+/// its Display implementation, `E` is usually `String`.
+impl<'de, E: Deserialize<'de>> Deserialize<'de> for Error<E> {
+    fn deserialize<D>(deserializer: D) -> Result<Error<E>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ErrorVisitor<E>(PhantomData<E>);
+
+        impl<'de, E: Deserialize<'de>> Visitor<'de> for ErrorVisitor<E> {
+            type Value = Error<E>;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("struct Error")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Error<E>, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut points: Option<Vec<ErrorPoint>> = None;
+                let mut context: Option<Vec<KvPair>> = None;
+                let mut error = None;
+                let mut code: Option<Option<Cow<'static, str>>> = None;
+                let mut severity: Option<Option<Severity>> = None;
+                let mut help: Option<Option<Cow<'static, str>>> = None;
+                while let Some(field) = map.next_key_seed(FieldSeed(ERROR_FIELDS))? {
+                    match field {
+                        Some(0) => set_field(&mut map, &mut points, "points")?,
+                        Some(1) => set_field(&mut map, &mut context, "context")?,
+                        Some(2) => set_field(&mut map, &mut error, "error")?,
+                        Some(3) => set_field(&mut map, &mut code, "code")?,
+                        Some(4) => set_field(&mut map, &mut severity, "severity")?,
+                        Some(_) => set_field(&mut map, &mut help, "help")?,
+                        None => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                let code = code.unwrap_or_default();
+                let severity = severity.unwrap_or_default();
+                let help = help.unwrap_or_default();
+                let metadata = if code.is_some() || severity.is_some() || help.is_some() {
+                    Some(Box::new(Metadata::new(code, severity, help)))
+                } else {
+                    None
+                };
+                Ok(Error {
+                    points: points.unwrap_or_default(),
+                    context: context.unwrap_or_default(),
+                    metadata,
+                    error: error.ok_or_else(|| de::Error::missing_field("error"))?,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("Error", ERROR_FIELDS, ErrorVisitor(PhantomData))
+    }
+}
+
+/// Serializes a MultiError as a sequence of its errors.
+impl<E: fmt::Display> Serialize for MultiError<E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.errors())
+    }
+}
+
+impl<'de, E: Deserialize<'de>> Deserialize<'de> for MultiError<E> {
+    fn deserialize<D>(deserializer: D) -> Result<MultiError<E>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(MultiError::new)
+    }
+}
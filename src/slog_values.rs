@@ -0,0 +1,161 @@
+use core::fmt;
+
+use slog::{
+    BorrowedKV, Level, Logger, Record, RecordLocation, RecordStatic, Serializer, Value, KV,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use crate::fmt::StackTrace;
+use crate::{Error, ErrorPoint, KvPair, ThrowContextValues};
+
+/// Location of records for errors whose newest point isn't from this program's source.
+static NO_LOCATION: RecordLocation = RecordLocation {
+    file: "",
+    line: 0,
+    column: 0,
+    function: "",
+    module: "throw",
+};
+
+impl<E: fmt::Display> Error<E> {
+    /// Logs this error to a slog logger at the given level.
+    ///
+    /// The record's message is the inner error's Display output, and the rest of the error is
+    /// attached through its `slog::KV` implementation. The record's location is that of the
+    /// error's newest point, so that drains filtering on modules match the module the error was
+    /// last propagated through.
+    ///
+    /// Only available with the `slog` feature.
+    ///
+    /// ```
+    /// # extern crate slog;
+    /// # extern crate throw;
+    /// # #[cfg(feature = "slog")]
+    /// # fn main() {
+    /// let logger = slog::Logger::root(slog::Discard, slog::o!());
+    /// let mut error = throw::Error::new("upload failed");
+    /// error.add_context("bytes", 1024);
+    /// error.log_to(&logger, slog::Level::Warning);
+    /// # }
+    /// # #[cfg(not(feature = "slog"))]
+    /// # fn main() {}
+    /// ```
+    pub fn log_to(&self, logger: &Logger, level: Level) {
+        if level.as_usize() > slog::__slog_static_max_level().as_usize() {
+            return;
+        }
+        let location = self.points().last().and_then(static_location);
+        let record_static = RecordStatic {
+            location: location.as_ref().unwrap_or(&NO_LOCATION),
+            tag: "",
+            level,
+        };
+        logger.log(&Record::new(
+            &record_static,
+            &format_args!("{}", self.error()),
+            BorrowedKV(self),
+        ));
+    }
+}
+
+/// The location of a point, if its file and module are static strings rather than ones parsed or
+/// deserialized at runtime.
+fn static_location(point: &ErrorPoint) -> Option<RecordLocation> {
+    point
+        .static_location()
+        .map(|(module, file)| RecordLocation {
+            file,
+            line: point.line,
+            column: point.column,
+            function: "",
+            module,
+        })
+}
+
+impl Value for ThrowContextValues {
+    fn serialize(
+        &self,
+        _: &slog::Record<'_>,
+        key: slog::Key,
+        serializer: &mut dyn Serializer,
+    ) -> slog::Result {
+        match *self {
+            ThrowContextValues::Bool(x) => serializer.emit_bool(key, x),
+            ThrowContextValues::Int8(x) => serializer.emit_i8(key, x),
+            ThrowContextValues::Uint8(x) => serializer.emit_u8(key, x),
+            ThrowContextValues::Int16(x) => serializer.emit_i16(key, x),
+            ThrowContextValues::Uint16(x) => serializer.emit_u16(key, x),
+            ThrowContextValues::Int32(x) => serializer.emit_i32(key, x),
+            ThrowContextValues::Uint32(x) => serializer.emit_u32(key, x),
+            ThrowContextValues::Int64(x) => serializer.emit_i64(key, x),
+            ThrowContextValues::Uint64(x) => serializer.emit_u64(key, x),
+            ThrowContextValues::Float32(x) => serializer.emit_f32(key, x),
+            ThrowContextValues::Float64(x) => serializer.emit_f64(key, x),
+            ThrowContextValues::String(ref x) => serializer.emit_str(key, x),
+            ThrowContextValues::StaticStr(x) => serializer.emit_str(key, x),
+        }
+    }
+}
+
+/// Adds an error's context, code and points to slog records as key/value pairs:
+///
+/// - each key/value pair of context, under its own key
+/// - `error.code`: the error's code, from `Error::code()`
+/// - `error.stack_trace`: one line for each point, most recent first, as in Display output
+///
+/// As slog keys are static strings, context whose keys aren't, such as in deserialized errors,
+/// is added under one `context` key as `key: value` pairs separated by commas.
+///
+/// Only available with the `slog` feature.
+impl<E> KV for Error<E> {
+    fn serialize(
+        &self,
+        record: &slog::Record<'_>,
+        serializer: &mut dyn Serializer,
+    ) -> slog::Result {
+        for kv in self.get_context() {
+            if let Cow::Borrowed(key) = kv.key {
+                kv.value().serialize(record, key, serializer)?;
+            }
+        }
+        let owned = OwnedKeys(self.get_context());
+        if owned.pairs().next().is_some() {
+            serializer.emit_arguments("context", &format_args!("{}", owned))?;
+        }
+        if let Some(code) = self.code() {
+            serializer.emit_str("error.code", code)?;
+        }
+        if !self.points().is_empty() {
+            serializer.emit_arguments(
+                "error.stack_trace",
+                &format_args!("{}", StackTrace(self.points())),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the context pairs with non-static keys as `key: value` pairs separated by commas.
+struct OwnedKeys<'a>(&'a [KvPair]);
+
+impl<'a> OwnedKeys<'a> {
+    fn pairs(&self) -> impl Iterator<Item = &'a KvPair> {
+        self.0.iter().filter(|kv| matches!(kv.key, Cow::Owned(_)))
+    }
+}
+
+impl<'a> fmt::Display for OwnedKeys<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, kv) in self.pairs().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: {}", kv.key(), kv.value())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::future::Future;
+use std::mem;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::{Error, ErrorPoint, Result};
+
+/// Extension methods for streams of `throw::Result`s, adding ErrorPoints and the index of the
+/// item to the errors they yield.
+///
+/// This trait is implemented for all streams of `throw::Result`s.
+///
+/// Only available with the `futures` feature.
+///
+/// ```
+/// # extern crate throw;
+/// # extern crate futures_executor;
+/// # #[cfg(feature = "futures")]
+/// # fn main() {
+/// use throw::ThrowStreamExt;
+///
+/// struct Items(Vec<throw::Result<u32, &'static str>>);
+///
+/// impl futures_core::Stream for Items {
+///     type Item = throw::Result<u32, &'static str>;
+///
+///     fn poll_next(
+///         mut self: std::pin::Pin<&mut Self>,
+///         _cx: &mut std::task::Context<'_>,
+///     ) -> std::task::Poll<Option<Self::Item>> {
+///         std::task::Poll::Ready(self.0.pop())
+///     }
+/// }
+///
+/// let items = Items(vec![Err(throw::Error::new("bad item")), Ok(2), Ok(1)]);
+/// let errors = futures_executor::block_on(items.up_throw().try_collect_throw()).unwrap_err();
+/// assert_eq!(errors.len(), 1);
+/// assert!(errors[0].to_string().contains("index: 2"));
+/// # }
+/// # #[cfg(not(feature = "futures"))]
+/// # fn main() {}
+/// ```
+pub trait ThrowStreamExt<T, E>: Stream<Item = Result<T, E>> + Sized {
+    /// Adds an ErrorPoint at this location to each error the stream yields, with the index of
+    /// the item in the stream added to its context under the `"index"` key.
+    #[track_caller]
+    fn up_throw(self) -> UpThrow<Self> {
+        UpThrow {
+            stream: self,
+            location: Location::caller(),
+            index: 0,
+        }
+    }
+
+    /// Collects every item of the stream, resolving to all of the values if none of the items
+    /// were errors, and to all of the errors, in the order they were yielded, otherwise.
+    fn try_collect_throw(self) -> TryCollectThrow<Self, T, E> {
+        TryCollectThrow {
+            stream: self,
+            values: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl<S, T, E> ThrowStreamExt<T, E> for S where S: Stream<Item = Result<T, E>> {}
+
+/// Stream returned by `ThrowStreamExt::up_throw`.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct UpThrow<S> {
+    stream: S,
+    location: &'static Location<'static>,
+    index: u64,
+}
+
+impl<S, T, E> Stream for UpThrow<S>
+where
+    S: Stream<Item = Result<T, E>>,
+{
+    type Item = Result<T, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T, E>>> {
+        // SAFETY: `stream` is never moved out of `self`, and the other fields aren't treated as
+        // pinned, as in `UpFuture`.
+        let this = unsafe { self.get_unchecked_mut() };
+        let stream = unsafe { Pin::new_unchecked(&mut this.stream) };
+        let item = match stream.poll_next(cx) {
+            Poll::Ready(Some(item)) => item,
+            poll => return poll,
+        };
+        let index = this.index;
+        this.index += 1;
+        Poll::Ready(Some(item.map_err(|mut error: Error<E>| {
+            error.__push_point(ErrorPoint::from_caller(this.location));
+            error.add_context("index", index);
+            crate::__private::PointHook::new(None).run(&error);
+            error
+        })))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+/// Future returned by `ThrowStreamExt::try_collect_throw`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TryCollectThrow<S, T, E> {
+    stream: S,
+    values: Vec<T>,
+    errors: Vec<Error<E>>,
+}
+
+impl<S, T, E> Future for TryCollectThrow<S, T, E>
+where
+    S: Stream<Item = Result<T, E>>,
+{
+    type Output = std::result::Result<Vec<T>, Vec<Error<E>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: as in `UpThrow::poll_next`.
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut stream = unsafe { Pin::new_unchecked(&mut this.stream) };
+        loop {
+            match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(value))) => {
+                    if this.errors.is_empty() {
+                        this.values.push(value);
+                    }
+                }
+                Poll::Ready(Some(Err(error))) => {
+                    this.values.clear();
+                    this.errors.push(error);
+                }
+                Poll::Ready(None) => {
+                    return Poll::Ready(if this.errors.is_empty() {
+                        Ok(mem::take(&mut this.values))
+                    } else {
+                        Err(mem::take(&mut this.errors))
+                    });
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::{Error, KvPair, ThrowContextValues};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static SYSTEM: OnceLock<System> = OnceLock::new();
+
+/// The process and host details, looked up once.
+struct System {
+    pid: u32,
+    executable: Option<String>,
+    hostname: Option<String>,
+}
+
+impl System {
+    fn get() -> &'static System {
+        SYSTEM.get_or_init(|| System {
+            pid: std::process::id(),
+            executable: std::env::current_exe()
+                .ok()
+                .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned())),
+            hostname: hostname(),
+        })
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = KvPair> + '_ {
+        let pairs = vec![
+            Some(("pid", ThrowContextValues::from(self.pid))),
+            self.executable
+                .clone()
+                .map(|executable| ("executable", executable.into())),
+            self.hostname
+                .clone()
+                .map(|hostname| ("hostname", hostname.into())),
+            Some(("os", std::env::consts::OS.into())),
+        ];
+        pairs
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| KvPair::new(key, value))
+    }
+}
+
+fn hostname() -> Option<String> {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .chain(std::fs::read_to_string("/etc/hostname").ok())
+        .map(|hostname| hostname.trim().to_owned())
+        .find(|hostname| !hostname.is_empty())
+}
+
+/// Sets whether every `throw::Error` created from now on starts out with the system context
+/// described in `Error::with_system_context()`. Disabled by default.
+///
+/// Only available with the `system-context` feature.
+pub fn set_system_context(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Adds the system context to a new error's context, if enabled with `set_system_context()`.
+pub(crate) fn extend_if_enabled(context: &mut Vec<KvPair>) {
+    if ENABLED.load(Ordering::Relaxed) {
+        context.extend(System::get().pairs());
+    }
+}
+
+impl<E> Error<E> {
+    /// Adds the process id, executable name, hostname and operating system to this error's
+    /// context, under the `"pid"`, `"executable"`, `"hostname"` and `"os"` keys.
+    ///
+    /// The executable name and hostname are left out if they can't be found. The hostname is read
+    /// from the `HOSTNAME` or `COMPUTERNAME` environment variables, or from `/etc/hostname`.
+    ///
+    /// Only available with the `system-context` feature.
+    ///
+    /// ```
+    /// # #[cfg(feature = "system-context")]
+    /// # fn main() {
+    /// let error = throw::Error::new("failed").with_system_context();
+    /// let pid = std::process::id().to_string();
+    /// assert!(error
+    ///     .get_context()
+    ///     .iter()
+    ///     .any(|pair| pair.key() == "pid" && pair.value().to_string() == pid));
+    /// # }
+    /// # #[cfg(not(feature = "system-context"))]
+    /// # fn main() {}
+    /// ```
+    pub fn with_system_context(mut self) -> Error<E> {
+        self.context.extend(System::get().pairs());
+        self
+    }
+}
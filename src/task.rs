@@ -0,0 +1,128 @@
+//! Spawning `tokio` tasks whose errors keep their ErrorPoints.
+//!
+//! Only available with the `tokio` feature.
+
+use std::fmt;
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::{Error, ErrorPoint, PanicError, Result};
+
+/// The error of a task spawned with `throw::task::spawn()`.
+#[derive(Debug)]
+pub enum TaskError<E> {
+    /// The task returned an error.
+    Failed(E),
+    /// The task panicked.
+    Panicked(PanicError),
+    /// The task was cancelled, with `JoinHandle::abort()` or by its runtime shutting down.
+    Cancelled,
+}
+
+impl<E: fmt::Display> fmt::Display for TaskError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TaskError::Failed(ref error) => error.fmt(fmt),
+            TaskError::Panicked(ref error) => write!(fmt, "task {}", error),
+            TaskError::Cancelled => fmt.write_str("task was cancelled"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for TaskError<E> {}
+
+/// Spawns a task returning a `throw::Result` on the current `tokio` runtime, as with
+/// `tokio::spawn()`.
+///
+/// The returned `JoinHandle` resolves to a `throw::Result` with an ErrorPoint at this location
+/// when the task fails. An error returned by the task keeps its points and context, with the inner
+/// error wrapped in `TaskError::Failed`, and a panic or cancellation becomes a new error. The
+/// point's module path is `<unknown>`, as with `throw::ThrowFutureExt`.
+///
+/// ```
+/// # #[cfg(feature = "tokio")]
+/// # fn main() {
+/// use throw::task::TaskError;
+///
+/// async fn fetch(id: u32) -> throw::Result<String, &'static str> {
+///     throw::throw_new!("not found", "id" => id);
+/// }
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let error = runtime
+///     .block_on(async { throw::task::spawn(fetch(4)).await })
+///     .unwrap_err();
+/// assert!(matches!(error.error(), TaskError::Failed("not found")));
+/// assert_eq!(error.points().len(), 2);
+/// # }
+/// # #[cfg(not(feature = "tokio"))]
+/// # fn main() {}
+/// ```
+///
+/// # Panics
+///
+/// Panics if called outside of a `tokio` runtime, as `tokio::spawn()` does.
+#[track_caller]
+pub fn spawn<F, T, E>(future: F) -> JoinHandle<T, E>
+where
+    F: Future<Output = Result<T, E>> + Send + 'static,
+    T: Send + 'static,
+    E: Send + 'static,
+{
+    JoinHandle {
+        location: Location::caller(),
+        inner: tokio::spawn(future),
+    }
+}
+
+/// A handle to a task spawned with `throw::task::spawn()`, resolving to its result.
+#[derive(Debug)]
+#[must_use = "dropping the handle detaches the task, losing its result"]
+pub struct JoinHandle<T, E> {
+    inner: tokio::task::JoinHandle<Result<T, E>>,
+    location: &'static Location<'static>,
+}
+
+impl<T, E> JoinHandle<T, E> {
+    /// Cancels the task, making this handle resolve to `TaskError::Cancelled` if it hasn't
+    /// finished yet.
+    pub fn abort(&self) {
+        self.inner.abort();
+    }
+
+    /// Checks whether the task has finished.
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+}
+
+impl<T, E> Future for JoinHandle<T, E> {
+    type Output = Result<T, TaskError<E>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T, TaskError<E>>> {
+        let result = match Pin::new(&mut self.inner).poll(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => return Poll::Pending,
+        };
+        let mut error = match result {
+            Ok(Ok(value)) => return Poll::Ready(Ok(value)),
+            Ok(Err(error)) => Error {
+                points: error.points,
+                context: error.context,
+                metadata: error.metadata,
+                error: TaskError::Failed(error.error),
+            },
+            Err(join_error) => match join_error.try_into_panic() {
+                Ok(payload) => Error::new(TaskError::Panicked(crate::catch_panic::from_payload(
+                    &*payload,
+                ))),
+                Err(_) => Error::new(TaskError::Cancelled),
+            },
+        };
+        error.__push_point(ErrorPoint::from_caller(self.location));
+        crate::__private::PointHook::new(None).run(&error);
+        Poll::Ready(Err(error))
+    }
+}
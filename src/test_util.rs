@@ -0,0 +1,147 @@
+//! Helpers for asserting on the rendered output of errors in tests.
+//!
+//! Available with the `test-util` feature.
+//!
+//! In expected output, four spaces may be used in place of the tab character which indents each
+//! line of context and each point. Line and column numbers in `at line:column in ...` lines are
+//! ignored by `trace_eq` and `assert_trace_eq!()`, and may be written as `_:_`, so tests don't
+//! break when the code they exercise moves. So are the crate, timestamp and thread recorded for
+//! each point with the `crate-info`, `timestamps` and `thread-info` features.
+
+pub use crate::{assert_trace_eq, assert_trace_matches};
+
+/// Replaces the line and column numbers of each point in rendered error output with `_:_`, removes
+/// the crate, timestamp and thread recorded for each point, and replaces leading indentation of
+/// four spaces with a tab.
+///
+/// ```
+/// use throw::test_util::normalize_trace;
+///
+/// assert_eq!(
+///     normalize_trace("Error: oops\n    at 12:4 in foo (src/foo.rs)"),
+///     "Error: oops\n\tat _:_ in foo (src/foo.rs)"
+/// );
+/// ```
+pub fn normalize_trace(trace: &str) -> String {
+    let mut normalized = String::with_capacity(trace.len());
+    for (i, line) in trace.split('\n').enumerate() {
+        if i != 0 {
+            normalized.push('\n');
+        }
+        let line = match line.strip_prefix("    ") {
+            Some(rest) => {
+                normalized.push('\t');
+                rest
+            }
+            None => line,
+        };
+        normalized.push_str(&normalize_point_line(line));
+    }
+    normalized
+}
+
+fn normalize_point_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let rest = match trimmed.strip_prefix("at ") {
+        Some(rest) => rest,
+        None => return line.to_owned(),
+    };
+    let location_end = match rest.find(" in ") {
+        Some(end) => end,
+        None => return line.to_owned(),
+    };
+    let location = &rest[..location_end];
+    let is_location = location.split(':').count() == 2
+        && location.split(':').all(|part| {
+            part == "_" || (!part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        });
+    if !is_location {
+        return line.to_owned();
+    }
+    format!(
+        "{}at _:_{}",
+        indent,
+        without_metadata(&rest[location_end..])
+    )
+}
+
+/// Removes the ` [...]` after a point's file holding the crate, timestamp and thread recorded for
+/// it, if there is one.
+fn without_metadata(rest: &str) -> String {
+    if let Some(start) = rest.find(") [") {
+        if let Some(len) = rest[start..].find(']') {
+            let after = &rest[start + len + 1..];
+            if after.is_empty() || after.starts_with(" \u{2014} ") {
+                return format!("{}{}", &rest[..=start], after);
+            }
+        }
+    }
+    rest.to_owned()
+}
+
+/// Compares rendered error output against an expected trace, ignoring point line and column
+/// numbers.
+pub fn trace_eq(expected: &str, actual: &str) -> bool {
+    normalize_trace(expected) == normalize_trace(actual)
+}
+
+/// Matches rendered error output against a regular expression, after replacing each four spaces
+/// in the expression with a tab.
+///
+/// # Panics
+///
+/// Panics if the expression is not a valid regular expression.
+pub fn trace_matches(expected: &str, actual: &str) -> bool {
+    let expected = expected.replace("    ", "\t");
+    regex::Regex::new(&expected)
+        .expect("expected trace regex to compile")
+        .is_match(actual)
+}
+
+/// Asserts that the `Display` output of an error equals an expected trace, ignoring point line and
+/// column numbers. See the `throw::test_util` module for details.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn possibly_fails() -> throw::Result<(), &'static str> {
+///     throw_new!("oops", "key" => "value");
+/// }
+///
+/// fn main() {
+///     assert_trace_eq!(
+///         possibly_fails().unwrap_err(),
+///         "Error: oops\n\tkey: value\n\tat _:_ in rust_out (src/test_util.rs)"
+///     );
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_trace_eq {
+    ($actual:expr, $expected:expr $(,)*) => {{
+        let actual = format!("{}", $actual);
+        let expected = $expected;
+        assert!(
+            $crate::test_util::trace_eq(&expected, &actual),
+            "expected error to equal `\n{}\n`, but found `\n{}\n`",
+            $crate::test_util::normalize_trace(&expected),
+            actual
+        );
+    }};
+}
+
+/// Asserts that the `Display` output of an error matches a regular expression, after replacing
+/// each four spaces in the expression with a tab.
+#[macro_export]
+macro_rules! assert_trace_matches {
+    ($actual:expr, $expected:expr $(,)*) => {{
+        let actual = format!("{}", $actual);
+        let expected = $expected;
+        assert!(
+            $crate::test_util::trace_matches(&expected, &actual),
+            "expected error to match regex `\n{}\n`, but found `\n{}\n`",
+            expected,
+            actual
+        );
+    }};
+}
@@ -0,0 +1,164 @@
+//! Middleware reporting the errors returned by `tower` services.
+//!
+//! Only available with the `tower` feature.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{Error, ThrowContextValues};
+
+/// Context taken from each request by a `ThrowReport` service, added to any error the request
+/// fails with.
+///
+/// Implemented for `()`, taking no context, and for closures returning key/value pairs.
+pub trait RequestContext<Request> {
+    /// Takes the key/value pairs to add to an error from a request.
+    fn request_context(&self, request: &Request) -> Vec<(&'static str, ThrowContextValues)>;
+}
+
+impl<Request> RequestContext<Request> for () {
+    fn request_context(&self, _request: &Request) -> Vec<(&'static str, ThrowContextValues)> {
+        Vec::new()
+    }
+}
+
+impl<Request, F> RequestContext<Request> for F
+where
+    F: Fn(&Request) -> Vec<(&'static str, ThrowContextValues)>,
+{
+    fn request_context(&self, request: &Request) -> Vec<(&'static str, ThrowContextValues)> {
+        self(request)
+    }
+}
+
+/// A `tower` layer wrapping services which fail with a `throw::Error<E>`.
+///
+/// When the wrapped service fails, the error is given context taken from the request, then
+/// reported to the hook set with `throw::set_hook()`, as for its newest point, and its inner error
+/// is returned. This gives stacks built on `tower`, such as those of hyper, tonic and axum, one
+/// place to report every error.
+///
+/// ```
+/// # extern crate throw;
+/// # #[cfg(feature = "tower")]
+/// # fn main() {
+/// use throw::tower::ThrowReportLayer;
+/// use throw::ThrowContextValues;
+///
+/// let layer = ThrowReportLayer::new()
+///     .request_context(|path: &String| vec![("path", ThrowContextValues::from(path.clone()))]);
+/// # let _ = layer;
+/// # }
+/// # #[cfg(not(feature = "tower"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThrowReportLayer<C = ()> {
+    context: C,
+}
+
+impl ThrowReportLayer {
+    /// Creates a layer which doesn't add any context from requests.
+    pub fn new() -> ThrowReportLayer {
+        ThrowReportLayer { context: () }
+    }
+}
+
+impl<C> ThrowReportLayer<C> {
+    /// Sets the context taken from each request, such as its method and path, which is added to
+    /// the error if the request fails.
+    pub fn request_context<F>(self, context: F) -> ThrowReportLayer<F> {
+        ThrowReportLayer { context }
+    }
+}
+
+impl<S, C: Clone> Layer<S> for ThrowReportLayer<C> {
+    type Service = ThrowReport<S, C>;
+
+    fn layer(&self, inner: S) -> ThrowReport<S, C> {
+        ThrowReport {
+            inner,
+            context: self.context.clone(),
+        }
+    }
+}
+
+/// A service reporting the errors of the service it wraps, created by `ThrowReportLayer`.
+#[derive(Debug, Clone)]
+pub struct ThrowReport<S, C> {
+    inner: S,
+    context: C,
+}
+
+impl<S, C, Request, E> Service<Request> for ThrowReport<S, C>
+where
+    S: Service<Request, Error = Error<E>>,
+    C: RequestContext<Request>,
+    E: fmt::Display,
+{
+    type Response = S::Response;
+    type Error = E;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+        self.inner
+            .poll_ready(cx)
+            .map_err(|error| report(error, Vec::new()))
+    }
+
+    fn call(&mut self, request: Request) -> ResponseFuture<S::Future> {
+        ResponseFuture {
+            context: Some(self.context.request_context(&request)),
+            inner: self.inner.call(request),
+        }
+    }
+}
+
+pin_project! {
+    /// The future returned by `ThrowReport`.
+    pub struct ResponseFuture<F> {
+        #[pin]
+        inner: F,
+        context: Option<Vec<(&'static str, ThrowContextValues)>>,
+    }
+}
+
+impl<F, T, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<T, Error<E>>>,
+    E: fmt::Display,
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T, E>> {
+        let this = self.project();
+        match this.inner.poll(cx) {
+            Poll::Ready(Err(error)) => {
+                let context = this.context.take().unwrap_or_default();
+                Poll::Ready(Err(report(error, context)))
+            }
+            Poll::Ready(Ok(response)) => Poll::Ready(Ok(response)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Adds the request's context to an error, reports it to the hook, and takes out its inner error.
+fn report<E: fmt::Display>(
+    mut error: Error<E>,
+    context: Vec<(&'static str, ThrowContextValues)>,
+) -> E {
+    for (key, value) in context {
+        error.add_context(key, value);
+    }
+    if crate::hook::is_active() {
+        crate::hook::run(&error, Some(&error.error().to_string()));
+    }
+    error.into_origin()
+}
@@ -0,0 +1,129 @@
+use core::fmt;
+
+use tracing::field::display;
+use tracing_error::{SpanTrace, SpanTraceStatus};
+
+use crate::fmt::StackTrace;
+use crate::{Error, ErrorPoint, KvPair, Metadata, Severity};
+
+impl<E> Error<E> {
+    /// Gets the `tracing` spans this error was created in, innermost first. These are captured
+    /// by `Error::new()` when `tracing_error::ErrorLayer` is installed in the current subscriber
+    /// and a span is entered.
+    ///
+    /// Only available with the `tracing` feature.
+    pub fn span_trace(&self) -> Option<&SpanTrace> {
+        self.metadata.as_ref().and_then(|m| m.span_trace.as_ref())
+    }
+}
+
+/// Captures the current span trace into new metadata, if there's one to capture.
+pub(crate) fn capture_span_trace() -> Option<Box<Metadata>> {
+    let span_trace = SpanTrace::capture();
+    if span_trace.status() != SpanTraceStatus::CAPTURED {
+        return None;
+    }
+    Some(Box::new(Metadata {
+        span_trace: Some(span_trace),
+        ..Metadata::default()
+    }))
+}
+
+impl<E: fmt::Display> Error<E> {
+    /// Records a `tracing` event describing this error, in whichever span is current.
+    ///
+    /// The event's message is the inner error's Display output, and it has these fields, with
+    /// any which don't apply left out:
+    ///
+    /// - `context`: each key/value pair of context, as `key: value`, separated by commas
+    /// - `code`: the error's code, from `Error::code()`
+    /// - `origin`: the `file:line:column` where the error was first thrown
+    /// - `stack_trace`: one line for each point, most recent first, as in Display output
+    ///
+    /// The event is recorded with the target `throw`, at the `ERROR` level, or at `WARN` or
+    /// `INFO` for errors with `Severity::Warning` or `Severity::Advice`.
+    ///
+    /// Only available with the `tracing` feature.
+    ///
+    /// ```
+    /// # extern crate throw;
+    /// # #[cfg(feature = "tracing")]
+    /// # fn main() {
+    /// let mut error = throw::Error::new("upload failed");
+    /// error.add_context("bytes", 1024);
+    /// error.emit();
+    /// # }
+    /// # #[cfg(not(feature = "tracing"))]
+    /// # fn main() {}
+    /// ```
+    pub fn emit(&self) {
+        let context = Some(ContextFields(self.get_context()))
+            .filter(|context| !context.0.is_empty())
+            .map(display);
+        let code = self.code();
+        let origin = self.points().first().map(|point| display(Origin(point)));
+        let stack_trace = Some(StackTrace(self.points()))
+            .filter(|trace| !trace.0.is_empty())
+            .map(display);
+        match self.severity() {
+            None | Some(Severity::Error) => tracing::error!(
+                target: "throw",
+                context = context,
+                code = code,
+                origin = origin,
+                stack_trace = stack_trace,
+                "{}",
+                self.error()
+            ),
+            Some(Severity::Warning) => tracing::warn!(
+                target: "throw",
+                context = context,
+                code = code,
+                origin = origin,
+                stack_trace = stack_trace,
+                "{}",
+                self.error()
+            ),
+            Some(Severity::Advice) => tracing::info!(
+                target: "throw",
+                context = context,
+                code = code,
+                origin = origin,
+                stack_trace = stack_trace,
+                "{}",
+                self.error()
+            ),
+        }
+    }
+}
+
+/// Renders context as `key: value` pairs separated by commas.
+struct ContextFields<'a>(&'a [KvPair]);
+
+impl<'a> fmt::Display for ContextFields<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, kv) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: {}", kv.key(), kv.value())?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders a point as `file:line:column`.
+struct Origin<'a>(&'a ErrorPoint);
+
+impl<'a> fmt::Display for Origin<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let point = self.0;
+        write!(
+            f,
+            "{}:{}:{}",
+            crate::fmt::remap_global(point.file()),
+            point.line(),
+            point.column()
+        )
+    }
+}
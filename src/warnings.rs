@@ -0,0 +1,135 @@
+use core::fmt;
+use core::slice;
+
+use crate::{Error, MultiError, Result};
+
+#[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// A collector of warnings: problems which don't stop the current operation, but should still be
+/// reported with the same points and context as errors.
+///
+/// Warnings are added with `throw_warn!()`, which records an ErrorPoint and context like
+/// `throw_new!()`, but pushes the new error onto the collector with `Severity::Warning` rather
+/// than returning it. If the operation fails anyway, `attach_to()` or `finish()` add the warnings
+/// to the final error's context.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate throw;
+/// fn parse(
+///     lines: &[&str],
+///     warnings: &mut throw::Warnings<&'static str>,
+/// ) -> throw::Result<usize, &'static str> {
+///     for (number, line) in lines.iter().enumerate() {
+///         if line.is_empty() {
+///             throw_warn!(warnings, "empty line", "line" => number as u64 + 1);
+///         }
+///     }
+///     Ok(lines.len())
+/// }
+///
+/// fn main() {
+///     let mut warnings = throw::Warnings::new();
+///     let result = parse(&["a", "", "b"], &mut warnings);
+///     let (count, warnings) = warnings.finish(result).unwrap();
+///     assert_eq!(count, 3);
+///     assert_eq!(warnings.len(), 1);
+///     assert_eq!(warnings.warnings()[0].severity(), Some(throw::Severity::Warning));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Warnings<W> {
+    warnings: Vec<Error<W>>,
+}
+
+impl<W> Warnings<W> {
+    /// Creates an empty collector.
+    pub fn new() -> Warnings<W> {
+        Warnings {
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Adds a warning. Used by `throw_warn!()`, which also sets its severity.
+    pub fn push(&mut self, warning: Error<W>) {
+        self.warnings.push(warning);
+    }
+
+    /// Gets the warnings, in the order they were added.
+    #[inline]
+    pub fn warnings(&self) -> &[Error<W>] {
+        &self.warnings
+    }
+
+    /// Iterates over the warnings, in the order they were added.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Error<W>> {
+        self.warnings.iter()
+    }
+
+    /// The number of warnings.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.warnings.len()
+    }
+
+    /// Checks whether there are no warnings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Turns the warnings into a `MultiError`, for reporting them together.
+    pub fn into_multi_error(self) -> MultiError<W> {
+        MultiError::new(self.warnings)
+    }
+}
+
+impl<W: fmt::Display> Warnings<W> {
+    /// Adds each warning to an error's context under the `"warning"` key, as the warning's compact
+    /// Display output, `{:#}`, including the location it was recorded at.
+    pub fn attach_to<E>(&self, error: &mut Error<E>) {
+        for warning in &self.warnings {
+            error.add_context("warning", format!("{:#}", warning));
+        }
+    }
+
+    /// Finishes an operation which recorded warnings: returns the value along with the warnings
+    /// if it succeeded, and the error with the warnings attached as in `attach_to()` otherwise.
+    pub fn finish<T, E>(self, result: Result<T, E>) -> Result<(T, Warnings<W>), E> {
+        match result {
+            Ok(value) => Ok((value, self)),
+            Err(mut error) => {
+                self.attach_to(&mut error);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl<W> Default for Warnings<W> {
+    fn default() -> Warnings<W> {
+        Warnings::new()
+    }
+}
+
+impl<W> IntoIterator for Warnings<W> {
+    type Item = Error<W>;
+    type IntoIter = <Vec<Error<W>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.warnings.into_iter()
+    }
+}
+
+impl<'a, W> IntoIterator for &'a Warnings<W> {
+    type Item = &'a Error<W>;
+    type IntoIter = slice::Iter<'a, Error<W>>;
+
+    fn into_iter(self) -> slice::Iter<'a, Error<W>> {
+        self.warnings.iter()
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, ErrorPoint, ThrowContextValues};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn console_error(value: &JsValue);
+}
+
+/// Sets a property of a JavaScript object. This only fails for frozen objects and proxies, which
+/// are never passed here.
+fn set(target: &JsValue, key: &str, value: &JsValue) {
+    let _ = Reflect::set(target, &JsValue::from_str(key), value);
+}
+
+fn context_value(value: &ThrowContextValues) -> JsValue {
+    match *value {
+        ThrowContextValues::Bool(v) => JsValue::from_bool(v),
+        ThrowContextValues::Int8(v) => JsValue::from_f64(v.into()),
+        ThrowContextValues::Uint8(v) => JsValue::from_f64(v.into()),
+        ThrowContextValues::Int16(v) => JsValue::from_f64(v.into()),
+        ThrowContextValues::Uint16(v) => JsValue::from_f64(v.into()),
+        ThrowContextValues::Int32(v) => JsValue::from_f64(v.into()),
+        ThrowContextValues::Uint32(v) => JsValue::from_f64(v.into()),
+        ThrowContextValues::Int64(v) => JsValue::from_f64(v as f64),
+        ThrowContextValues::Uint64(v) => JsValue::from_f64(v as f64),
+        ThrowContextValues::Float32(v) => JsValue::from_f64(v.into()),
+        ThrowContextValues::Float64(v) => JsValue::from_f64(v),
+        ThrowContextValues::String(ref v) => JsValue::from_str(v),
+        ThrowContextValues::StaticStr(v) => JsValue::from_str(v),
+    }
+}
+
+fn point_object(point: &ErrorPoint) -> JsValue {
+    let object = JsValue::from(Object::new());
+    set(&object, "line", &JsValue::from_f64(point.line().into()));
+    set(&object, "column", &JsValue::from_f64(point.column().into()));
+    set(
+        &object,
+        "module_path",
+        &JsValue::from_str(point.module_path()),
+    );
+    set(&object, "file", &JsValue::from_str(point.file()));
+    if let Some(note) = point.note() {
+        set(&object, "note", &JsValue::from_str(note));
+    }
+    object
+}
+
+impl<E: fmt::Display> Error<E> {
+    /// Creates a JavaScript `Error` describing this error.
+    ///
+    /// Its `message` is this error's full Display output, including context and points, and its
+    /// `cause` is the inner error's Display output. The points are in its `points` property, as
+    /// an array of objects with `line`, `column`, `module_path`, `file` and (if given) `note`
+    /// properties, and the context is in its `context` property, as an object with later values
+    /// replacing earlier ones with the same key. Its `code` property is the error code, if set.
+    ///
+    /// 64-bit integer context is converted to a JavaScript number, so large values lose precision,
+    /// as they do in JSON.
+    ///
+    /// Only available with the `wasm` feature.
+    pub fn to_js_error(&self) -> js_sys::Error {
+        let js_error = js_sys::Error::new(&self.to_string());
+        js_error.set_cause(&JsValue::from_str(&self.error().to_string()));
+
+        let points: Array = self.points().iter().map(point_object).collect();
+        set(&js_error, "points", &points);
+
+        let context = JsValue::from(Object::new());
+        for kv in self.get_context() {
+            set(&context, kv.key(), &context_value(kv.value()));
+        }
+        set(&js_error, "context", &context);
+
+        if let Some(code) = self.code() {
+            set(&js_error, "code", &JsValue::from_str(code));
+        }
+        js_error
+    }
+
+    /// Logs this error to the browser or JavaScript runtime's console with `console.error()`, as
+    /// the JavaScript `Error` created by `Error::to_js_error()`.
+    ///
+    /// Only available with the `wasm` feature.
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "wasm")]
+    /// # fn main() {
+    /// use wasm_bindgen::prelude::*;
+    ///
+    /// fn parse(input: &str) -> throw::Result<u32, std::num::ParseIntError> {
+    ///     let value = throw::throw!(input.parse::<u32>(), "input" => input.to_owned());
+    ///     Ok(value)
+    /// }
+    ///
+    /// #[wasm_bindgen]
+    /// pub fn double(input: &str) -> Result<u32, JsValue> {
+    ///     match parse(input) {
+    ///         Ok(value) => Ok(value * 2),
+    ///         Err(error) => {
+    ///             error.console_error();
+    ///             Err(error.into())
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// # #[cfg(not(feature = "wasm"))]
+    /// # fn main() {}
+    /// ```
+    pub fn console_error(&self) {
+        console_error(&self.to_js_error());
+    }
+}
+
+/// Converts an error into a JavaScript `Error`, as created by `Error::to_js_error()`. This keeps
+/// an error's trace when it's returned from a `#[wasm_bindgen]` function.
+///
+/// Only available with the `wasm` feature.
+impl<E: fmt::Display> From<Error<E>> for js_sys::Error {
+    fn from(error: Error<E>) -> js_sys::Error {
+        error.to_js_error()
+    }
+}
+
+/// Converts an error into a JavaScript `Error`, as created by `Error::to_js_error()`, so that
+/// `#[wasm_bindgen]` functions returning `Result<T, JsValue>` can use `?` on a `throw::Result`.
+///
+/// Only available with the `wasm` feature.
+impl<E: fmt::Display> From<Error<E>> for JsValue {
+    fn from(error: Error<E>) -> JsValue {
+        error.to_js_error().into()
+    }
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "actix-web")]
+// Kept in its own test binary, as `set_expose_details()` affects every response in the process.
+
+#[macro_use]
+extern crate throw;
+extern crate actix_web;
+extern crate futures_executor;
+extern crate serde_json;
+
+use actix_web::{HttpResponse, ResponseError};
+use throw::http::HttpStatus;
+use throw::Result;
+
+#[derive(Debug)]
+struct NotFound;
+
+impl std::fmt::Display for NotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("user not found")
+    }
+}
+
+impl HttpStatus for NotFound {
+    fn status(&self) -> u16 {
+        404
+    }
+}
+
+fn get_user() -> Result<(), NotFound> {
+    throw_new!(NotFound, "user_id" => 42);
+}
+
+fn body(response: HttpResponse) -> serde_json::Value {
+    let bytes =
+        futures_executor::block_on(actix_web::body::to_bytes(response.into_body())).unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[test]
+fn test_response_error() {
+    let error = get_user().unwrap_err();
+    assert_eq!(error.status_code(), 404);
+    let response = error.error_response();
+    assert_eq!(response.status(), 404);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/problem+json"
+    );
+    assert_eq!(
+        body(response),
+        serde_json::json!({
+            "type": "about:blank",
+            "title": "user not found",
+            "status": 404,
+        })
+    );
+
+    throw::http::set_expose_details(true);
+    let body = body(get_user().unwrap_err().error_response());
+    assert_eq!(body["user_id"], 42);
+
+    let error: actix_web::Error = throw::Error::new("failed").into();
+    assert_eq!(error.as_response_error().status_code(), 500);
+}
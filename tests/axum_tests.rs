@@ -0,0 +1,79 @@
+#![cfg(feature = "axum")]
+// Kept in its own test binary, as `set_expose_details()` affects every response in the process.
+
+#[macro_use]
+extern crate throw;
+extern crate axum_core;
+extern crate futures_executor;
+extern crate http_body_util;
+extern crate serde_json;
+
+use axum_core::response::{IntoResponse, Response};
+use http_body_util::BodyExt;
+use throw::http::HttpStatus;
+use throw::Result;
+
+#[derive(Debug)]
+enum ApiError {
+    NotFound,
+    Database,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ApiError::NotFound => f.write_str("user not found"),
+            ApiError::Database => f.write_str("connection to db-1 refused"),
+        }
+    }
+}
+
+impl HttpStatus for ApiError {
+    fn status(&self) -> u16 {
+        match self {
+            ApiError::NotFound => 404,
+            ApiError::Database => 500,
+        }
+    }
+}
+
+fn get_user(error: ApiError) -> Result<(), ApiError> {
+    throw_new!(error, "user_id" => 42);
+}
+
+fn body(response: Response) -> serde_json::Value {
+    let collected = futures_executor::block_on(response.into_body().collect()).unwrap();
+    serde_json::from_slice(&collected.to_bytes()).unwrap()
+}
+
+#[test]
+fn test_into_response() {
+    let response = get_user(ApiError::NotFound).unwrap_err().into_response();
+    assert_eq!(response.status(), 404);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    assert_eq!(
+        body(response),
+        serde_json::json!({
+            "type": "about:blank",
+            "title": "user not found",
+            "status": 404,
+        })
+    );
+
+    let response = get_user(ApiError::Database).unwrap_err().into_response();
+    assert_eq!(response.status(), 500);
+    assert_eq!(body(response)["title"], "Internal Server Error");
+
+    throw::http::set_expose_details(true);
+    let response = get_user(ApiError::Database).unwrap_err().into_response();
+    let body = body(response);
+    assert_eq!(body["title"], "connection to db-1 refused");
+    assert_eq!(body["user_id"], 42);
+    assert!(body["detail"]
+        .as_str()
+        .unwrap()
+        .contains("in axum_tests (tests/axum_tests.rs)"));
+}
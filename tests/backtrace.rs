@@ -0,0 +1,30 @@
+#![cfg(feature = "backtrace")]
+// Kept in its own test binary, as `set_force_backtrace()` affects every error created in the
+// process.
+
+#[macro_use]
+extern crate throw;
+
+use std::backtrace::BacktraceStatus;
+
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed");
+}
+
+#[test]
+fn test_force_backtrace() {
+    throw::set_force_backtrace(true);
+    let error = fails().unwrap_err();
+    throw::set_force_backtrace(false);
+
+    let backtrace = error.backtrace().unwrap();
+    assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+    assert!(backtrace.to_string().contains("backtrace::fails"));
+    let rendered = error.display_with_backtrace(backtrace).to_string();
+    assert!(rendered.contains("in backtrace (tests/backtrace.rs)"));
+
+    let report = throw::Report::from(error);
+    assert!(report.backtrace().to_string().contains("backtrace::fails"));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "crash-dump")]
+
+#[macro_use]
+extern crate throw;
+extern crate serde_json;
+
+use std::fs;
+use std::process::ExitCode;
+
+use serde_json::Value;
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed", "attempt" => 3);
+}
+
+fn read_dump(path: &std::path::Path) -> Value {
+    let dump = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+    fs::remove_file(path).unwrap();
+    dump
+}
+
+// Kept in its own test binary, as the panic hook applies to every test running alongside it.
+#[test]
+fn test_crash_dump() {
+    let dir = std::env::temp_dir().join(format!("throw-crash-dump-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let dumps = crash_dump!().dir(&dir);
+
+    let error = fails().unwrap_err();
+    let path = dumps.write(&error).unwrap();
+    assert!(path.starts_with(&dir));
+    let dump = read_dump(&path);
+    assert_eq!(dump["error"], error.to_json_value());
+    assert_eq!(dump["crate"]["name"], "throw");
+    assert_eq!(dump["crate"]["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(dump["os"]["name"], std::env::consts::OS);
+    assert!(dump["timestamp_us"].as_u64().unwrap() > 0);
+
+    assert_eq!(dumps.report(fails), ExitCode::FAILURE);
+    let paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(read_dump(&paths[0])["error"]["error"], "failed");
+
+    dumps.install_panic_hook();
+    assert!(std::panic::catch_unwind(|| panic!("oh no")).is_err());
+    let _ = std::panic::take_hook();
+    let paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(paths.len(), 1);
+    let dump = read_dump(&paths[0]);
+    assert_eq!(dump["panic"]["message"], "oh no");
+    assert!(dump["panic"]["location"]
+        .as_str()
+        .unwrap()
+        .starts_with("tests/crash_dump.rs:"));
+
+    fs::remove_dir(&dir).unwrap();
+}
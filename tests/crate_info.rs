@@ -0,0 +1,44 @@
+#![cfg(feature = "crate-info")]
+// Kept in its own test binary, as the crate name and version change how every point is rendered.
+
+#[macro_use]
+extern crate throw;
+
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed");
+}
+
+fn propagates() -> Result<(), &'static str> {
+    up!(fails());
+    Ok(())
+}
+
+#[test]
+fn test_crate_info() {
+    let error = propagates().unwrap_err();
+    for point in error.points() {
+        assert_eq!(point.crate_name(), Some("crate_info"));
+        assert_eq!(point.crate_version(), Some(env!("CARGO_PKG_VERSION")));
+    }
+
+    let rendered = error.to_string();
+    let location = format!(
+        "(tests/crate_info.rs) [crate_info {}",
+        env!("CARGO_PKG_VERSION")
+    );
+    assert_eq!(rendered.lines().count(), 3);
+    assert!(rendered
+        .lines()
+        .skip(1)
+        .all(|line| line.contains(&location)));
+
+    let parsed: throw::Error<String> = rendered.parse().unwrap();
+    assert_eq!(parsed.points()[0].crate_name(), Some("crate_info"));
+    assert_eq!(
+        parsed.points()[0].crate_version(),
+        Some(env!("CARGO_PKG_VERSION"))
+    );
+    assert_eq!(parsed.to_string(), rendered);
+}
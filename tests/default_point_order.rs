@@ -0,0 +1,39 @@
+#[macro_use]
+extern crate throw;
+
+use throw::fmt::PointOrder;
+use throw::Result;
+
+fn throw1() -> Result<(), &'static str> {
+    throw_new!("failed");
+}
+
+fn throw2() -> Result<(), &'static str> {
+    up!(throw1());
+    Ok(())
+}
+
+// Kept in its own test binary, as the default order applies to every test running alongside it.
+#[test]
+fn test_default_point_order() {
+    let error = throw2().unwrap_err();
+    let newest_first = error.to_string();
+    assert_eq!(throw::fmt::default_point_order(), PointOrder::NewestFirst);
+
+    throw::fmt::set_default_point_order(PointOrder::OldestFirst);
+    let oldest_first = error.to_string();
+    throw::fmt::set_default_point_order(PointOrder::NewestFirst);
+
+    let newest_lines = newest_first.lines().collect::<Vec<_>>();
+    let oldest_lines = oldest_first.lines().collect::<Vec<_>>();
+    assert_eq!(
+        oldest_lines,
+        [
+            newest_lines[0],
+            "\tpoints, oldest first:",
+            newest_lines[2],
+            newest_lines[1]
+        ]
+    );
+    assert_eq!(error.to_string(), newest_first);
+}
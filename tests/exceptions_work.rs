@@ -4,25 +4,34 @@ extern crate serde_json;
 #[macro_use]
 extern crate throw;
 
-use throw::Result;
+use std::convert::TryFrom;
+
+use throw::{Result, ThrowIteratorExt};
 
 macro_rules! assert_matches {
     ($expected:expr, $actual:expr) => {{
         let expected = ($expected).replace("    ", "\t");
         let re = regex::Regex::new(&expected).expect("expected hardcoded regex to compile");
 
-        let actual = format!("{}", $actual);
+        let actual = without_point_metadata(&format!("{}", $actual));
 
         assert!(
             re.is_match(&actual),
-            format!(
-                "expected error to match regex `\n{}\n`, but found `\n{}\n`",
-                expected, actual
-            )
+            "expected error to match regex `\n{}\n`, but found `\n{}\n`",
+            expected,
+            actual
         );
     }};
 }
 
+/// Removes the ` [...]` after each point's location holding the crate, timestamp and thread
+/// recorded with the `crate-info`, `timestamps` and `thread-info` features, so that the rendering
+/// tests below pass with them enabled.
+fn without_point_metadata(rendered: &str) -> String {
+    let metadata = regex::Regex::new(r"(?m)\) \[[^\[\]\n]*\]( — |$)").unwrap();
+    metadata.replace_all(rendered, ")$1").into_owned()
+}
+
 fn throw_static_message() -> Result<(), &'static str> {
     throw_new!("hi");
 }
@@ -93,6 +102,132 @@ fn throws_into_multiple_key_value_pairs() -> Result<(), String> {
     Ok(())
 }
 
+fn throws_opt(value: Option<u32>) -> Result<u32, &'static str> {
+    let value = throw_opt!(value, "missing value");
+    Ok(value)
+}
+
+fn throws_opt_key_value(value: Option<u32>) -> Result<u32, &'static str> {
+    let value = throw_opt!(value, "missing value", "user_id" => 42, "source" => "opt");
+    Ok(value)
+}
+
+fn throws_assert(value: u32) -> Result<u32, &'static str> {
+    throw_assert!(value < 10, "value too large", "value" => value);
+    Ok(value)
+}
+
+fn throws_debug_assert(value: u32) -> Result<u32, &'static str> {
+    throw_debug_assert!(value < 10, "value too large");
+    Ok(value)
+}
+
+fn throws_up_with_note() -> Result<(), &'static str> {
+    up!(throw_static_message(), msg: "while saying hi");
+    Ok(())
+}
+
+fn throws_up_with_note_key_value() -> Result<(), &'static str> {
+    up!(throw_static_message(), msg: format!("while saying hi {} times", 2), "times" => 2);
+    Ok(())
+}
+
+fn throws_up_same() -> Result<(), &'static str> {
+    up_same!(throw_with_context1(), "project_secret" => "omega");
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct WrappedError(&'static str);
+
+impl From<&'static str> for WrappedError {
+    fn from(s: &'static str) -> WrappedError {
+        WrappedError(s)
+    }
+}
+
+fn throws_up_into() -> Result<(), WrappedError> {
+    up_into!(WrappedError, throw_static_message(), msg: "while wrapping");
+    Ok(())
+}
+
+fn throws_throw_into() -> Result<(), WrappedError> {
+    throw_into!(WrappedError, Err("some static string"), "key" => "value");
+    Ok(())
+}
+
+const CONST_KEY: &str = "const_key";
+
+fn throws_kv_edge_cases(value: u32) -> Result<(), &'static str> {
+    throw_new!(
+        "edge cases",
+        "matched" => match value {
+            1 => "one",
+            _ => "other",
+        },
+        CONST_KEY => {
+            let doubled = value * 2;
+            doubled.to_string()
+        },
+        "closure" => Some(value).map(|x| x + 1).unwrap_or(0),
+    );
+}
+
+fn throws_trailing_commas() -> Result<(), &'static str> {
+    up!(throw_static_message(),);
+    Ok(())
+}
+
+fn throws_trailing_commas_key_value() -> Result<(), &'static str> {
+    up!(throws_trailing_commas(), "key" => "value",);
+    Ok(())
+}
+
+fn throws_trailing_commas_note() -> Result<(), &'static str> {
+    up_same!(throws_trailing_commas_key_value(), msg: "note",);
+    Ok(())
+}
+
+fn throws_trailing_commas_throw() -> Result<(), &'static str> {
+    throw!(Err("err"),);
+    throw_opt!(None::<()>, "none",);
+    throw_assert!(false, "assert",);
+    throw_new!("new",);
+}
+
+fn throws_repeated_trailing_commas() -> Result<(), &'static str> {
+    up!(throw_static_message(), "key" => "value",,);
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum LookupError {
+    NotFound(u32),
+    PermissionDenied,
+}
+
+fn lookup(id: u32) -> Result<&'static str, LookupError> {
+    match id {
+        0 => Ok("root"),
+        1 => throw_new!(LookupError::PermissionDenied),
+        _ => throw_new!(LookupError::NotFound(id)),
+    }
+}
+
+fn lookup_or_default(id: u32) -> Result<&'static str, LookupError> {
+    let name = rethrow!(lookup(id),
+        LookupError::NotFound(id) if *id < 10 => "default",
+    );
+    Ok(name)
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn test_error_point_size() {
+    // a point is created on every throw, so only the static location is stored inline
+    assert_eq!(std::mem::size_of::<throw::ErrorPoint>(), 48);
+}
+
 #[test]
 fn test_static_message() {
     let error = throw_static_message().unwrap_err();
@@ -114,11 +249,11 @@ fn serialize_json() {
     let expected = r#"\{
         "points":\[
             \{"line":[0-9]+,"column":[0-9]+,"module_path":"exceptions_work",
-                "file":"tests/exceptions_work.rs"\},
+                "file":"tests/exceptions_work.rs"[^{}]*\},
             \{"line":[0-9]+,"column":[0-9]+,"module_path":"exceptions_work",
-                "file":"tests/exceptions_work.rs"\},
+                "file":"tests/exceptions_work.rs"[^{}]*\},
             \{"line":[0-9]+,"column":[0-9]+,"module_path":"exceptions_work",
-                "file":"tests/exceptions_work.rs"\}
+                "file":"tests/exceptions_work.rs"[^{}]*\}
         \],
         "context":\[
             \{"key":"code","value":78\},
@@ -132,6 +267,675 @@ fn serialize_json() {
     assert_matches!(whitespace_trim.replace_all(expected, "\\s*"), json);
 }
 
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn serialize_json_diagnostic() {
+    let mut error = throw::Error::new("bad config");
+    error.set_code("E1042");
+    error.set_severity(throw::Severity::Warning);
+    error.set_help("remove the key");
+    assert_eq!(
+        serde_json::to_string(&error).unwrap(),
+        r#"{"points":[],"context":[],"error":"bad config","code":"E1042","severity":"warning","help":"remove the key"}"#
+    );
+}
+
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn deserialize_json_round_trip() {
+    let mut error = throw_with_context3().unwrap_err();
+    error.set_code("E1042");
+    let json = serde_json::to_string(&error).unwrap();
+    let parsed: throw::Error<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.to_string(), error.to_string());
+    assert_eq!(parsed.points().len(), 3);
+    assert_eq!(parsed.points()[0].file(), "tests/exceptions_work.rs");
+    assert_eq!(parsed.get_context()[0].key(), "code");
+    assert_eq!(parsed.code(), Some("E1042"));
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+}
+
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn deserialize_json_point_metadata() {
+    let json = r#"{"points":[{"line":1,"column":2,"module_path":"app","file":"src/main.rs","crate_name":"app","timestamp_us":1500000,"thread_id":7}],"context":[],"error":"failed"}"#;
+    let parsed: throw::Error<String> = serde_json::from_str(json).unwrap();
+    let point = &parsed.points()[0];
+    assert_eq!(point.crate_name(), Some("app"));
+    assert_eq!(point.crate_version(), None);
+    assert_eq!(
+        point.timestamp(),
+        Some(std::time::Duration::from_millis(1500))
+    );
+    assert_eq!(point.thread_id(), Some(7));
+    assert_eq!(point.thread_name(), None);
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+}
+
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn serialize_json_structured() {
+    #[derive(serde_derive::Serialize)]
+    enum ParseError {
+        UnexpectedChar { line: u32, found: char },
+    }
+
+    let mut error = throw::Error::new(ParseError::UnexpectedChar {
+        line: 3,
+        found: '}',
+    });
+    error.add_context("path", "config.toml");
+    assert_eq!(
+        serde_json::to_string(&error.serialize_structured()).unwrap(),
+        r#"{"points":[],"context":[{"key":"path","value":"config.toml"}],"error":{"UnexpectedChar":{"line":3,"found":"}"}}}"#
+    );
+}
+
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn serialize_json_context_map() {
+    use throw::ser::{ContextFormat, Duplicates, SerializeOptions};
+
+    let mut error = throw::Error::new("failed");
+    error.add_context("attempt", 1);
+    error.add_context("host", "a");
+    error.add_context("attempt", 2);
+    let json = |duplicates| {
+        let options = SerializeOptions::new().context(ContextFormat::Map(duplicates));
+        serde_json::to_string(&error.serialize_with(options)).unwrap()
+    };
+    assert_eq!(
+        json(Duplicates::KeepFirst),
+        r#"{"points":[],"context":{"attempt":1,"host":"a"},"error":"failed"}"#
+    );
+    assert_eq!(
+        json(Duplicates::KeepLast),
+        r#"{"points":[],"context":{"attempt":2,"host":"a"},"error":"failed"}"#
+    );
+    assert_eq!(
+        json(Duplicates::Collect),
+        r#"{"points":[],"context":{"attempt":[1,2],"host":"a"},"error":"failed"}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&error.serialize_with(SerializeOptions::new())).unwrap(),
+        serde_json::to_string(&error).unwrap()
+    );
+}
+
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn serialize_json_max_value_len() {
+    use throw::ser::{ContextFormat, Duplicates, SerializeOptions};
+
+    let mut error = throw::Error::new("failed");
+    error.add_context("body", "0123456789");
+    error.add_context("status", 500);
+    let options = SerializeOptions::new().max_value_len(4);
+    assert_eq!(
+        serde_json::to_string(&error.serialize_with(options)).unwrap(),
+        r#"{"points":[],"context":[{"key":"body","value":"0123… (6 bytes truncated)"},{"key":"status","value":500}],"error":"failed"}"#
+    );
+    let options = options.context(ContextFormat::Map(Duplicates::KeepFirst));
+    assert_eq!(
+        serde_json::to_string(&error.serialize_with(options)).unwrap(),
+        r#"{"points":[],"context":{"body":"0123… (6 bytes truncated)","status":500},"error":"failed"}"#
+    );
+}
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn serialize_json_compact_points() {
+    use throw::ser::{PointFormat, SerializeOptions};
+
+    let error = throw_with_context3().unwrap_err();
+    let options = SerializeOptions::new().points(PointFormat::Compact);
+    let json = serde_json::to_value(error.serialize_with(options)).unwrap();
+    let points = json["points"].as_array().unwrap();
+    assert_eq!(points.len(), 3);
+    for point in points {
+        assert_matches!(
+            r"^tests/exceptions_work\.rs:[0-9]+:[0-9]+ \(exceptions_work\)$",
+            point.as_str().unwrap()
+        );
+    }
+}
+
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn serialize_json_point_order() {
+    use throw::ser::{PointFormat, PointOrder, SerializeOptions};
+
+    let error = throw_with_context3().unwrap_err();
+    let lines = |options| {
+        let json = serde_json::to_value(error.serialize_with(options)).unwrap();
+        let lines: Vec<u64> = json["points"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|point| point["line"].as_u64().unwrap())
+            .collect();
+        (lines, json.get("order").cloned())
+    };
+    let (default_lines, default_order) = lines(SerializeOptions::new());
+    assert_eq!(default_order, None);
+
+    let (oldest_lines, oldest_order) =
+        lines(SerializeOptions::new().point_order(PointOrder::OldestFirst));
+    assert_eq!(oldest_lines, default_lines);
+    assert_eq!(oldest_order.unwrap(), "oldest_first");
+
+    let (mut newest_lines, newest_order) =
+        lines(SerializeOptions::new().point_order(PointOrder::NewestFirst));
+    newest_lines.reverse();
+    assert_eq!(newest_lines, default_lines);
+    assert_eq!(newest_order.unwrap(), "newest_first");
+
+    let options = SerializeOptions::new()
+        .points(PointFormat::Compact)
+        .point_order(PointOrder::NewestFirst);
+    let json = serde_json::to_value(error.serialize_with(options)).unwrap();
+    let first = json["points"][0].as_str().unwrap().to_owned();
+    assert!(first.starts_with(&format!("tests/exceptions_work.rs:{}:", default_lines[2])));
+}
+
+#[test]
+#[cfg(feature = "schemars")]
+fn json_schema() {
+    let schema = serde_json::to_value(schemars::schema_for!(throw::Error<String>)).unwrap();
+    assert_eq!(schema["title"], "Error");
+    assert_eq!(
+        schema["required"],
+        serde_json::json!(["points", "context", "error"])
+    );
+    assert_eq!(schema["properties"]["error"]["type"], "string");
+    assert_eq!(
+        schema["properties"]["points"]["items"]["$ref"],
+        "#/$defs/ErrorPoint"
+    );
+    let point = &schema["$defs"]["ErrorPoint"];
+    assert_eq!(
+        point["required"],
+        serde_json::json!(["line", "column", "module_path", "file"])
+    );
+    assert_eq!(
+        schema["$defs"]["ThrowContextValues"]["type"],
+        serde_json::json!(["boolean", "integer", "number", "string"])
+    );
+
+    let structured =
+        serde_json::to_value(schemars::schema_for!(throw::ser::Structured<'static, u32>)).unwrap();
+    assert_eq!(structured["properties"]["error"]["type"], "integer");
+}
+
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn portable_round_trip() {
+    use throw::ser::Portable;
+
+    fn check(error: &throw::Error<String>) {
+        assert_eq!(error.points().len(), 3);
+        assert_eq!(error.points()[1].note(), None);
+        assert_eq!(error.get_context()[0].key(), "code");
+        assert_eq!(error.code(), Some("E1042"));
+        assert_eq!(error.severity(), Some(throw::Severity::Warning));
+        assert_eq!(error.help(), None);
+    }
+
+    let mut error = throw_with_context3().unwrap_err();
+    error.set_code("E1042");
+    error.set_severity(throw::Severity::Warning);
+    let display = error.to_string();
+    let portable = Portable(error);
+
+    let bytes = postcard::to_allocvec(&portable).unwrap();
+    let Portable(parsed) = postcard::from_bytes::<Portable<String>>(&bytes).unwrap();
+    check(&parsed);
+    assert_eq!(parsed.to_string(), display);
+
+    let bytes = rmp_serde::to_vec(&portable).unwrap();
+    let Portable(parsed) = rmp_serde::from_slice::<Portable<String>>(&bytes).unwrap();
+    check(&parsed);
+    assert_eq!(parsed.to_string(), display);
+
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&portable, &mut bytes).unwrap();
+    let Portable(parsed) = ciborium::from_reader::<Portable<String>, _>(&bytes[..]).unwrap();
+    check(&parsed);
+    assert_eq!(parsed.to_string(), display);
+
+    let json = serde_json::to_string(&portable).unwrap();
+    let Portable(parsed) = serde_json::from_str::<Portable<String>>(&json).unwrap();
+    check(&parsed);
+    assert_eq!(parsed.to_string(), display);
+}
+
+#[test]
+fn test_parse_display_round_trip() {
+    let error = throw_with_context3().unwrap_err();
+    let text = error.to_string();
+    let parsed: throw::Error<String> = text.parse().unwrap();
+    assert_eq!(parsed.to_string(), text);
+    assert_eq!(parsed.error(), "Error with context");
+    assert_eq!(parsed.points().len(), 3);
+    for (parsed, original) in parsed.points().iter().zip(error.points()) {
+        assert_eq!(parsed.line(), original.line());
+        assert_eq!(parsed.column(), original.column());
+        assert_eq!(parsed.module_path(), original.module_path());
+        assert_eq!(parsed.file(), original.file());
+    }
+    let keys: Vec<_> = parsed.get_context().iter().map(|kv| kv.key()).collect();
+    let original_keys: Vec<_> = error.get_context().iter().map(|kv| kv.key()).collect();
+    assert_eq!(keys, original_keys);
+}
+
+#[test]
+fn test_parse_display_metadata() {
+    let text = "Warning[W7]: disk\n\t  almost full\n\tvolume: /data\n\tat 3:9 in app::disk (src/disk (old).rs) \u{2014} checking space\n\tat 10:1 in app (src/main.rs)\n\thelp: free some space";
+    let parsed: throw::Error<String> = text.parse().unwrap();
+    assert_eq!(parsed.error(), "disk\nalmost full");
+    assert_eq!(parsed.severity(), Some(throw::Severity::Warning));
+    assert_eq!(parsed.code(), Some("W7"));
+    assert_eq!(parsed.help(), Some("free some space"));
+    assert_eq!(parsed.points()[1].file(), "src/disk (old).rs");
+    assert_eq!(parsed.points()[1].note(), Some("checking space"));
+    assert_eq!(parsed.points()[0].line(), 10);
+    assert_eq!(parsed.to_string(), text);
+    let unindented = text.replace("\n\t  ", "\n");
+    let parsed: throw::Error<String> = unindented.parse().unwrap();
+    assert_eq!(parsed.error(), "disk\nalmost full");
+
+    let error = "Oops: no".parse::<throw::Error<String>>().unwrap_err();
+    assert_eq!(error.line(), 1);
+    let error = "Error: x\n\tat 1:1 in a (b)\n\tkey: value"
+        .parse::<throw::Error<String>>()
+        .unwrap_err();
+    assert_eq!(error.line(), 3);
+    assert_eq!(
+        error.to_string(),
+        "invalid throw error on line 3: expected `at L:C in module (file)` or `help: `"
+    );
+}
+
+#[test]
+fn test_diagnostic_display() {
+    let mut error = throw::Error::new("bad config");
+    error.set_severity(throw::Severity::Advice);
+    error.set_help("remove the key");
+    assert_eq!(
+        error.to_string(),
+        "Advice: bad config\n\thelp: remove the key"
+    );
+    assert_eq!(error.code(), None);
+}
+
+#[test]
+fn test_alternate_display() {
+    let error = throw_with_context3().unwrap_err();
+    assert_matches!(
+        r#"Error: Error with context \(tests/exceptions_work.rs:[0-9]+:[0-9]+\)"#,
+        format!("{:#}", error)
+    );
+    assert!(!format!("{}", error).starts_with(&format!("{:#}", error)));
+
+    assert_eq!(
+        format!("{:#}", throw::Error::new("failed")),
+        "Error: failed"
+    );
+}
+
+#[test]
+fn test_display_point_order() {
+    use throw::fmt::{ErrorFormatter, PointOrder};
+
+    let error = throw_with_context3().unwrap_err();
+    let formatter = ErrorFormatter::new().point_order(PointOrder::OldestFirst);
+    let text = error.display_with(&formatter).to_string();
+    let lines = text.lines().collect::<Vec<_>>();
+    assert_eq!(lines[6], "\tpoints, oldest first:");
+    assert_eq!(
+        without_point_metadata(lines[7]),
+        format!(
+            "\tat {}:{} in exceptions_work (tests/exceptions_work.rs)",
+            error.points()[0].line(),
+            error.points()[0].column()
+        )
+    );
+
+    let parsed: throw::Error<String> = text.parse().unwrap();
+    assert_eq!(parsed.points()[0].line(), error.points()[0].line());
+    assert_eq!(parsed.to_string(), error.to_string());
+
+    let formatter = ErrorFormatter::new().point_order(PointOrder::NewestFirst);
+    assert_eq!(
+        error.display_with(&formatter).to_string(),
+        error.to_string()
+    );
+}
+
+#[test]
+fn test_display_prefixes() {
+    use throw::fmt::ErrorFormatter;
+
+    fn fails() -> Result<(), &'static str> {
+        throw_new!("failed", "attempt" => 2);
+    }
+
+    let mut error = fails().unwrap_err();
+    error.set_help("try again");
+    let formatter = ErrorFormatter::new()
+        .indent("  ")
+        .point_prefix("from")
+        .label("error")
+        .line_prefix("// ");
+    assert_matches!(
+        r#"^// error: failed
+//   attempt: 2
+//   from [0-9]+:[0-9]+ in exceptions_work \(tests/exceptions_work.rs\)
+//   help: try again$"#,
+        error.display_with(&formatter)
+    );
+}
+
+#[test]
+fn test_display_terse() {
+    use throw::fmt::{ErrorFormatter, Style};
+
+    let error = throw_with_context3().unwrap_err();
+    let formatter = ErrorFormatter::new().terse(true);
+    let text = error.display_with(&formatter).to_string();
+    let full = error.to_string();
+    let full_lines = full.lines().collect::<Vec<_>>();
+    assert_eq!(
+        text.lines().collect::<Vec<_>>(),
+        [
+            &full_lines[..7],
+            &["\t\u{2026} 1 frame omitted \u{2026}", full_lines[8]]
+        ]
+        .concat()
+    );
+
+    let formatter = ErrorFormatter::new().style(Style::Pretty).terse(true);
+    let pretty = error.display_with(&formatter).to_string();
+    assert_eq!(pretty.lines().count(), 9);
+    assert!(pretty.contains("\n\u{251c}\u{2500} \u{2026} 1 frame omitted \u{2026}\n"));
+
+    let error = throw_with_context1().unwrap_err();
+    assert_eq!(
+        error.display_with(&formatter).to_string(),
+        error.to_string_pretty()
+    );
+}
+
+#[test]
+fn test_display_context_visibility() {
+    use throw::fmt::{ContextVisibility, ErrorFormatter};
+
+    let error = throw_with_context1().unwrap_err();
+    let formatter = ErrorFormatter::new().context(ContextVisibility::Omitted);
+    assert_matches!(
+        r#"^Error: Error with context
+    application: <omitted>
+    code: <omitted>
+    at [0-9]+:[0-9]+ in exceptions_work \(tests/exceptions_work.rs\)$"#,
+        error.display_with(&formatter)
+    );
+
+    let formatter = ErrorFormatter::new().context(ContextVisibility::Hidden);
+    assert_matches!(
+        r#"^Error: Error with context
+    at [0-9]+:[0-9]+ in exceptions_work \(tests/exceptions_work.rs\)$"#,
+        error.display_with(&formatter)
+    );
+}
+
+#[test]
+fn test_display_path_remap() {
+    use throw::fmt::{ErrorFormatter, PathRemap, Style};
+
+    let error = throw_with_context1().unwrap_err();
+    let formatter =
+        ErrorFormatter::new().path_remap(PathRemap::new().prefix("tests/", "/checkout/tests/"));
+    assert_matches!(
+        r#"\n    at [0-9]+:[0-9]+ in exceptions_work \(/checkout/tests/exceptions_work.rs\)$"#,
+        error.display_with(&formatter)
+    );
+
+    let formatter = ErrorFormatter::new()
+        .style(Style::Compact)
+        .path_remap(PathRemap::new().strip_prefix("tests/"));
+    assert_matches!(
+        r#"^Error: Error with context \(exceptions_work.rs:[0-9]+:[0-9]+\)$"#,
+        error.display_with(&formatter)
+    );
+}
+
+#[test]
+fn test_display_snapshot() {
+    use throw::fmt::{ErrorFormatter, Style};
+
+    let error = throw_with_context1().unwrap_err();
+    let formatter = ErrorFormatter::new().snapshot(true);
+    assert_eq!(
+        without_point_metadata(&error.display_with(&formatter).to_string()),
+        "Error: Error with context\n\tapplication: rust_core\n\tcode: 78\n\tat _:_ in \
+         exceptions_work (tests/exceptions_work.rs)"
+    );
+
+    let formatter = ErrorFormatter::new().style(Style::Compact).snapshot(true);
+    assert_eq!(
+        without_point_metadata(&error.display_with(&formatter).to_string()),
+        "Error: Error with context (tests/exceptions_work.rs:_:_)"
+    );
+}
+
+#[test]
+fn test_display_group_context() {
+    use throw::fmt::{ErrorFormatter, Style};
+
+    let error = throw_with_context3().unwrap_err();
+    let context = error.get_context();
+    assert_eq!(context[0].point(), Some(0));
+    assert_eq!(context[2].point(), Some(1));
+    assert_eq!(context[4].point(), Some(2));
+
+    let formatter = ErrorFormatter::new().snapshot(true).group_context(true);
+    assert_eq!(
+        without_point_metadata(&error.display_with(&formatter).to_string()),
+        "Error: Error with context\
+         \n\tat _:_ in exceptions_work (tests/exceptions_work.rs)\
+         \n\t  height: 948\n\t  score: 0.75\
+         \n\tat _:_ in exceptions_work (tests/exceptions_work.rs)\
+         \n\t  project_secret: omega\
+         \n\tat _:_ in exceptions_work (tests/exceptions_work.rs)\
+         \n\t  application: rust_core\n\t  code: 78"
+    );
+
+    let formatter = formatter.style(Style::Pretty).terse(true);
+    assert_eq!(
+        without_point_metadata(&error.display_with(&formatter).to_string()),
+        "Error: Error with context\
+         \n\u{251c}\u{2500} project_secret: omega\
+         \n\u{251c}\u{2500} at _:_ in exceptions_work (tests/exceptions_work.rs)\
+         \n\u{2502}  \u{251c}\u{2500} height: 948\n\u{2502}  \u{2570}\u{2500} score: 0.75\
+         \n\u{251c}\u{2500} \u{2026} 1 frame omitted \u{2026}\
+         \n\u{2570}\u{2500} at _:_ in exceptions_work (tests/exceptions_work.rs)\
+         \n   \u{251c}\u{2500} application: rust_core\n   \u{2570}\u{2500} code: 78"
+    );
+}
+
+#[test]
+fn test_display_max_value_len() {
+    use throw::fmt::ErrorFormatter;
+
+    let mut error = throw::Error::new("failed");
+    error.add_context("body", "h\u{e9}llo world");
+    error.add_context("status", 500);
+    let formatter = ErrorFormatter::new().max_value_len(2);
+    assert_eq!(
+        without_point_metadata(&error.display_with(&formatter).to_string()),
+        "Error: failed\n\tstatus: 50\u{2026} (1 byte truncated)\n\tbody: h\u{2026} (11 bytes truncated)"
+    );
+
+    let formatter = ErrorFormatter::new().wrap_values(4);
+    assert_eq!(
+        without_point_metadata(&error.display_with(&formatter).to_string()),
+        "Error: failed\n\tstatus: 500\n\tbody: h\u{e9}ll\n\t  o wo\n\t  rld"
+    );
+}
+
+#[test]
+fn test_display_templates() {
+    use throw::fmt::{ContextVisibility, ErrorFormatter, PointOrder};
+
+    let mut error = throw_with_context3().unwrap_err();
+    error.set_help("try again");
+    let formatter = ErrorFormatter::new()
+        .snapshot(true)
+        .label("Fehler")
+        .point_prefix("bei")
+        .module_prefix("im Modul")
+        .help_label("Hilfe")
+        .omitted_value("<ausgelassen>")
+        .oldest_first_header("Stellen, älteste zuerst:")
+        .frames_omitted(
+            "\u{2026} {} Stelle ausgelassen",
+            "\u{2026} {} Stellen ausgelassen",
+        )
+        .context(ContextVisibility::Omitted)
+        .point_order(PointOrder::OldestFirst)
+        .terse(true);
+    assert_eq!(
+        without_point_metadata(&error.display_with(&formatter).to_string()),
+        "Fehler: Error with context\
+         \n\theight: <ausgelassen>\n\tscore: <ausgelassen>\n\tproject_secret: <ausgelassen>\
+         \n\tapplication: <ausgelassen>\n\tcode: <ausgelassen>\
+         \n\tStellen, älteste zuerst:\
+         \n\tbei _:_ im Modul exceptions_work (tests/exceptions_work.rs)\
+         \n\t\u{2026} 1 Stelle ausgelassen\
+         \n\tbei _:_ im Modul exceptions_work (tests/exceptions_work.rs)\
+         \n\tHilfe: try again"
+    );
+
+    let mut error = throw::Error::new("failed");
+    error.add_context("body", "0123456789");
+    let formatter = ErrorFormatter::new()
+        .max_value_len(4)
+        .bytes_truncated(" [1 Byte gekürzt]", " [{} Bytes gekürzt]");
+    assert_eq!(
+        without_point_metadata(&error.display_with(&formatter).to_string()),
+        "Error: failed\n\tbody: 0123 [6 Bytes gekürzt]"
+    );
+}
+
+#[test]
+fn test_to_string_pretty() {
+    fn fails() -> Result<(), &'static str> {
+        throw_new!("failed", "query" => "SELECT *\nFROM users");
+    }
+
+    let mut error = fails().unwrap_err();
+    error.set_help("check the query");
+    assert_matches!(
+        "Error: failed\n\u{251c}\u{2500} query: SELECT \\*\n\u{2502}  FROM users\n\u{251c}\u{2500} at [0-9]+:[0-9]+ in exceptions_work \\(tests/exceptions_work.rs\\)\n\u{2570}\u{2500} help: check the query",
+        error.to_string_pretty()
+    );
+
+    assert_eq!(
+        throw::Error::new("failed").to_string_pretty(),
+        "Error: failed"
+    );
+}
+
+#[test]
+fn test_display_multi_line() {
+    fn fails() -> Result<(), String> {
+        throw_new!(
+            "failed to load config:\ncaused by: missing key".to_owned(),
+            "query" => "SELECT *\nFROM users"
+        );
+    }
+
+    let error = fails().unwrap_err();
+    assert_matches!(
+        r#"^Error: failed to load config:
+      caused by: missing key
+    query: SELECT \*
+      FROM users
+    at [0-9]+:[0-9]+ in exceptions_work \(tests/exceptions_work.rs\)$"#,
+        error
+    );
+
+    let parsed: throw::Error<String> = error.to_string().parse().unwrap();
+    assert_eq!(parsed.error(), error.error());
+    assert_eq!(
+        parsed.get_context()[0].value().to_string(),
+        "SELECT *\nFROM users"
+    );
+    assert_eq!(parsed.to_string(), error.to_string());
+}
+
+#[test]
+fn test_write_to() {
+    struct Buffer {
+        bytes: [u8; 1024],
+        len: usize,
+    }
+
+    impl std::fmt::Write for Buffer {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            let end = self.len + s.len();
+            if end > self.bytes.len() {
+                return Err(std::fmt::Error);
+            }
+            self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let error = throw_with_context3().unwrap_err();
+    let mut buffer = Buffer {
+        bytes: [0; 1024],
+        len: 0,
+    };
+    error.write_to(&mut buffer).unwrap();
+    assert_eq!(
+        std::str::from_utf8(&buffer.bytes[..buffer.len]).unwrap(),
+        error.to_string()
+    );
+
+    let mut small = Buffer {
+        bytes: [0; 1024],
+        len: 1000,
+    };
+    assert!(error.write_to(&mut small).is_err());
+}
+
+#[test]
+fn test_display_with_formatter() {
+    use throw::fmt::{ErrorFormatter, Style};
+
+    let error = throw_with_context3().unwrap_err();
+    let full = ErrorFormatter::new();
+    assert_eq!(error.display_with(&full).to_string(), error.to_string());
+
+    let compact = ErrorFormatter::new().style(Style::Compact);
+    assert_matches!(
+        r#"Error: Error with context \(tests/exceptions_work.rs:[0-9]+:[0-9]+\)"#,
+        error.display_with(&compact).to_string()
+    );
+
+    let custom = ErrorFormatter::new().style(Style::custom(|error, f| {
+        for kv in error.get_context() {
+            write!(f, "{}={} ", kv.key(), kv.value())?;
+        }
+        write!(f, "{}", error.error())
+    }));
+    assert_eq!(
+        error.display_with(&custom).to_string(),
+        "code=78 application=rust_core project_secret=omega score=0.75 height=948 Error with context"
+    );
+}
+
 #[test]
 fn test_throw_with_context() {
     let error = throw_with_context1().unwrap_err();
@@ -219,3 +1023,478 @@ fn test_throws_into_multiple_key_value_pairs() {
         error
     )
 }
+
+#[test]
+fn test_throw_opt() {
+    assert_eq!(throws_opt(Some(3)).unwrap(), 3);
+
+    let error = throws_opt(None).unwrap_err();
+    assert_matches!(
+        r#"Error: missing value
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)"#,
+        error
+    )
+}
+
+#[test]
+fn test_throw_opt_key_value() {
+    assert_eq!(throws_opt_key_value(Some(3)).unwrap(), 3);
+
+    let error = throws_opt_key_value(None).unwrap_err();
+    assert_matches!(
+        r#"Error: missing value
+    source: opt
+    user_id: 42
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)"#,
+        error
+    )
+}
+
+#[test]
+fn test_throw_assert() {
+    assert_eq!(throws_assert(3).unwrap(), 3);
+
+    let error = throws_assert(12).unwrap_err();
+    assert_matches!(
+        r#"Error: value too large
+    value: 12
+    condition: value < 10
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)"#,
+        error
+    )
+}
+
+#[test]
+fn test_throw_debug_assert() {
+    assert_eq!(throws_debug_assert(3).unwrap(), 3);
+    assert_eq!(throws_debug_assert(12).is_err(), cfg!(debug_assertions));
+}
+
+#[test]
+fn test_up_with_note() {
+    let error = throws_up_with_note().unwrap_err();
+    assert_eq!(error.points()[0].note(), None);
+    assert_eq!(error.points()[1].note(), Some("while saying hi"));
+    assert_matches!(
+        r#"Error: hi
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\) — while saying hi
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        error
+    )
+}
+
+#[test]
+fn test_up_with_note_key_value() {
+    let error = throws_up_with_note_key_value().unwrap_err();
+    assert_matches!(
+        r#"Error: hi
+    times: 2
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\) — while saying hi 2 times
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        error
+    )
+}
+
+#[test]
+fn test_up_same() {
+    let error = throws_up_same().unwrap_err();
+    assert_eq!(error.points().len(), 2);
+    assert_matches!(
+        r#"Error: Error with context
+    project_secret: omega
+    application: rust_core
+    code: 78
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        error
+    )
+}
+
+#[test]
+fn test_up_into() {
+    let error = throws_up_into().unwrap_err();
+    assert_eq!(*error.error(), WrappedError("hi"));
+    assert_eq!(error.points().len(), 2);
+    assert_eq!(error.points()[1].note(), Some("while wrapping"));
+}
+
+#[test]
+fn test_throw_into() {
+    let error = throws_throw_into().unwrap_err();
+    assert_matches!(
+        r#"Error: WrappedError\("some static string"\)
+    key: value
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        format!("{:?}", error)
+    )
+}
+
+#[test]
+fn test_key_value_edge_cases() {
+    let error = throws_kv_edge_cases(1).unwrap_err();
+    assert_matches!(
+        r#"Error: edge cases
+    closure: 2
+    const_key: 2
+    matched: one
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        error
+    )
+}
+
+#[test]
+fn test_trailing_commas() {
+    let error = throws_trailing_commas_note().unwrap_err();
+    assert_eq!(error.points().len(), 4);
+    assert_eq!(error.get_context().len(), 1);
+
+    let error = throws_trailing_commas_throw().unwrap_err();
+    assert_eq!(*error.error(), "err");
+
+    let error = throws_repeated_trailing_commas().unwrap_err();
+    assert_eq!(error.get_context().len(), 1);
+}
+
+#[test]
+fn test_rethrow() {
+    assert_eq!(lookup_or_default(0).unwrap(), "root");
+    assert_eq!(lookup_or_default(5).unwrap(), "default");
+
+    let error = lookup_or_default(1).unwrap_err();
+    assert_eq!(*error.error(), LookupError::PermissionDenied);
+    assert_eq!(error.points().len(), 2);
+
+    let error = lookup_or_default(12).unwrap_err();
+    assert_eq!(*error.error(), LookupError::NotFound(12));
+    assert_eq!(error.points().len(), 2);
+}
+
+#[test]
+fn test_catch() {
+    let result: Result<u32, String> = catch! {
+        let value = throw_opt!(Some(3), "missing");
+        Ok(value)
+    };
+    assert_eq!(result.unwrap(), 3);
+
+    let result: Result<u32, String> = catch! {
+        up!(throws_into(), "key" => "value");
+        Ok(0)
+    };
+    assert_matches!(
+        r#"Error: some static string
+    key: value
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        result.unwrap_err()
+    );
+}
+
+fn throws_after_retries(attempts: u32) -> Result<u32, &'static str> {
+    let mut attempt = 0;
+    let value = retry!(attempts, || -> Result<u32, &'static str> {
+        attempt += 1;
+        if attempt < 3 {
+            throw_new!("failed", "count" => attempt);
+        }
+        Ok(attempt)
+    });
+    Ok(value)
+}
+
+#[test]
+fn test_retry() {
+    assert_eq!(throws_after_retries(5).unwrap(), 3);
+
+    let error = throws_after_retries(2).unwrap_err();
+    assert_eq!(error.points().len(), 4);
+    assert_matches!(
+        r#"Error: failed
+    attempts: 2
+    attempt: 2
+    count: 2
+    attempt: 1
+    count: 1
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\) — attempt 2 of 2 failed
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\) — attempt 1 of 2 failed
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        error
+    );
+
+    // the closure is always called at least once
+    let error = throws_after_retries(0).unwrap_err();
+    assert_eq!(error.points().len(), 2);
+    assert_eq!(error.points()[1].note(), Some("attempt 1 of 1 failed"));
+    assert_eq!(error.get_context().last().unwrap().key(), "attempts");
+    assert_eq!(error.get_context().last().unwrap().value().to_string(), "1");
+}
+
+#[derive(Debug)]
+enum ServiceError {
+    NotImplemented(throw::NotImplemented),
+}
+
+impl From<throw::NotImplemented> for ServiceError {
+    fn from(e: throw::NotImplemented) -> ServiceError {
+        ServiceError::NotImplemented(e)
+    }
+}
+
+fn throws_unimplemented() -> Result<(), throw::NotImplemented> {
+    throw_unimplemented!();
+}
+
+fn throws_todo(feature: &str) -> Result<(), ServiceError> {
+    throw_todo!(format!("{} support", feature), "feature" => feature.to_owned());
+}
+
+#[test]
+fn test_throw_unimplemented() {
+    let error = throws_unimplemented().unwrap_err();
+    assert!(!error.error().is_todo());
+    assert_eq!(error.error().message(), None);
+    assert_matches!(
+        r#"Error: not implemented
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        error
+    );
+}
+
+#[test]
+fn test_throw_todo() {
+    let error = throws_todo("webp").unwrap_err();
+    let ServiceError::NotImplemented(ref inner) = *error.error();
+    assert!(inner.is_todo());
+    assert_eq!(inner.message(), Some("webp support"));
+    assert_eq!(error.get_context()[0].key(), "feature");
+}
+
+fn check_small(value: u32) -> Result<u32, &'static str> {
+    throw_assert!(value < 10, "value too large");
+    Ok(value)
+}
+
+#[test]
+fn test_try_for_each_throw() {
+    assert!(vec![1, 2, 3]
+        .into_iter()
+        .try_for_each_throw(|v| check_small(v).map(|_| ()))
+        .is_ok());
+
+    let error = vec![1, 20, 30]
+        .into_iter()
+        .try_for_each_throw(|v| check_small(v).map(|_| ()))
+        .unwrap_err();
+    assert_matches!(
+        r#"Error: value too large
+    index: 1
+    condition: value < 10
+    at [0-9]+:[0-9]+ in exceptions_work \([a-z/._-]+\)$"#,
+        error
+    );
+}
+
+#[test]
+fn test_try_for_each_throw_display() {
+    let error = vec![1, 2, 30]
+        .into_iter()
+        .try_for_each_throw_display(|v| check_small(*v).map(|_| ()))
+        .unwrap_err();
+    let context: Vec<_> = error
+        .get_context()
+        .iter()
+        .map(|kv| format!("{}={}", kv.key(), kv.value()))
+        .collect();
+    assert_eq!(context, ["condition=value < 10", "index=2", "item=30"]);
+}
+
+#[test]
+fn test_map_throw() {
+    let results: Vec<_> = vec![1, 20, 3, 40]
+        .into_iter()
+        .map_throw(check_small)
+        .collect();
+    assert_eq!(*results[0].as_ref().unwrap(), 1);
+    assert_eq!(*results[2].as_ref().unwrap(), 3);
+    assert_eq!(
+        results[1].as_ref().unwrap_err().get_context()[1]
+            .value()
+            .to_string(),
+        "1"
+    );
+    assert_eq!(
+        results[3].as_ref().unwrap_err().get_context()[1]
+            .value()
+            .to_string(),
+        "3"
+    );
+}
+
+#[test]
+fn test_collect_throw() {
+    let values: Vec<u32> = vec![1, 2, 3]
+        .into_iter()
+        .map(check_small)
+        .collect_throw()
+        .unwrap();
+    assert_eq!(values, [1, 2, 3]);
+
+    let errors = vec![1, 20, 3, 40]
+        .into_iter()
+        .map(check_small)
+        .collect_throw::<Vec<_>, _, _>()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    let indices: Vec<_> = errors
+        .errors()
+        .iter()
+        .map(|error| error.get_context()[1].value().to_string())
+        .collect();
+    assert_eq!(indices, ["1", "3"]);
+}
+
+#[test]
+fn test_multi_error() {
+    let mut errors: throw::MultiError<_> = vec![20, 2, 30]
+        .into_iter()
+        .filter_map(|value| check_small(value).err())
+        .collect();
+    assert_eq!(errors.len(), 2);
+    assert_matches!(
+        r#"^2 errors:
+1\. Error: value too large
+   \tcondition: value < 10
+   \tat [0-9]+:[0-9] in exceptions_work \([a-z/._]+\)
+2\. Error: value too large
+   \tcondition: value < 10
+   \tat [0-9]+:[0-9] in exceptions_work \([a-z/._]+\)$"#,
+        errors
+    );
+
+    errors.push(throw::Error::new("other"));
+    let inner: Vec<_> = errors.iter().map(|error| *error.error()).collect();
+    assert_eq!(inner, ["value too large", "value too large", "other"]);
+    assert_eq!((&errors).into_iter().count(), 3);
+    assert_eq!(errors.into_iter().count(), 3);
+
+    let single = throw::MultiError::from(throw::Error::new("only"));
+    assert_eq!(single.to_string(), "1 error:\n1. Error: only");
+    let error = throw::Error::try_from(single).unwrap();
+    assert_eq!(*error.error(), "only");
+    let empty = throw::MultiError::<&str>::default();
+    assert!(throw::Error::try_from(empty).unwrap_err().is_empty());
+}
+
+fn import(
+    rows: &[&str],
+    warnings: &mut throw::Warnings<&'static str>,
+) -> Result<usize, &'static str> {
+    for (index, row) in rows.iter().enumerate() {
+        if row.is_empty() {
+            throw_warn!(warnings, "skipped empty row", "row" => index as u64);
+        }
+    }
+    throw_assert!(rows.len() < 4, "too many rows");
+    Ok(rows.len())
+}
+
+#[test]
+fn test_warnings() {
+    let mut warnings = throw::Warnings::new();
+    let result = import(&["a", "", "c"], &mut warnings);
+    let (count, warnings) = warnings.finish(result).unwrap();
+    assert_eq!(count, 3);
+    assert_eq!(warnings.len(), 1);
+    let warning = &warnings.warnings()[0];
+    assert_eq!(*warning.error(), "skipped empty row");
+    assert_eq!(warning.severity(), Some(throw::Severity::Warning));
+    assert_eq!(warning.points().len(), 1);
+    assert_eq!(warning.get_context()[0].value().to_string(), "1");
+    assert_eq!(warnings.into_multi_error().len(), 1);
+
+    let mut warnings = throw::Warnings::new();
+    let result = import(&["", "b", "", "d"], &mut warnings);
+    assert_eq!(warnings.iter().count(), 2);
+    let error = warnings.finish(result).unwrap_err();
+    assert_eq!(*error.error(), "too many rows");
+    let context: Vec<_> = error
+        .get_context()
+        .iter()
+        .filter(|pair| pair.key() == "warning")
+        .map(|pair| pair.value().to_string())
+        .collect();
+    assert_eq!(context.len(), 2);
+    assert_matches!(
+        r"^Warning: skipped empty row \(tests/exceptions_work\.rs:[0-9]+:[0-9]+\)$",
+        context[0]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "serde-1", feature = "serde-1-std"))]
+fn multi_error_json_round_trip() {
+    let errors = throw::MultiError::new(vec![
+        check_small(20).unwrap_err(),
+        throw_static_message().unwrap_err(),
+    ]);
+    let json = serde_json::to_string(&errors).unwrap();
+    assert!(json.starts_with(r#"[{"points":[{"#));
+    let parsed: throw::MultiError<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed.errors()[1].error(), "hi");
+    assert_eq!(parsed.to_string(), errors.to_string());
+}
+
+#[test]
+fn test_assert_throws() {
+    let error = assert_throws!(throw3());
+    assert_eq!(error.points().len(), 3);
+
+    assert_throws!(
+        throw_with_context3(),
+        inner == "Error with context",
+        context_has("project_secret"),
+        context("height") == "948",
+        points == 3,
+        points_contain("tests/exceptions_work.rs"),
+    );
+    assert_throws!(
+        lookup(12),
+        inner matches LookupError::NotFound(12)
+    );
+}
+
+#[test]
+#[should_panic(expected = "expected error to have context key `missing`")]
+fn test_assert_throws_failure() {
+    assert_throws!(throw_with_context3(), context_has("missing"));
+}
+
+#[test]
+#[should_panic(expected = "expected `throws_ok()` to throw an error")]
+fn test_assert_throws_ok() {
+    assert_throws!(throws_ok());
+}
+
+fn guards_lookup(id: u32) -> Result<u32, &'static str> {
+    let error = lookup(id).unwrap_err();
+    guard!(let LookupError::NotFound(missing) = *error.error(), "not a missing id");
+    guard!(
+        let 0..=9 = missing,
+        "missing id out of range",
+        "id" => missing,
+    );
+    Ok(missing)
+}
+
+#[test]
+fn test_guard() {
+    assert_eq!(guards_lookup(5).unwrap(), 5);
+    assert_throws!(guards_lookup(1), inner == "not a missing id", points == 1);
+    assert_throws!(
+        guards_lookup(12),
+        inner == "missing id out of range",
+        context("id") == "12",
+    );
+}
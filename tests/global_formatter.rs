@@ -0,0 +1,44 @@
+#![cfg(feature = "std")]
+
+#[macro_use]
+extern crate throw;
+
+use throw::fmt::{ErrorFormatter, Style};
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed", "attempt" => 2);
+}
+
+// Kept in its own test binary, as the formatter applies to every test running alongside it.
+#[test]
+fn test_global_style() {
+    let error = fails().unwrap_err();
+    let full = error.to_string();
+    let compact = format!("{:#}", error);
+
+    assert!(throw::fmt::set_global_formatter(
+        ErrorFormatter::new()
+            .style(Style::Compact)
+            .backtrace_header("points:")
+    )
+    .is_ok());
+    assert_eq!(error.to_string(), compact);
+    assert_eq!(format!("{:#}", error), compact);
+    assert!(format!("{:?}", error).starts_with("Error: \"failed\"\n"));
+    assert_ne!(error.to_string(), full);
+
+    let mut written = String::new();
+    error.write_to(&mut written).unwrap();
+    assert_eq!(written, compact);
+    let disabled = std::backtrace::Backtrace::disabled();
+    assert!(error
+        .display_with_backtrace(&disabled)
+        .to_string()
+        .starts_with("Error: failed\n\tattempt: 2\n\tpoints:\n\tat 10:5 in global_formatter (tests/global_formatter.rs)"));
+
+    assert!(matches!(
+        throw::fmt::set_global_style(Style::Pretty),
+        Err(Style::Pretty)
+    ));
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "std")]
+
+#[macro_use]
+extern crate throw;
+
+use throw::fmt::ErrorFormatter;
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed", "attempt" => 2);
+}
+
+// Kept in its own test binary, as the formatter applies to every test running alongside it.
+#[test]
+fn test_global_line_prefix() {
+    let error = fails().unwrap_err();
+    assert!(throw::fmt::set_global_formatter(ErrorFormatter::new().line_prefix("// ")).is_ok());
+
+    for rendered in [
+        error.to_string(),
+        format!("{:#}", error),
+        error
+            .display_with_backtrace(&std::backtrace::Backtrace::disabled())
+            .to_string(),
+    ] {
+        assert!(rendered.starts_with("// Error: failed"), "{}", rendered);
+        assert!(
+            rendered.lines().all(|line| line.starts_with("// ")),
+            "{}",
+            rendered
+        );
+    }
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "std")]
+
+#[macro_use]
+extern crate throw;
+
+use throw::fmt::PathRemap;
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed");
+}
+
+// Kept in its own test binary, as the remapping applies to every test running alongside it.
+#[test]
+fn test_global_path_remap() {
+    let error = fails().unwrap_err();
+    assert!(error.to_string().contains(" (tests/global_path_remap.rs)"));
+
+    throw::fmt::set_global_path_remap(PathRemap::new().strip_prefix("tests/"));
+    assert!(error.to_string().contains(" (global_path_remap.rs)"));
+    assert_eq!(error.points()[0].file(), "tests/global_path_remap.rs");
+    #[cfg(feature = "serde-1-std")]
+    assert_eq!(
+        serde_json::to_value(&error).unwrap()["points"][0]["file"],
+        "global_path_remap.rs"
+    );
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "async-graphql")]
+
+#[macro_use]
+extern crate throw;
+extern crate async_graphql;
+
+use async_graphql::{ErrorExtensions, ResultExt, Value};
+use throw::Result;
+
+fn find_user(id: u32) -> Result<String, &'static str> {
+    throw_new!("user not found", "user_id" => id, "role" => "admin");
+}
+
+fn user(id: u32) -> async_graphql::Result<String> {
+    let name = find_user(id).extend()?;
+    Ok(name.to_uppercase())
+}
+
+#[test]
+fn test_error_extensions() {
+    let error = user(42).unwrap_err();
+    assert_eq!(error.message, "user not found");
+    let extensions = error.extensions.unwrap();
+    assert_eq!(extensions.get("user_id"), Some(&Value::from(42)));
+    assert_eq!(extensions.get("role"), Some(&Value::from("admin")));
+    assert_eq!(extensions.get("code"), None);
+
+    let mut error = find_user(1).unwrap_err();
+    error.set_code("E_NOT_FOUND");
+    let extensions = error.extend().extensions.unwrap();
+    assert_eq!(extensions.get("code"), Some(&Value::from("E_NOT_FOUND")));
+}
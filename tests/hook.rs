@@ -0,0 +1,151 @@
+#![cfg(feature = "std")]
+
+#[macro_use]
+extern crate throw;
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use throw::Result;
+
+#[derive(Debug, PartialEq)]
+struct Recorded {
+    line: u32,
+    error: Option<String>,
+    context: Vec<String>,
+}
+
+static RECORDED: Mutex<Vec<Recorded>> = Mutex::new(Vec::new());
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+static NOTES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed", "attempt" => 3);
+}
+
+fn propagates() -> Result<(), &'static str> {
+    up!(fails(), "user" => "alice");
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Opaque;
+
+fn generic<E>(error: E) -> Result<(), E> {
+    throw_new!(error);
+}
+
+fn retries() -> Result<(), &'static str> {
+    retry!(2, fails);
+    Ok(())
+}
+
+fn thrown_in_hook() -> Result<(), &'static str> {
+    throw_new!("thrown in hook");
+}
+
+// Kept in its own test binary, as the hook applies to every test running alongside it.
+#[test]
+fn test_hook() {
+    throw::set_hook(Box::new(|event| {
+        assert!(thrown_in_hook().is_err());
+        RECORDED.lock().unwrap().push(Recorded {
+            line: event.point().line(),
+            error: event.error().map(str::to_owned),
+            context: event
+                .context()
+                .iter()
+                .map(|kv| format!("{}: {}", kv.key(), kv.value()))
+                .collect(),
+        });
+    }));
+    let error = propagates().unwrap_err();
+    assert!(generic(Opaque).is_err());
+    assert!(throw::take_hook().is_some());
+    assert!(fails().is_err());
+
+    let recorded = RECORDED.lock().unwrap();
+    assert_eq!(
+        *recorded,
+        vec![
+            Recorded {
+                line: error.points()[0].line(),
+                error: Some("failed".to_owned()),
+                context: vec!["attempt: 3".to_owned()],
+            },
+            Recorded {
+                line: error.points()[1].line(),
+                error: Some("failed".to_owned()),
+                context: vec!["attempt: 3".to_owned(), "user: alice".to_owned()],
+            },
+            Recorded {
+                line: generic(Opaque).unwrap_err().points()[0].line(),
+                error: None,
+                context: vec![],
+            },
+        ]
+    );
+
+    // the hook can replace or remove itself
+    throw::set_hook(Box::new(|_| {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        throw::set_hook(Box::new(|_| {
+            CALLS.fetch_add(10, Ordering::SeqCst);
+            assert!(throw::take_hook().is_some());
+        }));
+    }));
+    assert!(fails().is_err());
+    assert!(fails().is_err());
+    assert!(fails().is_err());
+    assert_eq!(CALLS.load(Ordering::SeqCst), 11);
+
+    // a panicking hook doesn't stop later hooks from running on this thread
+    throw::set_hook(Box::new(|_| panic!("hook panicked")));
+    assert!(panic::catch_unwind(fails).is_err());
+    throw::set_hook(Box::new(|_| {
+        CALLS.fetch_add(100, Ordering::SeqCst);
+    }));
+    assert!(fails().is_err());
+    assert!(throw::take_hook().is_some());
+    assert_eq!(CALLS.load(Ordering::SeqCst), 111);
+
+    // retry!() runs the hook for the point it records after each attempt
+    throw::set_hook(Box::new(|event| {
+        if let Some(note) = event.point().note() {
+            let attempt = event.context().last().unwrap();
+            NOTES.lock().unwrap().push(format!(
+                "{}: {} ({} {})",
+                event.error().unwrap(),
+                note,
+                attempt.key(),
+                attempt.value()
+            ));
+        }
+    }));
+    assert!(retries().is_err());
+    assert!(throw::take_hook().is_some());
+    assert_eq!(
+        *NOTES.lock().unwrap(),
+        [
+            "failed: attempt 1 of 2 failed (attempt 1)",
+            "failed: attempt 2 of 2 failed (attempts 2)",
+        ]
+    );
+
+    // and so does throw_warn!()
+    throw::set_hook(Box::new(|event| {
+        NOTES
+            .lock()
+            .unwrap()
+            .push(format!("{}: warning", event.error().unwrap()));
+    }));
+    let mut warnings = throw::Warnings::<String>::new();
+    throw_warn!(warnings, "deprecated key", "key" => "colour");
+    assert!(throw::take_hook().is_some());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        NOTES.lock().unwrap().last().unwrap(),
+        "deprecated key: warning"
+    );
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "lambda")]
+
+#[macro_use]
+extern crate throw;
+
+use lambda_runtime::Diagnostic;
+use throw::Result;
+
+fn lookup(id: u32) -> Result<(), &'static str> {
+    throw_new!("user not found", "user_id" => id);
+}
+
+fn handle(id: u32) -> Result<(), &'static str> {
+    up!(lookup(id), "request" => "get");
+    Ok(())
+}
+
+#[test]
+fn test_diagnostic_from_error() {
+    let error = handle(3).unwrap_err();
+    let message = error.to_string();
+    let diagnostic = Diagnostic::from(error);
+    assert_eq!(diagnostic.error_type, "&str");
+    assert_eq!(diagnostic.error_message, message);
+
+    let mut error = handle(3).unwrap_err();
+    error.set_code("E404");
+    assert_eq!(Diagnostic::from(error).error_type, "E404");
+}
+
+#[test]
+fn test_lambda_payload() {
+    let error = handle(3).unwrap_err();
+    let payload = error.to_lambda_payload();
+    assert_eq!(payload["errorType"], "&str");
+    assert_eq!(payload["errorMessage"], "user not found");
+    let stack_trace: Vec<_> = error
+        .points()
+        .iter()
+        .rev()
+        .map(|point| {
+            format!(
+                "at {}:{} in lambda_tests (tests/lambda_tests.rs)",
+                point.line(),
+                point.column()
+            )
+        })
+        .collect();
+    assert_eq!(payload["stackTrace"], serde_json::json!(stack_trace));
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "log")]
+
+#[macro_use]
+extern crate throw;
+extern crate log;
+
+use std::sync::Mutex;
+
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use throw::Result;
+
+#[derive(Debug, PartialEq)]
+struct Logged {
+    level: Level,
+    target: String,
+    message: String,
+    line: Option<u32>,
+    key_values: Vec<(String, String)>,
+}
+
+struct KeyValues(Vec<(String, String)>);
+
+impl<'kvs> VisitSource<'kvs> for KeyValues {
+    fn visit_pair(
+        &mut self,
+        key: Key<'kvs>,
+        value: Value<'kvs>,
+    ) -> std::result::Result<(), kv::Error> {
+        self.0.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+struct Recorder(Mutex<Vec<Logged>>);
+
+impl Log for Recorder {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let mut key_values = KeyValues(Vec::new());
+        record.key_values().visit(&mut key_values).unwrap();
+        self.0.lock().unwrap().push(Logged {
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+            line: record.line(),
+            key_values: key_values.0,
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+fn parse(input: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = throw_logged!(Level::Warn, input.parse::<u32>(), "input" => input.to_owned());
+    Ok(value)
+}
+
+fn fails() -> Result<(), &'static str> {
+    throw_new_logged!(Level::Error, "failed");
+}
+
+// Kept in its own test binary, as the logger is set for the whole program.
+#[test]
+fn test_log() {
+    log::set_logger(&RECORDER).unwrap();
+    log::set_max_level(LevelFilter::Warn);
+
+    let error = parse("x").unwrap_err();
+    assert!(fails().is_err());
+    error.log(Level::Info);
+    throw::Error::new("no points").log(Level::Error);
+
+    let records = RECORDER.0.lock().unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].level, Level::Warn);
+    assert_eq!(records[0].target, "log_tests");
+    assert_eq!(records[0].message, "invalid digit found in string");
+    assert_eq!(records[0].line, Some(error.points()[0].line()));
+    assert_eq!(
+        records[0].key_values[0],
+        ("input".to_owned(), "x".to_owned())
+    );
+    assert_eq!(records[0].key_values[1].0, "error.stack_trace");
+    assert!(records[0].key_values[1]
+        .1
+        .starts_with(&format!("at {}:", error.points()[0].line())));
+    assert_eq!(records[1].level, Level::Error);
+    assert_eq!(records[1].message, "failed");
+    assert_eq!(
+        records[2],
+        Logged {
+            level: Level::Error,
+            target: "throw".to_owned(),
+            message: "no points".to_owned(),
+            line: None,
+            key_values: Vec::new(),
+        }
+    );
+}
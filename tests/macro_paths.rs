@@ -0,0 +1,32 @@
+use throw::{throw, throw_new, throw_opt, up, up_into};
+
+fn throws_new() -> throw::Result<(), &'static str> {
+    throw_new!("hi", "key" => "value");
+}
+
+fn throws_up() -> throw::Result<(), String> {
+    up_into!(String, throws_new());
+    Ok(())
+}
+
+fn throws_throw() -> throw::Result<(), String> {
+    throw!(Err("some static string"));
+    Ok(())
+}
+
+fn throws_up_into_throw_opt() -> throw::Result<(), String> {
+    up!(throws_throw());
+    throw_opt!(None::<()>, "unreachable");
+    Ok(())
+}
+
+#[test]
+fn test_macros_usable_by_path() {
+    let error = throws_up().unwrap_err();
+    assert_eq!(error.error(), "hi");
+    assert_eq!(error.points().len(), 2);
+
+    let error = throws_up_into_throw_opt().unwrap_err();
+    assert_eq!(error.error(), "some static string");
+    assert_eq!(error.points().len(), 2);
+}
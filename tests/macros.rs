@@ -0,0 +1,308 @@
+#![cfg(feature = "macros")]
+#[macro_use]
+extern crate throw;
+
+use throw::throws;
+
+#[throws(&'static str)]
+fn half(value: u32) -> u32 {
+    if value % 2 == 1 {
+        throw_new!("odd value", "value" => value);
+    }
+    value / 2
+}
+
+#[throws(&'static str)]
+fn early_return(value: u32) -> u32 {
+    if value == 0 {
+        return 100;
+    }
+    let doubled = |x: u32| -> u32 {
+        if x > 100 {
+            return 0;
+        }
+        x * 2
+    };
+    up!(half(doubled(value))) + 1
+}
+
+#[throws(&'static str)]
+fn no_return_type(value: u32) {
+    if value == 0 {
+        return;
+    }
+    up!(half(value));
+}
+
+struct Halver;
+
+impl Halver {
+    #[throws(String)]
+    fn half(&self, value: u32) -> u32 {
+        up!(half(value))
+    }
+}
+
+#[test]
+fn test_throws() {
+    assert_eq!(half(4).unwrap(), 2);
+    assert_throws!(
+        half(3),
+        inner == "odd value",
+        context("value") == "3",
+        points == 1
+    );
+}
+
+#[test]
+fn test_throws_returns() {
+    assert_eq!(early_return(0).unwrap(), 100);
+    assert_eq!(early_return(3).unwrap(), 4);
+    assert_eq!(no_return_type(0).unwrap(), ());
+    assert_throws!(no_return_type(3), points == 2);
+}
+
+#[test]
+fn test_throws_method() {
+    assert_eq!(Halver.half(2).unwrap(), 1);
+    assert_throws!(Halver.half(1), inner == "odd value");
+}
+
+fn parse(input: &str) -> Result<u32, std::num::ParseIntError> {
+    input.parse()
+}
+
+#[throw::trace]
+fn parse_half(input: &str) -> throw::Result<u32, String> {
+    let value = parse(input).map_err(|e| e.to_string())?;
+    let halved = half(value)?;
+    Ok(halved)
+}
+
+#[throw::trace]
+fn parse_half_twice(input: &str) -> throw::Result<u32, String> {
+    let first = parse_half(input)?;
+    let second =
+        (|| -> Result<u32, String> { Ok(parse(input).map_err(|e| e.to_string())? / 2) })()?;
+    Ok(first + second)
+}
+
+#[test]
+fn test_trace() {
+    assert_eq!(parse_half_twice("4").unwrap(), 4);
+    assert_throws!(
+        parse_half_twice("x"),
+        inner == "invalid digit found in string",
+        points == 2,
+        points_contain("tests/macros.rs"),
+    );
+    assert_throws!(parse_half_twice("3"), inner == "odd value", points == 3);
+}
+
+#[derive(throw::ThrowContext)]
+struct RequestMeta {
+    user_id: u64,
+    path: String,
+    #[throw(rename = "method")]
+    http_method: &'static str,
+    #[throw(skip)]
+    #[allow(dead_code)]
+    body: Vec<u8>,
+}
+
+fn handle(meta: &RequestMeta) -> throw::Result<(), &'static str> {
+    let mut error = throw::Error::new("not found");
+    error.add_context_from(meta);
+    Err(error)
+}
+
+#[test]
+fn test_derive_throw_context() {
+    let meta = RequestMeta {
+        user_id: 7,
+        path: "/index".to_owned(),
+        http_method: "GET",
+        body: vec![1, 2, 3],
+    };
+    assert_throws!(
+        handle(&meta),
+        inner == "not found",
+        context("user_id") == "7",
+        context("path") == "/index",
+        context("method") == "GET",
+    );
+    let error = handle(&meta).unwrap_err();
+    let keys: Vec<_> = error.get_context().iter().map(|kv| kv.key()).collect();
+    assert_eq!(keys, ["user_id", "path", "method"]);
+}
+
+#[derive(Debug, throw::UpFrom)]
+enum AppError {
+    Parse(std::num::ParseIntError),
+    Message {
+        message: &'static str,
+    },
+    #[throw(skip)]
+    #[allow(dead_code)]
+    Other(String),
+    #[allow(dead_code)]
+    Unknown,
+}
+
+#[throw::trace]
+fn parse_app(input: &str) -> throw::Result<u32, AppError> {
+    let value = parse(input)?;
+    let halved = half(value)?;
+    Ok(halved)
+}
+
+#[test]
+fn test_derive_up_from() {
+    assert_eq!(parse_app("8").unwrap(), 4);
+    match parse_app("x").unwrap_err().into_origin() {
+        AppError::Parse(e) => assert_eq!(e.to_string(), "invalid digit found in string"),
+        other => panic!("expected Parse, found {:?}", other),
+    }
+    let error = parse_app("5").unwrap_err();
+    assert_eq!(error.points().len(), 2);
+    assert_eq!(error.get_context()[0].key(), "value");
+    match error.into_origin() {
+        AppError::Message { message } => assert_eq!(message, "odd value"),
+        other => panic!("expected Message, found {:?}", other),
+    }
+}
+
+#[derive(Debug)]
+struct Query {
+    limit: u32,
+}
+
+#[throw::context_args(user_id, path, ?query)]
+fn lookup(user_id: u64, path: &str, query: Query, flag: bool) -> throw::Result<u32, &'static str> {
+    if !flag {
+        return Ok(query.limit);
+    }
+    let value = up!(half(query.limit));
+    if path.is_empty() || user_id == 0 {
+        throw_new!("bad request");
+    }
+    Ok(value)
+}
+
+#[test]
+fn test_context_args() {
+    assert_eq!(lookup(1, "/", Query { limit: 3 }, false).unwrap(), 3);
+    assert_eq!(lookup(1, "/", Query { limit: 4 }, true).unwrap(), 2);
+    assert_throws!(
+        lookup(2, "/a", Query { limit: 5 }, true),
+        inner == "odd value",
+        context("value") == "5",
+        context("user_id") == "2",
+        context("path") == "/a",
+        context("query") == "Query { limit: 5 }",
+        points == 2,
+    );
+    let error = lookup(0, "/", Query { limit: 2 }, true).unwrap_err();
+    let keys: Vec<_> = error.get_context().iter().map(|kv| kv.key()).collect();
+    assert_eq!(keys, ["user_id", "path", "query"]);
+}
+
+#[derive(Debug, throw::ThrowDiagnostic)]
+#[diagnostic(code = "E1000", help = "check the configuration file")]
+enum ConfigError {
+    Missing,
+    #[diagnostic(code = "E1042", severity = "warn")]
+    Deprecated(&'static str),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ConfigError::Missing => write!(fmt, "missing configuration"),
+            ConfigError::Deprecated(key) => write!(fmt, "deprecated key {}", key),
+        }
+    }
+}
+
+#[test]
+fn test_derive_throw_diagnostic() {
+    use throw::{Severity, ThrowDiagnostic};
+
+    assert_eq!(ConfigError::Missing.code(), Some("E1000"));
+    assert_eq!(ConfigError::Missing.severity(), None);
+    assert_eq!(ConfigError::Deprecated("x").code(), Some("E1042"));
+    assert_eq!(
+        ConfigError::Deprecated("x").severity(),
+        Some(Severity::Warning)
+    );
+    // left out of the variant's attribute, so taken from the type's
+    assert_eq!(
+        ConfigError::Deprecated("x").help(),
+        Some("check the configuration file")
+    );
+
+    let error = throw::Error::new(ConfigError::Missing).diagnose();
+    assert_eq!(
+        error.to_string(),
+        "Error[E1000]: missing configuration\n\thelp: check the configuration file"
+    );
+
+    let mut error = throw::Error::new(ConfigError::Deprecated("port"));
+    error.set_code("E2000");
+    let error = error.diagnose();
+    assert_eq!(error.code(), Some("E2000"));
+    assert_eq!(
+        error.to_string(),
+        "Warning[E2000]: deprecated key port\n\thelp: check the configuration file"
+    );
+}
+
+#[derive(Debug, throw::ThrowDiagnostic)]
+#[diagnostic(severity = "advice", help = "see the manual")]
+enum LintError {
+    #[diagnostic(code = "L1")]
+    Unused,
+    #[diagnostic(help = "rename it")]
+    Shadowed,
+}
+
+#[test]
+fn test_derive_throw_diagnostic_fallback() {
+    use throw::{Severity, ThrowDiagnostic};
+
+    assert_eq!(LintError::Unused.code(), Some("L1"));
+    assert_eq!(LintError::Unused.severity(), Some(Severity::Advice));
+    assert_eq!(LintError::Unused.help(), Some("see the manual"));
+    assert_eq!(LintError::Shadowed.code(), None);
+    assert_eq!(LintError::Shadowed.severity(), Some(Severity::Advice));
+    assert_eq!(LintError::Shadowed.help(), Some("rename it"));
+}
+
+#[allow(dead_code)]
+#[derive(throw::http::HttpStatus)]
+#[http(status = 400)]
+enum ApiError {
+    Invalid,
+    #[http(status = 404)]
+    NotFound {
+        id: u32,
+    },
+    #[http(status = 503)]
+    Unavailable(&'static str),
+}
+
+#[derive(throw::http::HttpStatus)]
+struct Unknown;
+
+#[test]
+fn test_derive_http_status() {
+    use throw::http::HttpStatus;
+
+    assert_eq!(ApiError::Invalid.status(), 400);
+    assert_eq!(ApiError::NotFound { id: 3 }.status(), 404);
+    assert_eq!(ApiError::Unavailable("db").status(), 503);
+    assert_eq!(Unknown.status(), 500);
+
+    let error = throw::Error::new(ApiError::NotFound { id: 3 });
+    assert_eq!(error.status(), 404);
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "metrics")]
+
+#[macro_use]
+extern crate throw;
+extern crate metrics;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use metrics::{
+    Counter, Gauge, Histogram, Key, KeyName, Label, Metadata, Recorder, SharedString, Unit,
+};
+use throw::Result;
+
+#[derive(Default)]
+struct Counters(Mutex<HashMap<Key, Arc<AtomicU64>>>);
+
+impl Counters {
+    fn get(&self, module: &'static str, code: &'static str) -> u64 {
+        let key = Key::from_parts(
+            "throw.errors_total",
+            vec![Label::new("module", module), Label::new("code", code)],
+        );
+        let counters = self.0.lock().unwrap();
+        counters.get(&key).map_or(0, |c| c.load(Ordering::SeqCst))
+    }
+}
+
+impl Recorder for Counters {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut counters = self.0.lock().unwrap();
+        Counter::from_arc(counters.entry(key.clone()).or_default().clone())
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+mod inner {
+    use throw::Result;
+
+    pub fn fails() -> Result<(), &'static str> {
+        throw_new!("failed");
+    }
+}
+
+fn propagate(result: Result<(), &'static str>) -> Result<(), &'static str> {
+    up!(result);
+    Ok(())
+}
+
+fn retry_fails() -> Result<(), &'static str> {
+    retry!(2, inner::fails);
+    Ok(())
+}
+
+#[test]
+fn test_errors_total() {
+    let counters = Counters::default();
+    metrics::with_local_recorder(&counters, || {
+        assert!(inner::fails().is_err());
+        assert!(propagate(inner::fails()).is_err());
+
+        let mut error = propagate(inner::fails()).unwrap_err();
+        error.set_code("E42");
+        assert!(propagate(Err(error)).is_err());
+        assert!(retry_fails().is_err());
+    });
+
+    assert_eq!(counters.get("metrics_tests::inner", ""), 5);
+    assert_eq!(counters.get("metrics_tests", ""), 4);
+    assert_eq!(counters.get("metrics_tests", "E42"), 1);
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "color")]
+// Kept in its own test binary, as it sets NO_COLOR for the whole process.
+
+#[macro_use]
+extern crate throw;
+
+use throw::fmt::{ColorChoice, ErrorFormatter};
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed", "attempt" => 2);
+}
+
+#[test]
+fn test_no_color() {
+    assert_eq!(ColorChoice::default(), ColorChoice::Auto);
+
+    std::env::set_var("NO_COLOR", "1");
+    let error = fails().unwrap_err();
+    let always = ErrorFormatter::new().color(ColorChoice::Always);
+    assert!(error.display_with(&always).to_string().contains('\x1b'));
+    assert!(!error.to_string().contains('\x1b'));
+    assert!(!format!("{:#}", error).contains('\x1b'));
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "opentelemetry")]
+
+#[macro_use]
+extern crate throw;
+extern crate opentelemetry;
+
+use std::borrow::Cow;
+use std::time::SystemTime;
+
+use opentelemetry::trace::{Span, SpanContext, Status};
+use opentelemetry::KeyValue;
+use throw::Result;
+
+#[derive(Default)]
+struct RecordingSpan {
+    attributes: Vec<KeyValue>,
+    events: Vec<(Cow<'static, str>, Vec<KeyValue>)>,
+    status: Status,
+}
+
+impl Span for RecordingSpan {
+    fn add_event_with_timestamp<T>(&mut self, name: T, _: SystemTime, attributes: Vec<KeyValue>)
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.events.push((name.into(), attributes));
+    }
+
+    fn span_context(&self) -> &SpanContext {
+        &SpanContext::NONE
+    }
+
+    fn is_recording(&self) -> bool {
+        true
+    }
+
+    fn set_attribute(&mut self, attribute: KeyValue) {
+        self.attributes.push(attribute);
+    }
+
+    fn set_status(&mut self, status: Status) {
+        self.status = status;
+    }
+
+    fn update_name<T>(&mut self, _: T)
+    where
+        T: Into<Cow<'static, str>>,
+    {
+    }
+
+    fn add_link(&mut self, _: SpanContext, _: Vec<KeyValue>) {}
+
+    fn end_with_timestamp(&mut self, _: SystemTime) {}
+}
+
+fn parse(input: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = throw!(input.parse::<u32>(), "input" => input.to_owned(), "attempt" => 2u8);
+    Ok(value)
+}
+
+#[test]
+fn test_record_on_span() {
+    let mut span = RecordingSpan::default();
+    let mut error = parse("x").unwrap_err();
+    error.add_context("limit", u64::MAX);
+    error.set_code("E42");
+    error.record_on_span(&mut span);
+
+    assert_eq!(span.status, Status::error("invalid digit found in string"));
+    assert_eq!(
+        span.attributes,
+        vec![
+            KeyValue::new("input", "x"),
+            KeyValue::new("attempt", 2i64),
+            KeyValue::new("limit", u64::MAX.to_string()),
+            KeyValue::new("error.code", "E42"),
+        ]
+    );
+
+    let point = &error.points()[0];
+    assert_eq!(span.events.len(), 1);
+    let (name, attributes) = &span.events[0];
+    assert_eq!(name, "exception");
+    assert_eq!(
+        attributes[..2],
+        [
+            KeyValue::new(
+                "exception.type",
+                std::any::type_name::<std::num::ParseIntError>()
+            ),
+            KeyValue::new("exception.message", "invalid digit found in string"),
+        ]
+    );
+    assert_eq!(attributes.len(), 3);
+    assert_eq!(attributes[2].key.as_str(), "exception.stacktrace");
+    // the crate, timestamp and thread are added after the location when they're recorded
+    assert!(attributes[2].value.as_str().starts_with(&format!(
+        "at {}:{} in opentelemetry_tests (tests/opentelemetry_tests.rs)",
+        point.line(),
+        point.column()
+    )));
+}
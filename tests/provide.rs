@@ -0,0 +1,36 @@
+#![cfg(all(feature = "std", feature = "nightly"))]
+#![feature(error_generic_member_access)]
+
+#[macro_use]
+extern crate throw;
+
+use std::error::{request_ref, Error};
+use std::num::ParseIntError;
+
+use throw::{ErrorPoint, Result};
+
+fn parse() -> Result<u32, ParseIntError> {
+    let value: u32 = throw!("x".parse());
+    Ok(value)
+}
+
+#[test]
+fn test_provide() {
+    let error = parse().unwrap_err();
+    let dyn_error: &dyn Error = &error;
+    assert_eq!(request_ref::<ParseIntError>(dyn_error), Some(error.error()));
+    assert_eq!(request_ref::<[ErrorPoint]>(dyn_error).unwrap().len(), 1);
+    assert!(request_ref::<String>(dyn_error).is_none());
+}
+
+#[cfg(feature = "backtrace")]
+#[test]
+fn test_provide_backtrace() {
+    use std::backtrace::Backtrace;
+
+    throw::set_force_backtrace(true);
+    let error = parse().unwrap_err();
+    throw::set_force_backtrace(false);
+    let provided = request_ref::<Backtrace>(&error as &dyn Error).unwrap();
+    assert!(std::ptr::eq(provided, error.backtrace().unwrap()));
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "pyo3")]
+
+#[macro_use]
+extern crate throw;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::types::{PyAnyMethods, PyDict, PyDictMethods, PyTuple, PyTupleMethods};
+use pyo3::{PyErr, Python};
+use throw::Result;
+
+fn parse(input: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = throw!(input.parse::<u32>(), "input" => input.to_owned());
+    Ok(value)
+}
+
+fn load(input: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = up!(parse(input), "attempt" => 2, "ratio" => 0.5);
+    Ok(value)
+}
+
+#[test]
+fn test_pyerr_from_error() {
+    Python::initialize();
+    let error = load("x").unwrap_err();
+    let (first, second) = (error.points()[0].line(), error.points()[1].line());
+    let err = PyErr::from(error);
+    Python::attach(|py| {
+        assert!(err.is_instance_of::<PyRuntimeError>(py));
+        let args = err.value(py).getattr("args").unwrap();
+        let args = args.cast::<PyTuple>().unwrap();
+        assert_eq!(args.len(), 2);
+
+        let message: String = args.get_item(0).unwrap().extract().unwrap();
+        assert_eq!(
+            message,
+            format!(
+                "invalid digit found in string\n\n\
+                 Traceback (most recent call last):\n  \
+                 File \"tests/pyo3_tests.rs\", line {}, in pyo3_tests\n  \
+                 File \"tests/pyo3_tests.rs\", line {}, in pyo3_tests",
+                second, first
+            )
+        );
+
+        let context = args.get_item(1).unwrap();
+        let context = context.cast::<PyDict>().unwrap();
+        let input: String = context
+            .get_item("input")
+            .unwrap()
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(input, "x");
+        let attempt: i32 = context
+            .get_item("attempt")
+            .unwrap()
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(attempt, 2);
+        let ratio: f64 = context
+            .get_item("ratio")
+            .unwrap()
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(ratio, 0.5);
+    });
+}
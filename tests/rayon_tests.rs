@@ -0,0 +1,54 @@
+#![cfg(feature = "rayon")]
+
+#[macro_use]
+extern crate throw;
+
+use rayon::prelude::*;
+
+use throw::{Result, ThrowParallelIteratorExt};
+
+fn check(value: u32) -> Result<u32, &'static str> {
+    throw_assert!(value < 50, "value too large");
+    Ok(value)
+}
+
+fn process(value: u32) -> Result<u32, &'static str> {
+    up!(check(value));
+    Ok(value * 2)
+}
+
+#[test]
+fn test_collect_throw_ok() {
+    let doubled: Vec<u32> = vec![1, 2, 4, 5]
+        .into_par_iter()
+        .map(process)
+        .collect_throw()
+        .unwrap();
+    assert_eq!(doubled, [2, 4, 8, 10]);
+}
+
+#[test]
+fn test_collect_throw_errors() {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .unwrap();
+    let errors = pool
+        .install(|| {
+            (1..101u32)
+                .into_par_iter()
+                .map(process)
+                .collect_throw::<Vec<_>, _, _>()
+        })
+        .unwrap_err();
+    assert_eq!(errors.len(), 51);
+    for (i, error) in errors.iter().enumerate() {
+        assert_eq!(*error.error(), "value too large");
+        assert_eq!(error.points().len(), 2);
+        let context = error.get_context();
+        assert_eq!(context[1].key(), "index");
+        assert_eq!(context[1].value().to_string(), (i + 49).to_string());
+        assert_eq!(context[2].key(), "thread");
+        assert!(context[2].value().to_string() == "0" || context[2].value().to_string() == "1");
+    }
+}
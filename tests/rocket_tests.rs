@@ -0,0 +1,59 @@
+#![cfg(feature = "rocket")]
+
+#[macro_use]
+extern crate throw;
+extern crate rocket;
+extern crate serde_json;
+
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use throw::http::HttpStatus;
+
+#[derive(Debug)]
+struct NotFound;
+
+impl std::fmt::Display for NotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("user not found")
+    }
+}
+
+impl HttpStatus for NotFound {
+    fn status(&self) -> u16 {
+        404
+    }
+}
+
+#[rocket::get("/users/<id>")]
+fn get_user(id: u32) -> throw::Result<&'static str, NotFound> {
+    if id != 1 {
+        throw_new!(NotFound, "user_id" => id);
+    }
+    Ok("ferris")
+}
+
+#[test]
+fn test_responder() {
+    let rocket = rocket::build().mount("/", rocket::routes![get_user]);
+    let client = Client::untracked(rocket).unwrap();
+    assert_eq!(
+        client.get("/users/1").dispatch().into_string().unwrap(),
+        "ferris"
+    );
+
+    let response = client.get("/users/2").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(
+        response.headers().get_one("Content-Type"),
+        Some("application/problem+json")
+    );
+    let body: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "type": "about:blank",
+            "title": "user not found",
+            "status": 404,
+        })
+    );
+}
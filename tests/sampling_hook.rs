@@ -0,0 +1,75 @@
+#![cfg(feature = "std")]
+
+#[macro_use]
+extern crate throw;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use throw::{Result, SamplingHook};
+
+static HOT: AtomicUsize = AtomicUsize::new(0);
+static COLD: AtomicUsize = AtomicUsize::new(0);
+static UNLIMITED: AtomicUsize = AtomicUsize::new(0);
+
+fn hot() -> Result<(), &'static str> {
+    throw_new!("hot");
+}
+
+fn propagates_hot() -> Result<(), &'static str> {
+    up!(hot());
+    Ok(())
+}
+
+fn cold() -> Result<(), &'static str> {
+    throw_new!("cold");
+}
+
+// Kept in its own test binary, as the hook applies to every test running alongside it.
+#[test]
+fn test_sampling_hook() {
+    let sampling = SamplingHook::new(
+        3,
+        Duration::from_secs(3600),
+        Box::new(|event| {
+            let counter = match event.error() {
+                Some("hot") => &HOT,
+                _ => &COLD,
+            };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }),
+    );
+    throw::set_hook(sampling.into_hook());
+    for _ in 0..10 {
+        assert!(hot().is_err());
+    }
+    // propagating the error records its points under the same origin, which is already limited
+    for _ in 0..10 {
+        assert!(propagates_hot().is_err());
+    }
+    assert!(cold().is_err());
+    assert_eq!(HOT.load(Ordering::SeqCst), 3);
+    assert_eq!(COLD.load(Ordering::SeqCst), 1);
+
+    // each event starts a new window once the window has passed
+    let unlimited = Arc::new(SamplingHook::new(
+        1,
+        Duration::from_secs(0),
+        Box::new(|_| {
+            UNLIMITED.fetch_add(1, Ordering::SeqCst);
+        }),
+    ));
+    let hook = Arc::clone(&unlimited);
+    throw::set_hook(Box::new(move |event| hook.call(event)));
+    for _ in 0..5 {
+        assert!(hot().is_err());
+    }
+    assert_eq!(UNLIMITED.load(Ordering::SeqCst), 5);
+    assert_eq!(unlimited.__origins(), 1);
+    // the hot origin's window has passed, so it's forgotten when the cold one comes in
+    assert!(cold().is_err());
+    assert_eq!(UNLIMITED.load(Ordering::SeqCst), 6);
+    assert_eq!(unlimited.__origins(), 1);
+    throw::take_hook();
+}
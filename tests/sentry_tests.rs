@@ -0,0 +1,45 @@
+#![cfg(feature = "sentry")]
+
+#[macro_use]
+extern crate throw;
+extern crate sentry_core;
+
+use sentry_core::protocol::{Level, Value};
+use throw::{Result, Severity};
+
+fn parse(input: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = throw!(input.parse::<u32>());
+    Ok(value)
+}
+
+fn load(input: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = up!(parse(input));
+    Ok(value)
+}
+
+#[test]
+fn test_breadcrumbs() {
+    let mut error = load("x").unwrap_err();
+    error.set_severity(Severity::Warning);
+    let breadcrumbs = error.breadcrumbs();
+    assert_eq!(breadcrumbs.len(), 2);
+
+    for (breadcrumb, point) in breadcrumbs.iter().zip(error.points()) {
+        assert_eq!(breadcrumb.ty, "error");
+        assert_eq!(breadcrumb.category.as_deref(), Some("throw"));
+        assert_eq!(breadcrumb.level, Level::Warning);
+        let location = format!(
+            "at {}:{} in sentry_tests (tests/sentry_tests.rs)",
+            point.line(),
+            point.column()
+        );
+        assert!(breadcrumb.message.as_ref().unwrap().starts_with(&location));
+        assert_eq!(breadcrumb.data["line"], Value::from(point.line()));
+        assert_eq!(breadcrumb.data["module_path"], Value::from("sentry_tests"));
+    }
+    assert_eq!(
+        breadcrumbs[0].data["error"],
+        Value::from("invalid digit found in string")
+    );
+    assert!(!breadcrumbs[1].data.contains_key("error"));
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "slog")]
+
+#[macro_use]
+extern crate throw;
+extern crate slog;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use slog::{Drain, Key, Level, Logger, Never, OwnedKVList, Record, Serializer, KV};
+use throw::Result;
+
+#[derive(Debug, PartialEq)]
+struct Logged {
+    level: Level,
+    module: &'static str,
+    message: String,
+    line: u32,
+    key_values: Vec<(String, String)>,
+}
+
+struct KeyValues(Vec<(String, String)>);
+
+impl Serializer for KeyValues {
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        self.0.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct Recorder(Arc<Mutex<Vec<Logged>>>);
+
+impl Drain for Recorder {
+    type Ok = ();
+    type Err = Never;
+
+    fn log(&self, record: &Record, _: &OwnedKVList) -> std::result::Result<(), Never> {
+        let mut key_values = KeyValues(Vec::new());
+        record.kv().serialize(record, &mut key_values).unwrap();
+        self.0.lock().unwrap().push(Logged {
+            level: record.level(),
+            module: record.module(),
+            message: record.msg().to_string(),
+            line: record.line(),
+            key_values: key_values.0,
+        });
+        Ok(())
+    }
+}
+
+fn parse(input: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = throw!(input.parse::<u32>(), "input" => input.to_owned(), "attempt" => 2u8);
+    Ok(value)
+}
+
+#[test]
+fn test_log_to() {
+    let recorder = Recorder(Arc::new(Mutex::new(Vec::new())));
+    let logger = Logger::root(recorder.clone(), slog::o!());
+
+    let mut error = parse("x").unwrap_err();
+    error.set_code("E42");
+    error.log_to(&logger, Level::Warning);
+    throw::Error::new("no points").log_to(&logger, Level::Error);
+
+    let records = recorder.0.lock().unwrap();
+    assert_eq!(records.len(), 2);
+
+    let logged = &records[0];
+    let point = &error.points()[0];
+    assert_eq!(logged.level, Level::Warning);
+    assert_eq!(logged.module, module_path!());
+    assert_eq!(logged.message, "invalid digit found in string");
+    assert_eq!(logged.line, point.line());
+    let stack_trace = format!(
+        "at {}:{} in slog_tests (tests/slog_tests.rs)",
+        point.line(),
+        point.column()
+    );
+    assert_eq!(
+        logged.key_values[..3],
+        [
+            ("input".to_owned(), "x".to_owned()),
+            ("attempt".to_owned(), "2".to_owned()),
+            ("error.code".to_owned(), "E42".to_owned()),
+        ]
+    );
+    assert_eq!(logged.key_values.len(), 4);
+    assert_eq!(logged.key_values[3].0, "error.stack_trace");
+    // the crate, timestamp and thread are added after the location when they're recorded
+    assert!(logged.key_values[3].1.starts_with(&stack_trace));
+
+    let logged = &records[1];
+    assert_eq!(logged.module, "throw");
+    assert_eq!(logged.message, "no points");
+    assert!(logged.key_values.is_empty());
+}
@@ -2,6 +2,8 @@
 #[macro_use]
 extern crate throw;
 
+use std::future::Future;
+
 use throw::Result;
 
 #[derive(Debug)]
@@ -33,6 +35,7 @@ fn throws_error_with_description_and_key_value_pairs() -> Result<(), CustomError
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_error_description() {
     use std::error::Error;
 
@@ -41,6 +44,7 @@ fn test_error_description() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_error_description_with_key_value_pairs() {
     use std::error::Error;
 
@@ -49,6 +53,7 @@ fn test_error_description_with_key_value_pairs() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_error_with_cause() {
     use std::error::Error;
 
@@ -58,3 +63,630 @@ fn test_error_with_cause() {
         "CustomError: err"
     );
 }
+
+fn throws_in_scope() -> Result<(), CustomError> {
+    context_scope!("request_id" => 7, "tenant" => "acme");
+    throw!(Err(CustomError("err".to_owned())), "key" => "value");
+    Ok(())
+}
+
+fn throws_in_nested_scope() -> Result<(), CustomError> {
+    context_scope!("outer" => 1);
+    {
+        context_scope!("inner" => 2);
+        let _ = throws_error_with_description();
+    }
+    up!(throws_error_with_description());
+    Ok(())
+}
+
+#[test]
+fn test_context_scope() {
+    let error = throws_in_scope().unwrap_err();
+    let keys: Vec<_> = error.get_context().iter().map(|kv| kv.key()).collect();
+    assert_eq!(keys, ["request_id", "tenant", "key"]);
+
+    let error = throws_error_with_description().unwrap_err();
+    assert!(error.get_context().is_empty());
+}
+
+#[test]
+fn test_nested_context_scope() {
+    let error = throws_in_nested_scope().unwrap_err();
+    let keys: Vec<_> = error.get_context().iter().map(|kv| kv.key()).collect();
+    assert_eq!(keys, ["outer"]);
+}
+
+/// Returns `Pending` once, so the future is polled again as it would be after an `.await`.
+struct YieldOnce(bool);
+
+impl std::future::Future for YieldOnce {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context,
+    ) -> std::task::Poll<()> {
+        if self.0 {
+            std::task::Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    }
+}
+
+fn noop_waker() -> std::task::Waker {
+    use std::task::{RawWaker, RawWakerVTable, Waker};
+
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    unsafe { Waker::from_raw(clone(std::ptr::null())) }
+}
+
+#[test]
+fn test_scope_future() {
+    async fn handle() -> Result<(), CustomError> {
+        YieldOnce(false).await;
+        up!(throws_error_with_description());
+        Ok(())
+    }
+
+    let mut fut = Box::pin(throw::scope(handle()).context("request_id", 42));
+    let waker = noop_waker();
+    let mut cx = std::task::Context::from_waker(&waker);
+    assert!(fut.as_mut().poll(&mut cx).is_pending());
+
+    // the scope is only entered while the future is being polled
+    let error = throws_error_with_description().unwrap_err();
+    assert!(error.get_context().is_empty());
+
+    let error = match fut.as_mut().poll(&mut cx) {
+        std::task::Poll::Ready(result) => result.unwrap_err(),
+        std::task::Poll::Pending => panic!("expected the future to be ready"),
+    };
+    let context: Vec<_> = error
+        .get_context()
+        .iter()
+        .map(|kv| (kv.key(), kv.value().to_string()))
+        .collect();
+    assert_eq!(context, vec![("request_id", "42".to_owned())]);
+}
+
+#[cfg(unix)]
+fn throws_errno(path: &str) -> Result<(), std::io::Error> {
+    let result = std::fs::File::open(path);
+    throw_errno!(result.is_err(), "open failed", "path" => path.to_owned());
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn test_throw_errno() {
+    let error = throws_errno("/nonexistent/throw-test").unwrap_err();
+    assert_eq!(error.error().kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(error.points()[0].note(), Some("open failed"));
+
+    let keys: Vec<_> = error.get_context().iter().map(|kv| kv.key()).collect();
+    assert_eq!(keys, ["errno", "expression", "path"]);
+    assert_eq!(error.get_context()[0].value().to_string(), "2");
+    assert_eq!(
+        error.get_context()[1].value().to_string(),
+        "result.is_err()"
+    );
+}
+
+#[cfg(windows)]
+fn throws_win32(path: &str) -> Result<(), std::io::Error> {
+    let result = std::fs::File::open(path);
+    throw_win32!(result.is_err(), "open failed", "path" => path.to_owned());
+    Ok(())
+}
+
+#[test]
+#[cfg(windows)]
+fn test_throw_win32() {
+    let error = throws_win32("Z:\\nonexistent\\throw-test").unwrap_err();
+    assert_eq!(error.points()[0].note(), Some("open failed"));
+
+    let keys: Vec<_> = error.get_context().iter().map(|kv| kv.key()).collect();
+    assert_eq!(keys, ["last_error", "expression", "path"]);
+}
+
+fn throws_env(name: String) -> Result<String, std::env::VarError> {
+    let value = throw_env!(name, "purpose" => "testing");
+    Ok(value)
+}
+
+#[test]
+fn test_throw_env() {
+    let error = throws_env("THROW_TEST_MISSING_VARIABLE".to_owned()).unwrap_err();
+    assert_eq!(*error.error(), std::env::VarError::NotPresent);
+    assert_eq!(error.points().len(), 1);
+
+    let keys: Vec<_> = error.get_context().iter().map(|kv| kv.key()).collect();
+    assert_eq!(keys, ["variable", "purpose"]);
+    assert_eq!(
+        error.get_context()[0].value().to_string(),
+        "THROW_TEST_MISSING_VARIABLE"
+    );
+
+    assert!(throws_env("PATH".to_owned()).is_ok());
+}
+
+fn sleeps_then_throws() -> Result<(), CustomError> {
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    throw!(Err(CustomError("err".to_owned())));
+    Ok(())
+}
+
+fn throws_timed() -> Result<(), CustomError> {
+    timed!("sleep", {
+        up!(sleeps_then_throws());
+        Ok(())
+    });
+    Ok(())
+}
+
+#[test]
+fn test_timed() {
+    let error = throws_timed().unwrap_err();
+    assert_eq!(error.points().len(), 3);
+
+    let elapsed = &error.get_context()[0];
+    assert_eq!(elapsed.key(), "sleep_elapsed_ms");
+    match *elapsed.value() {
+        throw::ThrowContextValues::Uint64(ms) => assert!(ms >= 20, "elapsed: {}", ms),
+        ref other => panic!("unexpected elapsed value: {:?}", other),
+    }
+}
+
+#[test]
+fn test_report_handler() {
+    use throw::{DefaultReportHandler, Report, ReportHandler};
+
+    struct Rendered<'a>(&'a DefaultReportHandler, &'a Report);
+
+    impl std::fmt::Display for Rendered<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            self.0.render(self.1, f)
+        }
+    }
+
+    let report = Report::from(throws_error_with_description_and_key_value_pairs().unwrap_err());
+    let rendered = format!("{:?}", report);
+    assert!(
+        rendered.starts_with("\nError: CustomError: err\n\u{251c}\u{2500} key: value\n"),
+        "{}",
+        rendered
+    );
+    assert!(
+        rendered.contains("\n\nEnvironment:\n  os: "),
+        "{}",
+        rendered
+    );
+
+    let handler = DefaultReportHandler::new()
+        .formatter(throw::fmt::ErrorFormatter::new().style(throw::fmt::Style::Compact))
+        .backtrace(false)
+        .environment(false);
+    assert_eq!(
+        Rendered(&handler, &report).to_string(),
+        format!("\n{:#}", report.error())
+    );
+}
+
+#[test]
+fn test_report() {
+    use std::process::ExitCode;
+
+    assert_eq!(
+        throw::report(|| -> Result<(), CustomError> { Ok(()) }),
+        ExitCode::SUCCESS
+    );
+    assert_eq!(
+        throw::report(throws_error_with_description),
+        ExitCode::FAILURE
+    );
+}
+
+#[test]
+#[cfg(feature = "problem-json")]
+fn test_to_problem_json() {
+    let mut error = throw::Error::new("user not found");
+    error.add_context("user_id", 42);
+    error.add_context("status", "ignored");
+    error.add_context("region", "eu");
+    error.add_context("region", "us");
+    let problem = error.to_problem_json(404);
+    assert_eq!(
+        problem,
+        serde_json::json!({
+            "type": "about:blank",
+            "title": "user not found",
+            "status": 404,
+            "detail": error.to_string(),
+            "user_id": 42,
+            "region": "us",
+        })
+    );
+}
+
+#[test]
+#[cfg(feature = "ecs")]
+fn test_to_ecs_value() {
+    fn inner() -> Result<(), &'static str> {
+        throw_new!("disk full", "volume" => "/data", "free_bytes" => 0u64);
+    }
+    fn outer() -> Result<(), &'static str> {
+        up!(inner(), msg: "saving snapshot");
+        Ok(())
+    }
+
+    let mut error = outer().unwrap_err();
+    error.set_severity(throw::Severity::Warning);
+    let ecs = error.to_ecs_value();
+    assert_eq!(ecs["error"]["message"], "disk full");
+    assert_eq!(ecs["error"]["type"], "&str");
+    assert!(ecs["error"].get("code").is_none());
+    assert_eq!(ecs["log"]["level"], "warning");
+    assert_eq!(
+        ecs["labels"],
+        serde_json::json!({ "volume": "/data", "free_bytes": "0" })
+    );
+    let stack_trace = ecs["error"]["stack_trace"].as_str().unwrap();
+    let lines: Vec<&str> = stack_trace.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with("(tests/std_tests.rs) \u{2014} saving snapshot"));
+    assert!(lines[1].starts_with("at "));
+    // Display output also has the crate, timestamp and thread recorded for each point
+    #[cfg(not(any(
+        feature = "crate-info",
+        feature = "timestamps",
+        feature = "thread-info"
+    )))]
+    assert!(error
+        .to_string()
+        .ends_with(stack_trace.replace('\n', "\n\t").as_str()));
+}
+
+#[test]
+#[cfg(feature = "gelf")]
+fn test_to_gelf() {
+    let mut error = throw::Error::new("upload failed");
+    error.add_context("bytes", 1024);
+    error.add_context("ratio", 0.5);
+    error.add_context("user name", "alice");
+    error.add_context("id", 7);
+    error.set_severity(throw::Severity::Warning);
+    assert_eq!(
+        error.to_gelf("web-1"),
+        serde_json::json!({
+            "version": "1.1",
+            "host": "web-1",
+            "short_message": "upload failed",
+            "full_message": error.to_string(),
+            "level": 4,
+            "_bytes": 1024,
+            "_ratio": 0.5,
+            "_user_name": "alice",
+        })
+    );
+    assert_eq!(throw::Error::new("x").to_gelf("h")["level"], 3);
+}
+
+#[test]
+#[cfg(all(feature = "std", feature = "serde-1-std"))]
+fn test_to_json() {
+    fn fails() -> Result<(), &'static str> {
+        throw_new!("failed", "attempt" => 2);
+    }
+
+    let error = fails().unwrap_err();
+    assert_eq!(
+        error.to_json_string(),
+        serde_json::to_string(&error).unwrap()
+    );
+    let value = error.to_json_value();
+    assert_eq!(value["error"], "failed");
+    assert_eq!(
+        value["context"],
+        serde_json::json!([{ "key": "attempt", "value": 2 }])
+    );
+    assert_eq!(value["points"][0]["file"], "tests/std_tests.rs");
+
+    let formatter = throw::fmt::ErrorFormatter::new().style(throw::fmt::Style::Json);
+    assert_eq!(
+        error.display_with(&formatter).to_string(),
+        error.to_json_string()
+    );
+}
+
+#[test]
+#[cfg(all(feature = "std", feature = "serde-1-std"))]
+fn test_json_reporter() {
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn fails(attempt: u32) -> Result<(), &'static str> {
+        throw_new!("failed", "attempt" => attempt);
+    }
+
+    let buffer = Buffer::default();
+    let reporter = throw::report::JsonReporter::new(buffer.clone());
+    reporter.report(&fails(1).unwrap_err()).unwrap();
+    reporter.report(&fails(2).unwrap_err()).unwrap();
+    reporter.report(&throw::Error::new("other")).unwrap();
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let documents: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(documents.len(), 3);
+    assert_eq!(documents[1]["error"], "failed");
+    assert_eq!(
+        documents[1]["context"],
+        serde_json::json!([{ "key": "attempt", "value": 2 }])
+    );
+    assert_eq!(documents[1]["points"][0]["file"], "tests/std_tests.rs");
+    assert!(documents[1]["timestamp_us"].as_u64().unwrap() > 0);
+    assert_eq!(documents[0]["fingerprint"], documents[1]["fingerprint"]);
+    assert_ne!(documents[1]["fingerprint"], documents[2]["fingerprint"]);
+    // an error without points hashes nothing, so it's the FNV-1a offset basis
+    assert_eq!(documents[2]["fingerprint"], "cbf29ce484222325");
+}
+
+#[test]
+#[cfg(feature = "color")]
+fn test_color() {
+    use throw::fmt::{ColorChoice, ErrorFormatter};
+
+    fn fails() -> Result<(), &'static str> {
+        throw_new!("failed", "attempt" => 2);
+    }
+
+    let error = fails().unwrap_err();
+    let formatter = ErrorFormatter::new().color(ColorChoice::Always);
+    let colored = error.display_with(&formatter).to_string();
+    assert!(
+        colored.starts_with("\x1b[31mError: failed\x1b[0m\n\t\x1b[1mattempt\x1b[0m: 2\n\tat "),
+        "{:?}",
+        colored
+    );
+    assert!(
+        colored.contains(" (\x1b[2mtests/std_tests.rs\x1b[0m)"),
+        "{:?}",
+        colored
+    );
+
+    let formatter = ErrorFormatter::new().color(ColorChoice::Never);
+    assert_eq!(
+        error.display_with(&formatter).to_string(),
+        error.to_string()
+    );
+}
+
+#[test]
+fn test_render_with_source() {
+    fn fails() -> Result<(), &'static str> {
+        throw_new!("failed");
+    }
+
+    let error = fails().unwrap_err();
+    let line = error.points()[0].line().to_string();
+    let gutter = " ".repeat(line.len());
+    assert_eq!(
+        error.render_with_source(),
+        format!(
+            "{}\n\t{} |\n\t{} |         throw_new!(\"failed\");\n\t{} |         ^",
+            error, gutter, line, gutter
+        )
+    );
+}
+
+#[test]
+fn test_display_with_backtrace() {
+    fn fails() -> Result<(), &'static str> {
+        throw_new!("failed");
+    }
+
+    let error = fails().unwrap_err();
+    let disabled = std::backtrace::Backtrace::disabled();
+    assert_eq!(
+        error.display_with_backtrace(&disabled).to_string(),
+        error
+            .to_string()
+            .replace("\n\tat", "\n\tbacktrace and points, innermost first:\n\tat")
+    );
+
+    let backtrace = std::backtrace::Backtrace::force_capture();
+    let rendered = error.display_with_backtrace(&backtrace).to_string();
+    let point = error.to_string().lines().nth(1).unwrap().to_owned();
+    assert!(rendered.lines().any(|line| line == point), "{}", rendered);
+    if backtrace
+        .to_string()
+        .contains("test_display_with_backtrace")
+    {
+        assert!(
+            rendered.contains(": std_tests::test_display_with_backtrace"),
+            "{}",
+            rendered
+        );
+    }
+}
+
+#[test]
+fn test_render_panic_payload() {
+    use std::panic::{catch_unwind, panic_any, AssertUnwindSafe};
+
+    let mut pretty = String::new();
+    let payload = catch_unwind(AssertUnwindSafe(|| {
+        let result = throws_error_with_description_and_key_value_pairs();
+        pretty = result.as_ref().unwrap_err().to_string_pretty();
+        result.expect("config")
+    }))
+    .unwrap_err();
+    let rendered = throw::render_panic_payload(&*payload).unwrap();
+    let (prefix, rendered) = rendered.split_once('\n').unwrap();
+    assert_eq!(prefix, "config:");
+    // the inner error is parsed from its Debug output
+    assert_eq!(
+        rendered,
+        pretty.replace("CustomError: err", "CustomError(\"err\")")
+    );
+
+    let payload = catch_unwind(|| panic_any(throw::Error::new("failed"))).unwrap_err();
+    assert_eq!(
+        throw::render_panic_payload(&*payload).unwrap(),
+        "Error: failed"
+    );
+
+    let payload = catch_unwind(|| panic!("Error: not a throw error")).unwrap_err();
+    assert_eq!(throw::render_panic_payload(&*payload), None);
+}
+
+#[test]
+fn test_catch_panic() {
+    let line = line!() + 1;
+    let error = catch_panic!(|| -> u32 { panic!("failed with {}", 2) }).unwrap_err();
+    assert_eq!(error.error().message(), "failed with 2");
+    assert_eq!(error.error().location(), Some((file!(), line, 42)));
+    assert_eq!(
+        error.error().to_string(),
+        format!("panicked at {}:{}:42: failed with 2", file!(), line)
+    );
+    assert_eq!(error.points().len(), 1);
+    assert_eq!(error.points()[0].line(), line);
+    assert_eq!(error.points()[0].module_path(), module_path!());
+
+    let error = catch_panic!(|| std::panic::panic_any(5)).unwrap_err();
+    assert_eq!(error.error().message(), "Box<dyn Any>");
+
+    assert_eq!(catch_panic!(|| "ok").unwrap(), "ok");
+}
+
+#[test]
+fn test_exit() {
+    use std::process::{ExitCode, Termination};
+    use throw::{Exit, ExitStatus};
+
+    fn open() -> Result<(), std::io::Error> {
+        throw!(std::fs::File::open("/nonexistent/throw-test"));
+        Ok(())
+    }
+
+    assert_eq!(open().unwrap_err().error().exit_code(), 66);
+    assert_eq!(Exit::from(open()).report(), ExitCode::from(66));
+    assert_eq!(Exit::<&str>::from(Ok(())).report(), ExitCode::SUCCESS);
+    assert_eq!(
+        Exit(Err(throw::Error::new("failed"))).report(),
+        ExitCode::from(1)
+    );
+}
+
+#[test]
+#[cfg(feature = "ffi")]
+fn test_ffi_error() {
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+
+    fn string(ptr: *const c_char) -> String {
+        assert!(!ptr.is_null());
+        unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned()
+    }
+
+    fn fails() -> Result<(), String> {
+        throw_new!("bad\0byte".to_owned(), "user" => "alice", "attempt" => 2);
+    }
+
+    let error = fails().unwrap_err();
+    let ffi = throw::ffi::FfiError::new(&error);
+    unsafe {
+        let ffi_ref = &*ffi;
+        assert_eq!(string(ffi_ref.message), "bad\u{fffd}byte");
+        assert_eq!(
+            string(ffi_ref.display),
+            error.to_string().replace('\0', "\u{fffd}")
+        );
+
+        assert_eq!(ffi_ref.points_len, 1);
+        let point = &*ffi_ref.points;
+        assert_eq!(point.line, error.points()[0].line());
+        assert_eq!(point.column, error.points()[0].column());
+        assert_eq!(string(point.module_path), "std_tests");
+        assert_eq!(string(point.file), "tests/std_tests.rs");
+        assert!(point.note.is_null());
+
+        assert_eq!(ffi_ref.context_len, 2);
+        let keys = std::slice::from_raw_parts(ffi_ref.context_keys, 2);
+        let values = std::slice::from_raw_parts(ffi_ref.context_values, 2);
+        assert_eq!(string(keys[0]), "user");
+        assert_eq!(string(values[0]), "alice");
+        assert_eq!(string(keys[1]), "attempt");
+        assert_eq!(string(values[1]), "2");
+
+        throw::ffi::throw_ffi_error_free(ffi);
+        throw::ffi::throw_ffi_error_free(std::ptr::null_mut());
+    }
+}
+
+#[test]
+fn test_future_up() {
+    use throw::ThrowFutureExt;
+
+    async fn fetch(id: u32) -> Result<u32, &'static str> {
+        if id == 0 {
+            throw_new!("invalid id");
+        }
+        Ok(id)
+    }
+
+    assert_eq!(futures_executor::block_on(fetch(3).up()).unwrap(), 3);
+
+    let line = line!() + 2;
+    let fut = fetch(0)
+        .up()
+        .throw_context("attempt", 2)
+        .throw_context("user", "alice");
+    let error = futures_executor::block_on(fut).unwrap_err();
+    assert_eq!(error.points().len(), 2);
+    let point = &error.points()[1];
+    assert_eq!(point.line(), line);
+    assert_eq!(point.file(), "tests/std_tests.rs");
+    assert_eq!(point.module_path(), "<unknown>");
+    let context: Vec<_> = error
+        .get_context()
+        .iter()
+        .map(|kv| (kv.key(), kv.value().to_string(), kv.point()))
+        .collect();
+    assert_eq!(
+        context,
+        vec![
+            ("attempt", "2".to_owned(), Some(1)),
+            ("user", "alice".to_owned(), Some(1)),
+        ]
+    );
+
+    let error = futures_executor::block_on(fetch(0).throw_context("attempt", 1)).unwrap_err();
+    assert_eq!(error.points().len(), 2);
+    assert_eq!(error.get_context()[0].point(), Some(1));
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "futures")]
+
+extern crate throw;
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use futures_executor::block_on;
+use throw::{Result, ThrowStreamExt};
+
+struct Items(VecDeque<Result<u32, &'static str>>);
+
+impl Stream for Items {
+    type Item = Result<u32, &'static str>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.0.pop_front())
+    }
+}
+
+fn items(items: Vec<Result<u32, &'static str>>) -> Items {
+    Items(items.into())
+}
+
+#[test]
+fn test_up_throw() {
+    let line = line!() + 7;
+    let stream = items(vec![
+        Ok(1),
+        Err(throw::Error::new("first")),
+        Ok(3),
+        Err(throw::Error::new("second")),
+    ])
+    .up_throw();
+    let errors = block_on(stream.try_collect_throw()).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    for (error, (message, index)) in errors.iter().zip(vec![("first", "1"), ("second", "3")]) {
+        assert_eq!(*error.error(), message);
+        assert_eq!(error.points().len(), 1);
+        assert_eq!(error.points()[0].line(), line);
+        assert_eq!(error.points()[0].file(), "tests/stream_tests.rs");
+        assert_eq!(error.get_context()[0].key(), "index");
+        assert_eq!(error.get_context()[0].value().to_string(), index);
+        assert_eq!(error.get_context()[0].point(), Some(0));
+    }
+}
+
+#[test]
+fn test_try_collect_throw() {
+    let values = block_on(items(vec![Ok(1), Ok(2), Ok(3)]).try_collect_throw()).unwrap();
+    assert_eq!(values, vec![1, 2, 3]);
+    let values = block_on(items(vec![]).up_throw().try_collect_throw()).unwrap();
+    assert!(values.is_empty());
+}
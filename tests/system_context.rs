@@ -0,0 +1,47 @@
+#![cfg(feature = "system-context")]
+// Kept in its own test binary, as `set_system_context()` affects every error created in the
+// process.
+
+#[macro_use]
+extern crate throw;
+
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed", "attempt" => 3);
+}
+
+fn keys<E>(error: &throw::Error<E>) -> Vec<&str> {
+    error.get_context().iter().map(|pair| pair.key()).collect()
+}
+
+#[test]
+fn test_system_context() {
+    let error = fails().unwrap_err();
+    assert_eq!(keys(&error), ["attempt"]);
+
+    let error = error.with_system_context();
+    let context = error.get_context();
+    assert_eq!(context[1].key(), "pid");
+    assert_eq!(
+        context[1].value().to_string(),
+        std::process::id().to_string()
+    );
+    assert!(context
+        .iter()
+        .any(|pair| pair.key() == "executable"
+            && pair.value().to_string().starts_with("system_context")));
+    assert_eq!(context.last().unwrap().key(), "os");
+    assert_eq!(
+        context.last().unwrap().value().to_string(),
+        std::env::consts::OS
+    );
+
+    throw::set_system_context(true);
+    let error = fails().unwrap_err();
+    assert_eq!(keys(&error)[0], "pid");
+    assert_eq!(keys(&error).last(), Some(&"attempt"));
+
+    throw::set_system_context(false);
+    assert_eq!(keys(&fails().unwrap_err()), ["attempt"]);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "test-util")]
+#[macro_use]
+extern crate throw;
+
+use throw::test_util::{normalize_trace, trace_eq, trace_matches};
+use throw::Result;
+
+fn throws() -> Result<(), &'static str> {
+    throw_new!("hi", "key" => "value");
+}
+
+fn throws_up() -> Result<(), &'static str> {
+    up!(throws(), msg: "while testing");
+    Ok(())
+}
+
+#[test]
+fn test_normalize_trace() {
+    assert_eq!(
+        normalize_trace("Error: hi\n    key: value\n\tat 12:34 in a::b (src/b.rs) — note"),
+        "Error: hi\n\tkey: value\n\tat _:_ in a::b (src/b.rs) — note"
+    );
+    assert_eq!(
+        normalize_trace("Error: hi\n\tat 1:2 in a (src/a.rs) [a 0.1.0, thread 'main' #1] — note"),
+        "Error: hi\n\tat _:_ in a (src/a.rs) — note"
+    );
+    assert_eq!(
+        normalize_trace("Error: at 12:34 in text\n\tat here in b (src/b.rs)"),
+        "Error: at 12:34 in text\n\tat here in b (src/b.rs)"
+    );
+}
+
+#[test]
+fn test_trace_eq() {
+    let actual = throws_up().unwrap_err().to_string();
+    assert!(trace_eq(
+        "Error: hi
+    key: value
+    at _:_ in test_util (tests/test_util.rs) — while testing
+    at 1:1 in test_util (tests/test_util.rs)",
+        &actual
+    ));
+    assert!(!trace_eq("Error: hi", &actual));
+}
+
+#[test]
+fn test_trace_matches() {
+    let actual = throws().unwrap_err().to_string();
+    assert!(trace_matches(
+        r"^Error: hi\n    key: value\n    at \d+:\d+",
+        &actual
+    ));
+}
+
+#[test]
+fn test_assert_trace_macros() {
+    assert_trace_eq!(
+        throws().unwrap_err(),
+        "Error: hi
+    key: value
+    at _:_ in test_util (tests/test_util.rs)"
+    );
+    throw::test_util::assert_trace_matches!(throws().unwrap_err(), "key: value");
+}
+
+#[test]
+#[should_panic(expected = "expected error to equal")]
+fn test_assert_trace_eq_failure() {
+    assert_trace_eq!(throws().unwrap_err(), "Error: bye");
+}
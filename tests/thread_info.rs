@@ -0,0 +1,55 @@
+#![cfg(feature = "thread-info")]
+// Kept in its own test binary, as thread info changes how every point is rendered.
+
+#[macro_use]
+extern crate throw;
+
+use std::sync::mpsc;
+use std::thread;
+
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed");
+}
+
+fn propagates(error: throw::Error<&'static str>) -> Result<(), &'static str> {
+    up!(Err(error));
+    Ok(())
+}
+
+#[test]
+fn test_thread_info() {
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("worker".to_owned())
+        .spawn(move || sender.send(fails().unwrap_err()).unwrap())
+        .unwrap()
+        .join()
+        .unwrap();
+    let error = propagates(receiver.recv().unwrap()).unwrap_err();
+    let points = error.points();
+    assert_eq!(points[0].thread_name(), Some("worker"));
+    assert_eq!(points[1].thread_name(), thread::current().name());
+    assert!(points[0].thread_id().is_some());
+    assert_ne!(points[0].thread_id(), points[1].thread_id());
+
+    let rendered = error.to_string();
+    assert!(rendered.contains(&format!(
+        "thread 'worker' #{}]",
+        points[0].thread_id().unwrap()
+    )));
+    let parsed: throw::Error<String> = rendered.parse().unwrap();
+    assert_eq!(parsed.points()[0].thread_name(), Some("worker"));
+    assert_eq!(parsed.points()[0].thread_id(), points[0].thread_id());
+    assert_eq!(parsed.to_string(), rendered);
+}
+
+#[test]
+fn test_unnamed_thread() {
+    let text = "Error: failed\n\tat 3:5 in app (src/main.rs) [thread #7]";
+    let error: throw::Error<String> = text.parse().unwrap();
+    assert_eq!(error.points()[0].thread_name(), None);
+    assert_eq!(error.points()[0].thread_id(), Some(7));
+    assert_eq!(error.to_string(), text);
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "timestamps")]
+// Kept in its own test binary, as timestamps change how every point is rendered.
+
+#[macro_use]
+extern crate throw;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use throw::Result;
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("failed");
+}
+
+fn propagates() -> Result<(), &'static str> {
+    up!(fails());
+    Ok(())
+}
+
+fn now() -> std::time::Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+}
+
+#[test]
+fn test_timestamps() {
+    let before = now();
+    let error = propagates().unwrap_err();
+    let after = now();
+    let timestamps: Vec<_> = error
+        .points()
+        .iter()
+        .map(|point| point.timestamp().unwrap())
+        .collect();
+    assert!(before <= timestamps[0]);
+    assert!(timestamps[0] <= timestamps[1]);
+    assert!(timestamps[1] <= after);
+
+    let rendered = error.to_string();
+    let parsed: throw::Error<String> = rendered.parse().unwrap();
+    for (parsed, point) in parsed.points().iter().zip(error.points()) {
+        let timestamp = point.timestamp().unwrap();
+        assert_eq!(parsed.timestamp().unwrap().as_secs(), timestamp.as_secs());
+        assert_eq!(
+            parsed.timestamp().unwrap().subsec_micros(),
+            timestamp.subsec_micros()
+        );
+    }
+    assert_eq!(parsed.to_string(), rendered);
+}
+
+#[test]
+fn test_timestamp_rendering() {
+    let text = "Error: failed\n\tat 3:5 in app (src/main.rs) [2024-02-29T23:59:58.000042Z]";
+    let error: throw::Error<String> = text.parse().unwrap();
+    let timestamp = error.points()[0].timestamp().unwrap();
+    assert_eq!(timestamp.as_secs(), 1_709_251_198);
+    assert_eq!(timestamp.subsec_micros(), 42);
+    assert_eq!(error.to_string(), text);
+}
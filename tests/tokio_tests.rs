@@ -0,0 +1,81 @@
+#![cfg(feature = "tokio")]
+
+#[macro_use]
+extern crate throw;
+
+use std::future::Future;
+
+use throw::task::TaskError;
+use throw::Result;
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+async fn fetch(id: u32) -> Result<u32, &'static str> {
+    if id == 0 {
+        throw_new!("invalid id", "id" => id);
+    }
+    Ok(id * 2)
+}
+
+#[test]
+fn test_spawn_ok() {
+    assert_eq!(
+        block_on(async { throw::task::spawn(fetch(2)).await }).unwrap(),
+        4
+    );
+}
+
+#[test]
+fn test_spawn_failed() {
+    let (line, error) = block_on(async {
+        let line = line!() + 1;
+        let handle = throw::task::spawn(fetch(0));
+        (line, handle.await.unwrap_err())
+    });
+    assert!(matches!(error.error(), TaskError::Failed("invalid id")));
+    assert_eq!(error.points().len(), 2);
+    assert_eq!(error.points()[1].line(), line);
+    assert_eq!(error.points()[1].file(), "tests/tokio_tests.rs");
+    assert_eq!(error.get_context()[0].key(), "id");
+    assert_eq!(error.get_context()[0].point(), Some(0));
+    assert!(error.to_string().starts_with("Error: invalid id"));
+}
+
+#[test]
+fn test_spawn_panicked() {
+    let error = block_on(async {
+        throw::task::spawn(async {
+            if true {
+                panic!("task exploded");
+            }
+            Ok::<(), throw::Error<&'static str>>(())
+        })
+        .await
+        .unwrap_err()
+    });
+    match error.error() {
+        TaskError::Panicked(panic) => assert_eq!(panic.message(), "task exploded"),
+        other => panic!("expected a panic, got {:?}", other),
+    }
+    assert_eq!(error.points().len(), 1);
+    assert_eq!(error.error().to_string(), "task panicked: task exploded");
+}
+
+#[test]
+fn test_spawn_cancelled() {
+    let error = block_on(async {
+        let handle = throw::task::spawn(async {
+            std::future::pending::<()>().await;
+            Ok::<(), throw::Error<&'static str>>(())
+        });
+        handle.abort();
+        handle.await.unwrap_err()
+    });
+    assert!(matches!(error.error(), TaskError::Cancelled));
+    assert_eq!(error.error().to_string(), "task was cancelled");
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "tonic")]
+// Kept in its own test binary, as `set_exposed_context()` affects every status in the process.
+
+#[macro_use]
+extern crate throw;
+extern crate tonic;
+
+use throw::grpc::GrpcCode;
+use throw::Result;
+use tonic::{Code, Status};
+
+#[derive(Debug)]
+struct NotFound;
+
+impl std::fmt::Display for NotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("user not found")
+    }
+}
+
+impl GrpcCode for NotFound {
+    fn grpc_code(&self) -> Code {
+        Code::NotFound
+    }
+}
+
+fn get_user(user_id: u32) -> Result<(), NotFound> {
+    throw_new!(NotFound, "user_id" => user_id, "Request ID" => "abc", "token" => "secret");
+}
+
+fn handler(user_id: u32) -> std::result::Result<(), Status> {
+    get_user(user_id)?;
+    Ok(())
+}
+
+#[test]
+fn test_status() {
+    let status = handler(1).unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+    assert_eq!(status.message(), "user not found");
+    let fingerprint = status.metadata().get("throw-fingerprint").unwrap();
+    assert_eq!(fingerprint.len(), 16);
+    assert!(status.metadata().get("user_id").is_none());
+
+    throw::grpc::set_exposed_context(&["user_id", "Request ID"]);
+    let status = handler(2).unwrap_err();
+    assert_eq!(
+        status.metadata().get("throw-fingerprint").unwrap(),
+        fingerprint
+    );
+    assert_eq!(status.metadata().get("user_id").unwrap(), "2");
+    assert_eq!(status.metadata().get("request-id").unwrap(), "abc");
+    assert!(status.metadata().get("token").is_none());
+
+    let status = Status::from(throw::Error::new("failed"));
+    assert_eq!(status.code(), Code::Internal);
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "tower")]
+
+#[macro_use]
+extern crate throw;
+
+use std::future::{self, Ready};
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+use futures_executor::block_on;
+use throw::tower::ThrowReportLayer;
+use throw::Result;
+use tower_layer::Layer;
+use tower_service::Service;
+
+static RECORDED: Mutex<Vec<(Option<String>, Vec<String>)>> = Mutex::new(Vec::new());
+
+fn lookup(path: &str) -> Result<String, &'static str> {
+    if path == "/missing" {
+        throw_new!("not found", "attempt" => 1);
+    }
+    Ok(format!("found {}", path))
+}
+
+struct Lookup;
+
+impl Service<String> for Lookup {
+    type Response = String;
+    type Error = throw::Error<&'static str>;
+    type Future = Ready<Result<String, &'static str>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), &'static str>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, path: String) -> Self::Future {
+        future::ready(lookup(&path))
+    }
+}
+
+// Kept in its own test binary, as the hook applies to every test running alongside it.
+#[test]
+fn test_throw_report_layer() {
+    throw::set_hook(Box::new(|event| {
+        RECORDED.lock().unwrap().push((
+            event.error().map(str::to_owned),
+            event
+                .context()
+                .iter()
+                .map(|kv| format!("{}: {}", kv.key(), kv.value()))
+                .collect(),
+        ));
+    }));
+    let mut service = ThrowReportLayer::new()
+        .request_context(|path: &String| vec![("path", path.clone().into())])
+        .layer(Lookup);
+
+    assert_eq!(
+        block_on(service.call("/index".to_owned())),
+        Ok("found /index".to_owned())
+    );
+    assert!(RECORDED.lock().unwrap().is_empty());
+
+    assert_eq!(
+        block_on(service.call("/missing".to_owned())),
+        Err("not found")
+    );
+    throw::take_hook();
+    let recorded = RECORDED.lock().unwrap();
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(
+        recorded[1],
+        (
+            Some("not found".to_owned()),
+            vec!["attempt: 1".to_owned(), "path: /missing".to_owned()]
+        )
+    );
+}
@@ -0,0 +1,143 @@
+#![cfg(all(feature = "tracing", feature = "std"))]
+
+#[macro_use]
+extern crate throw;
+extern crate tracing;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use throw::{Result, Severity};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+#[derive(Debug, Default)]
+struct Recorded {
+    level: Option<Level>,
+    target: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for Recorded {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields
+            .push((field.name().to_owned(), format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields
+            .push((field.name().to_owned(), value.to_owned()));
+    }
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Recorded>>>);
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut recorded = Recorded {
+            level: Some(*event.metadata().level()),
+            target: event.metadata().target().to_owned(),
+            ..Recorded::default()
+        };
+        event.record(&mut recorded);
+        self.0.lock().unwrap().push(recorded);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn fails() -> Result<(), &'static str> {
+    throw_new!("upload failed", "bytes" => 1024, "host" => "a");
+}
+
+#[test]
+fn test_emit() {
+    let recorder = Recorder::default();
+    let error = fails().unwrap_err();
+    let mut warning = throw::Error::new("disk almost full");
+    warning.set_severity(Severity::Warning);
+    warning.set_code("W1");
+    tracing::subscriber::with_default(recorder.clone(), || {
+        error.emit();
+        warning.emit();
+    });
+
+    assert!(error.span_trace().is_none());
+
+    let events = recorder.0.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].level, Some(Level::ERROR));
+    assert_eq!(events[0].target, "throw");
+    let point = &error.points()[0];
+    assert_eq!(
+        events[0].fields[..3],
+        [
+            ("message".to_owned(), "upload failed".to_owned()),
+            ("context".to_owned(), "bytes: 1024, host: a".to_owned()),
+            (
+                "origin".to_owned(),
+                format!("tests/tracing_tests.rs:{}:{}", point.line(), point.column())
+            ),
+        ]
+    );
+    assert_eq!(events[0].fields.len(), 4);
+    assert_eq!(events[0].fields[3].0, "stack_trace");
+    // the crate, timestamp and thread are added after the location when they're recorded
+    assert!(events[0].fields[3].1.starts_with(&format!(
+        "at {}:{} in tracing_tests (tests/tracing_tests.rs)",
+        point.line(),
+        point.column()
+    )));
+    assert_eq!(events[1].level, Some(Level::WARN));
+    assert_eq!(
+        events[1].fields,
+        vec![
+            ("message".to_owned(), "disk almost full".to_owned()),
+            ("code".to_owned(), "W1".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_span_trace() {
+    use throw::fmt::ErrorFormatter;
+    use tracing_error::ErrorLayer;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let subscriber = tracing_subscriber::registry().with(ErrorLayer::default());
+    let (error, line) = tracing::subscriber::with_default(subscriber, || {
+        let line = line!() + 1;
+        let span = tracing::info_span!("handle_request", id = 42);
+        let _entered = span.enter();
+        (fails().unwrap_err(), line)
+    });
+    assert!(error.span_trace().is_some());
+    let formatter = ErrorFormatter::new().snapshot(true);
+    assert_eq!(
+        error.display_with(&formatter).to_string(),
+        format!(
+            "Error: upload failed\n\thost: a\n\tbytes: 1024\
+             \n\tat _:_ in tracing_tests (tests/tracing_tests.rs)\
+             \n\tin handle_request{{id=42}} (tests/tracing_tests.rs:{})",
+            line
+        )
+    );
+    let parsed: throw::Error<String> = error.to_string().parse().unwrap();
+    assert_eq!(parsed.points().len(), 1);
+}
@@ -0,0 +1,526 @@
+//! Procedural macros for [`throw`](https://docs.rs/throw/).
+//!
+//! These are re-exported by `throw` when its `macros` feature is enabled, and should be used
+//! through it rather than by depending on this crate directly.
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Expr, ExprReturn, ExprTry, Fields, Ident,
+    ItemFn, LitStr, ReturnType, Token, Type,
+};
+
+/// Rewrites a function to return a `throw::Result` wrapping its declared return type.
+///
+/// `#[throws(E)]` on a function declared as returning `T` changes its return type to
+/// `throw::Result<T, E>`. The function's body, and the value of any `return` expression in it,
+/// are wrapped in `Ok`, so errors are only ever returned using the macros from `throw`.
+#[proc_macro_attribute]
+pub fn throws(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let error = parse_macro_input!(attr as Type);
+    let mut function = parse_macro_input!(item as ItemFn);
+
+    let output = match function.sig.output {
+        ReturnType::Default => parse_quote!(()),
+        ReturnType::Type(_, ref ty) => (**ty).clone(),
+    };
+    function.sig.output = parse_quote!(-> ::throw::Result<#output, #error>);
+
+    OkWrapReturns.visit_block_mut(&mut function.block);
+    let block = &function.block;
+    function.block = parse_quote!({
+        ::core::result::Result::Ok(#block)
+    });
+
+    TokenStream::from(quote!(#function))
+}
+
+/// Wraps the value of each `return` expression in `Ok`, without descending into closures, async
+/// blocks, or nested items, which `return` from something else.
+struct OkWrapReturns;
+
+impl VisitMut for OkWrapReturns {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match *expr {
+            Expr::Closure(_) | Expr::Async(_) => {}
+            Expr::Return(ExprReturn { ref mut expr, .. }) => {
+                if let Some(ref mut value) = *expr {
+                    self.visit_expr_mut(value);
+                }
+                let value = match expr.take() {
+                    Some(value) => quote!(#value),
+                    None => quote!(()),
+                };
+                *expr = Some(parse_quote!(::core::result::Result::Ok(#value)));
+            }
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut syn::Item) {}
+}
+
+/// Adds an ErrorPoint to every error returned with `?` in a function returning a `throw::Result`.
+///
+/// Each `expr?` is rewritten to behave like `up!(expr)` if `expr` returns a `throw::Result`, and
+/// like `throw!(expr)` otherwise, with the ErrorPoint located at `expr`. The error is then
+/// transformed into the function's error type with `Into`, as `up!()` does.
+///
+/// As with `#[throws]`, closures, async blocks, and nested items are left alone. Inside generic
+/// code where the error type of `expr` isn't known, errors are always treated like `throw!()`
+/// would treat them.
+#[proc_macro_attribute]
+pub fn trace(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new(attr.span(), "#[trace] does not take any arguments")
+            .to_compile_error()
+            .into();
+    }
+    let mut function = parse_macro_input!(item as ItemFn);
+    TraceTries.visit_block_mut(&mut function.block);
+    TokenStream::from(quote!(#function))
+}
+
+/// Replaces each `?` with a match adding an ErrorPoint to the error, without descending into
+/// closures, async blocks, or nested items, in which `?` returns from something else.
+struct TraceTries;
+
+impl VisitMut for TraceTries {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match *expr {
+            Expr::Closure(_) | Expr::Async(_) => {}
+            Expr::Try(ExprTry {
+                expr: ref mut inner,
+                ..
+            }) => {
+                self.visit_expr_mut(inner);
+                let span = inner.span();
+                let replacement = quote_spanned! {span=>
+                    match #inner {
+                        ::core::result::Result::Ok(v) => v,
+                        ::core::result::Result::Err(e) => {
+                            #[allow(unused_imports)]
+                            use ::throw::__private::{ThrowKind as _, UpKind as _};
+                            let kind = (&e).__throw_trace_kind();
+                            let mut e = kind.into_error(e);
+                            let hook = ::throw::__point_hook!(e.error());
+                            e.__push_point(::throw::__errorpoint!());
+                            hook.run(&e);
+                            return ::core::result::Result::Err(e.transform());
+                        }
+                    }
+                };
+                *expr = parse_quote!(#replacement);
+            }
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut syn::Item) {}
+}
+
+/// Implements `throw::ThrowContext` for a struct with named fields.
+///
+/// Each field is added as a key/value pair keyed by the field's name, and must be `Clone` and
+/// convertible into `throw::ThrowContextValues`. A field can be left out with `#[throw(skip)]`, or
+/// given a different key with `#[throw(rename = "key")]`.
+#[proc_macro_derive(ThrowContext, attributes(throw))]
+pub fn derive_throw_context(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match throw_context_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn throw_context_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "ThrowContext can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "ThrowContext can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut additions = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let mut key = LitStr::new(&ident.to_string(), ident.span());
+        let mut skip = false;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("throw")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    key = meta.value()?.parse()?;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `skip` or `rename = \"...\"`"))
+                }
+            })?;
+        }
+        if !skip {
+            additions.push(quote! {
+                error.add_context(#key, ::core::clone::Clone::clone(&self.#ident));
+            });
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::throw::ThrowContext for #name #ty_generics #where_clause {
+            fn add_context_to<__ThrowE>(&self, error: &mut ::throw::Error<__ThrowE>) {
+                #(#additions)*
+            }
+        }
+    })
+}
+
+/// Implements `From` for the error wrapped by each single-field variant of an enum.
+///
+/// With `#[derive(UpFrom)]` on `enum AppError { Io(io::Error), Parse(ParseIntError) }`,
+/// `up!()`, `transform()` and `#[trace]` can turn a `throw::Error<io::Error>` into a
+/// `throw::Error<AppError>`, keeping its ErrorPoints and context. Variants can be left out with
+/// `#[throw(skip)]`, and variants with no fields or more than one are skipped.
+#[proc_macro_derive(UpFrom, attributes(throw))]
+pub fn derive_up_from(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match up_from_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn up_from_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let variants = match input.data {
+        Data::Enum(ref data) => &data.variants,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "UpFrom can only be derived for enums",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut impls = Vec::new();
+    for variant in variants {
+        let mut skip = false;
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("throw")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `skip`"))
+                }
+            })?;
+        }
+        if skip || variant.fields.len() != 1 {
+            continue;
+        }
+        let field = variant.fields.iter().next().expect("one field");
+        let ty = &field.ty;
+        let ident = &variant.ident;
+        let construct = match field.ident {
+            Some(ref field_name) => quote!(#name::#ident { #field_name: error }),
+            None => quote!(#name::#ident(error)),
+        };
+        impls.push(quote! {
+            impl #impl_generics ::core::convert::From<#ty> for #name #ty_generics #where_clause {
+                fn from(error: #ty) -> Self {
+                    #construct
+                }
+            }
+        });
+    }
+    Ok(quote!(#(#impls)*))
+}
+
+/// Adds the named arguments of a function returning a `throw::Result` to any error it returns.
+///
+/// `#[context_args(user_id, path)]` adds `user_id` and `path` as key/value pairs, formatted with
+/// `Display`. Arguments prefixed with `?`, as in `#[context_args(?query)]`, are formatted with
+/// `Debug` instead. Arguments are only formatted if the function returns an error, so the
+/// function's body must not move any of the named arguments.
+#[proc_macro_attribute]
+pub fn context_args(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr with parse_context_args);
+    let mut function = parse_macro_input!(item as ItemFn);
+
+    if let Some(asyncness) = function.sig.asyncness {
+        return syn::Error::new(
+            asyncness.span,
+            "#[context_args] does not support async functions",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let output = match function.sig.output {
+        ReturnType::Default => {
+            return syn::Error::new(
+                function.sig.ident.span(),
+                "#[context_args] requires a function returning a `throw::Result`",
+            )
+            .to_compile_error()
+            .into();
+        }
+        ReturnType::Type(_, ref ty) => (**ty).clone(),
+    };
+
+    let additions = args.iter().map(|&(debug, ref arg)| {
+        let key = LitStr::new(&arg.to_string(), arg.span());
+        let format = if debug {
+            quote!(::throw::__private::debug)
+        } else {
+            quote!(::throw::__private::display)
+        };
+        quote!(error.add_context(#key, #format(&#arg));)
+    });
+    let block = &function.block;
+    function.block = parse_quote!({
+        let result: #output = ::throw::__catch(|| #block);
+        match result {
+            ::core::result::Result::Ok(value) => ::core::result::Result::Ok(value),
+            ::core::result::Result::Err(mut error) => {
+                #(#additions)*
+                ::core::result::Result::Err(error)
+            }
+        }
+    });
+
+    TokenStream::from(quote!(#function))
+}
+
+/// Parses `#[context_args]` arguments: a comma-separated list of identifiers, each optionally
+/// prefixed with `?`.
+fn parse_context_args(input: syn::parse::ParseStream) -> syn::Result<Vec<(bool, Ident)>> {
+    let mut args = Vec::new();
+    while !input.is_empty() {
+        let debug = input.parse::<Option<Token![?]>>()?.is_some();
+        let ident = input.call(Ident::parse_any)?;
+        args.push((debug, ident));
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+    Ok(args)
+}
+
+/// Implements `throw::http::HttpStatus` from `#[http(status = ...)]` attributes.
+///
+/// `#[http(status = 404)]` on the type sets the status of every value. On an enum, the same
+/// attribute on a variant overrides the type's status for that variant. Variants without a status
+/// use the type's, and types without one use the trait's default of 500.
+#[proc_macro_derive(HttpStatus, attributes(http))]
+pub fn derive_http_status(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match http_status_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Parses the status given in a set of `#[http(status = ...)]` attributes.
+fn parse_http_status(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::LitInt>> {
+    let mut status = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("http")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("status") {
+                let lit: syn::LitInt = meta.value()?.parse()?;
+                match lit.base10_parse::<u16>()? {
+                    100..=999 => status = Some(lit),
+                    _ => {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "expected a status from 100 to 999",
+                        ))
+                    }
+                }
+                Ok(())
+            } else {
+                Err(meta.error("expected `status`"))
+            }
+        })?;
+    }
+    Ok(status)
+}
+
+fn http_status_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let default = match parse_http_status(&input.attrs)? {
+        Some(status) => quote!(#status),
+        None => quote!(500),
+    };
+
+    let mut arms = Vec::new();
+    if let Data::Enum(ref data) = input.data {
+        for variant in &data.variants {
+            if let Some(status) = parse_http_status(&variant.attrs)? {
+                let ident = &variant.ident;
+                arms.push(quote!(Self::#ident { .. } => #status,));
+            }
+        }
+    }
+    let status = if arms.is_empty() {
+        default
+    } else {
+        quote! {
+            #[allow(unreachable_patterns)]
+            match *self {
+                #(#arms)*
+                _ => #default,
+            }
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::throw::http::HttpStatus for #name #ty_generics #where_clause {
+            fn status(&self) -> u16 {
+                #status
+            }
+        }
+    })
+}
+
+/// Implements `throw::ThrowDiagnostic` from `#[diagnostic(...)]` attributes.
+///
+/// `#[diagnostic(code = "E1042", severity = "warning", help = "...")]` on the type sets its
+/// metadata, each part of which is optional. On an enum, the same attribute on a variant
+/// overrides the parts it gives for that variant, with the parts it leaves out taken from the
+/// type's attribute. Severity is one of `"error"`, `"warning"` (or `"warn"`) and `"advice"`.
+#[proc_macro_derive(ThrowDiagnostic, attributes(diagnostic))]
+pub fn derive_throw_diagnostic(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match throw_diagnostic_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Metadata given in one set of `#[diagnostic(...)]` attributes, as `Option<_>` expressions, with
+/// `None` for each part which isn't given.
+struct DiagnosticAttrs {
+    code: Option<proc_macro2::TokenStream>,
+    severity: Option<proc_macro2::TokenStream>,
+    help: Option<proc_macro2::TokenStream>,
+}
+
+impl DiagnosticAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<DiagnosticAttrs> {
+        let mut parsed = DiagnosticAttrs {
+            code: None,
+            severity: None,
+            help: None,
+        };
+        for attr in attrs.iter().filter(|a| a.path().is_ident("diagnostic")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("code") {
+                    let code: LitStr = meta.value()?.parse()?;
+                    parsed.code = Some(quote!(::core::option::Option::Some(#code)));
+                } else if meta.path.is_ident("help") {
+                    let help: LitStr = meta.value()?.parse()?;
+                    parsed.help = Some(quote!(::core::option::Option::Some(#help)));
+                } else if meta.path.is_ident("severity") {
+                    let severity: LitStr = meta.value()?.parse()?;
+                    let variant = match &*severity.value() {
+                        "error" => quote!(Error),
+                        "warning" | "warn" => quote!(Warning),
+                        "advice" => quote!(Advice),
+                        _ => {
+                            return Err(syn::Error::new(
+                                severity.span(),
+                                "expected \"error\", \"warning\" or \"advice\"",
+                            ))
+                        }
+                    };
+                    parsed.severity = Some(quote!(::core::option::Option::Some(
+                        ::throw::Severity::#variant
+                    )));
+                } else {
+                    return Err(meta.error("expected `code`, `severity` or `help`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+fn throw_diagnostic_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let defaults = DiagnosticAttrs::parse(&input.attrs)?;
+
+    let mut overrides = Vec::new();
+    if let Data::Enum(ref data) = input.data {
+        for variant in &data.variants {
+            overrides.push((&variant.ident, DiagnosticAttrs::parse(&variant.attrs)?));
+        }
+    }
+
+    let none = quote!(::core::option::Option::None);
+    let method = |get: fn(&DiagnosticAttrs) -> &Option<proc_macro2::TokenStream>| {
+        // variants which don't give a part use the type's
+        let default = get(&defaults).as_ref().unwrap_or(&none);
+        let arms = overrides
+            .iter()
+            .filter_map(|&(ident, ref attrs)| {
+                let value = get(attrs).as_ref()?;
+                Some(quote!(Self::#ident { .. } => #value,))
+            })
+            .collect::<Vec<_>>();
+        if arms.is_empty() {
+            return quote!(#default);
+        }
+        quote! {
+            #[allow(unreachable_patterns)]
+            match *self {
+                #(#arms)*
+                _ => #default,
+            }
+        }
+    };
+    let code = method(|attrs| &attrs.code);
+    let severity = method(|attrs| &attrs.severity);
+    let help = method(|attrs| &attrs.help);
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::throw::ThrowDiagnostic for #name #ty_generics #where_clause {
+            fn code(&self) -> ::core::option::Option<&'static str> {
+                #code
+            }
+
+            fn severity(&self) -> ::core::option::Option<::throw::Severity> {
+                #severity
+            }
+
+            fn help(&self) -> ::core::option::Option<&'static str> {
+                #help
+            }
+        }
+    })
+}